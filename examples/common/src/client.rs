@@ -0,0 +1,27 @@
+// /-- THIS FILE WAS AUTOMATICALLY GENERATED BY OLYMPUS --\
+#![allow(unused_qualifications)]
+#![allow(non_snake_case)]
+use super::models;
+
+#[derive(Clone)]
+pub struct ClientRpc<Ctx> {
+	pub inner: ::olympus_client::OlympusClient<Ctx>,
+}
+
+impl<Ctx: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static> ClientRpc<Ctx> {
+	pub fn new(inner: ::olympus_client::OlympusClient<Ctx>) -> Self {
+		Self { inner }
+	}
+	/// Calls `GetServerVersion` and waits for its response.
+	pub async fn get_server_version(&self) -> ::olympus_net_common::Result<i8> {
+		self.inner.call("GetServerVersion", &()).await
+	}
+	/// Calls `GetFile` and waits for its response.
+	pub async fn get_file(&self, params: models::GetFileParams) -> ::olympus_net_common::Result<models::File> {
+		self.inner.call("GetFile", &params).await
+	}
+	/// Sends `DeleteFile` without waiting, it doesn't return anything.
+	pub async fn delete_file(&self, params: models::DeleteFileParams) -> ::olympus_net_common::Result<()> {
+		self.inner.send("DeleteFile", &params)
+	}
+}
@@ -1,3 +1,4 @@
 // /-- THIS FILE WAS AUTOMATICALLY GENERATED BY OLYMPUS --\
+pub mod client;
 pub mod models;
 pub mod server;
@@ -1,7 +1,6 @@
 // /-- THIS FILE WAS AUTOMATICALLY GENERATED BY OLYMPUS --\
 #![allow(unused_qualifications)]
 #![allow(non_snake_case)]
-
 #[derive(Debug, Clone, Copy)]
 #[repr(u16)]
 pub enum Action {
@@ -32,11 +31,73 @@ impl ::olympus_net_common::ProcedureOutput for Action {
 	}
 }
 
+impl Action {
+	/// Every variant in the order they are declared in.
+	pub const ALL: [Self; 3] = [Self::Delete, Self::SecureDelete, Self::Encrypt];
+	/// The name of the variant in the schema.
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Delete => "Delete",
+			Self::SecureDelete => "SecureDelete",
+			Self::Encrypt => "Encrypt",
+		}
+	}
+	/// The variant with the name `name` in the schema.
+	#[must_use]
+	pub fn from_name(name: &str) -> ::core::option::Option<Self> {
+		match name {
+			"Delete" => ::core::option::Option::Some(Self::Delete),
+			"SecureDelete" => ::core::option::Option::Some(Self::SecureDelete),
+			"Encrypt" => ::core::option::Option::Some(Self::Encrypt),
+			_ => ::core::option::Option::None,
+		}
+	}
+}
+
+impl ::std::convert::TryFrom<u16> for Action {
+	type Error = ::olympus_net_common::UnknownVariant;
+	fn try_from(tag: u16) -> ::core::result::Result<Self, Self::Error> {
+		match tag {
+			1 => ::core::result::Result::Ok(Self::Delete),
+			2 => ::core::result::Result::Ok(Self::SecureDelete),
+			3 => ::core::result::Result::Ok(Self::Encrypt),
+			_ => ::core::result::Result::Err(::olympus_net_common::UnknownVariant::Tag {
+				enum_name: "Action",
+				tag,
+			}),
+		}
+	}
+}
+
+impl ::std::convert::From<Action> for u16 {
+	fn from(value: Action) -> Self {
+		value as u16
+	}
+}
+
+impl ::std::fmt::Display for Action {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl ::std::str::FromStr for Action {
+	type Err = ::olympus_net_common::UnknownVariant;
+	fn from_str(name: &str) -> ::core::result::Result<Self, Self::Err> {
+		Self::from_name(name).ok_or_else(|| ::olympus_net_common::UnknownVariant::Name {
+			enum_name: "Action",
+			name: ::std::string::ToString::to_string(name),
+		})
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct File {
-	pub path: String,
+	pub path: ::std::string::String,
 	pub size: ::olympus_net_common::Variable<u64>,
-	pub content: Vec<u8>,
+	pub content: ::std::vec::Vec<u8>,
+	pub owner: ::core::option::Option<User>,
 }
 
 impl ::olympus_net_common::ProcedureInput for File {
@@ -45,6 +106,7 @@ impl ::olympus_net_common::ProcedureInput for File {
 			path: ::olympus_net_common::ProcedureInput::deserialize(input)?,
 			size: ::olympus_net_common::ProcedureInput::deserialize(input)?,
 			content: ::olympus_net_common::ProcedureInput::deserialize(input)?,
+			owner: ::olympus_net_common::ProcedureInput::deserialize(input)?,
 		})
 	}
 }
@@ -55,14 +117,36 @@ impl ::olympus_net_common::ProcedureOutput for File {
 		out.extend(self.path.serialize()?);
 		out.extend(self.size.serialize()?);
 		out.extend(self.content.serialize()?);
+		out.extend(self.owner.serialize()?);
+		Ok(out)
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct User {
+	pub name: ::std::string::String,
+}
+
+impl ::olympus_net_common::ProcedureInput for User {
+	fn deserialize(input: &mut ::olympus_net_common::bytes::BytesMut) -> ::olympus_net_common::Result<Self> {
+		Ok(Self {
+			name: ::olympus_net_common::ProcedureInput::deserialize(input)?,
+		})
+	}
+}
+
+impl ::olympus_net_common::ProcedureOutput for User {
+	fn serialize(&self) -> ::olympus_net_common::Result<::olympus_net_common::bytes::BytesMut> {
+		let mut out = ::olympus_net_common::bytes::BytesMut::new();
+		out.extend(self.name.serialize()?);
 		Ok(out)
 	}
 }
 
 #[derive(Debug, Clone)]
 pub struct GetFileParams {
-	pub path: String,
-	pub after_action: Option<Action>,
+	pub path: ::std::string::String,
+	pub after_action: ::core::option::Option<Action>,
 }
 
 impl ::olympus_net_common::ProcedureInput for GetFileParams {
@@ -85,7 +169,7 @@ impl ::olympus_net_common::ProcedureOutput for GetFileParams {
 
 #[derive(Debug, Clone)]
 pub struct DeleteFileParams {
-	pub path: String,
+	pub path: ::std::string::String,
 }
 
 impl ::olympus_net_common::ProcedureInput for DeleteFileParams {
@@ -1,14 +1,19 @@
 // /-- THIS FILE WAS AUTOMATICALLY GENERATED BY OLYMPUS --\
 #![allow(unused_qualifications)]
 #![allow(non_snake_case)]
-use crate::models::*;
+use super::models;
+
 #[::olympus_net_common::async_trait]
-pub trait ServerRpc<Ctx: Clone + Send + Sync + 'static> {
+pub trait ServerRpc<Ctx: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static> {
 	async fn get_server_version(context: Ctx) -> ::olympus_net_common::Result<i8>;
-	async fn get_file(context: Ctx, params: GetFileParams) -> ::olympus_net_common::Result<File>;
-	async fn delete_file(context: Ctx, params: DeleteFileParams) -> ::olympus_net_common::Result<()>;
+	async fn get_file(context: Ctx, params: models::GetFileParams) -> ::olympus_net_common::Result<models::File>;
+	async fn delete_file(context: Ctx, params: models::DeleteFileParams) -> ::olympus_net_common::Result<()>;
 }
-pub async fn register_procedures<C: Clone + Send + Sync + 'static, I: ServerRpc<C> + 'static>(
+
+pub async fn register_procedures<
+	C: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static,
+	I: ServerRpc<C> + 'static,
+>(
 	server: &mut ::olympus_server::OlympusServer<C>,
 	_imp: I,
 ) {
@@ -22,6 +22,7 @@ impl ServerRpc<Context> for ServerImpl {
 			path: params.path,
 			size: Variable(content.len() as u64),
 			content,
+			owner: None,
 		})
 	}
 
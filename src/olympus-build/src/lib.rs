@@ -0,0 +1,310 @@
+//! Compiles olympus schemas to Rust from a build script.
+//!
+//! ```no_run
+//! // in the main function of build.rs
+//! olympus_build::Config::new().compile(&["schemas/files.ol"]).unwrap();
+//! ```
+//!
+//! Every schema is written to `OUT_DIR` as `<file stem>.rs`, with the `models`, `server` and `client` modules
+//! `olympusc compile --rs-crate` generates:
+//!
+//! ```ignore
+//! mod files {
+//!     include!(concat!(env!("OUT_DIR"), "/files.rs"));
+//! }
+//! ```
+
+use std::{
+	collections::HashMap,
+	io::Write,
+	path::{Path, PathBuf},
+};
+
+use eyre::eyre;
+use olympus_compiler::{
+	diagnostics::write_olympus_error, generate_rust_modules, generator::rust::pretty, loader::RealFileSystem,
+	verify_schema_from, GenerateOptions,
+};
+use olympus_spanned::OlympusError;
+
+pub use olympus_compiler::{CompileLanguage, NamingConvention, NamingConventionConfig};
+pub use olympus_verifier::{Lint, LintConfig, LintLevel};
+
+/// Configures how schemas are compiled, start with [`Config::new`].
+#[derive(Debug, Clone)]
+pub struct Config {
+	out_dir: Option<PathBuf>,
+	include_paths: Vec<PathBuf>,
+	options: GenerateOptions,
+	lint_config: LintConfig,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Config {
+	/// Rust naming conventions, the default lint levels and `OUT_DIR` as the output directory.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			out_dir: None,
+			include_paths: Vec::new(),
+			options: GenerateOptions::new(CompileLanguage::Rust),
+			lint_config: LintConfig::default(),
+		}
+	}
+
+	#[must_use]
+	pub fn naming(mut self, naming_convention_config: NamingConventionConfig) -> Self {
+		self.options.naming_convention_config = naming_convention_config;
+		self
+	}
+
+	#[must_use]
+	pub fn lints(mut self, lint_config: LintConfig) -> Self {
+		self.lint_config = lint_config;
+		self
+	}
+
+	/// Derives `derive` for every enum and struct on top of the ones the generated code needs.
+	#[must_use]
+	pub fn derive(mut self, derive: impl Into<String>) -> Self {
+		self.options.rs_derives.push(derive.into());
+		self
+	}
+
+	/// Derives `derive` for the enum or struct named `ty` in the schema on top of the ones derived for every type.
+	#[must_use]
+	pub fn type_derive(mut self, ty: impl Into<String>, derive: impl Into<String>) -> Self {
+		self.options
+			.rs_type_derives
+			.entry(ty.into())
+			.or_default()
+			.push(derive.into());
+		self
+	}
+
+	/// Derives `Serialize` and `Deserialize` behind a `serde` feature, which the crate including the generated code
+	/// has to declare.
+	#[must_use]
+	pub fn serde(mut self, serde: bool) -> Self {
+		self.options.rs_serde = serde;
+		self
+	}
+
+	/// Looks for imports that aren't next to the importing file in `path` as well, like `olympusc --include` does.
+	#[must_use]
+	pub fn include_path(mut self, path: impl Into<PathBuf>) -> Self {
+		self.include_paths.push(path.into());
+		self
+	}
+
+	/// Writes the generated files somewhere other than `OUT_DIR`.
+	#[must_use]
+	pub fn out_dir(mut self, out_dir: impl Into<PathBuf>) -> Self {
+		self.out_dir = Some(out_dir.into());
+		self
+	}
+
+	/// Compiles every schema and tells cargo to rerun the build script once one of them or their imports change.
+	///
+	/// Warnings are passed on to cargo, errors for every schema are returned together.
+	pub fn compile(&self, schemas: &[impl AsRef<Path>]) -> eyre::Result<()> {
+		self.compile_with(schemas, &mut std::io::stdout().lock())
+	}
+
+	/// [`Config::compile`] writing the instructions for cargo to `cargo`.
+	fn compile_with(&self, schemas: &[impl AsRef<Path>], cargo: &mut impl Write) -> eyre::Result<()> {
+		let out_dir = match &self.out_dir {
+			Some(out_dir) => out_dir.clone(),
+			None => std::env::var_os("OUT_DIR")
+				.map(PathBuf::from)
+				.ok_or_else(|| eyre!("OUT_DIR isn't set, call this from a build script or set an output directory"))?,
+		};
+
+		let mut outputs = HashMap::<PathBuf, &Path>::new();
+		let mut errors = Vec::new();
+		for schema_path in schemas {
+			let schema_path = schema_path.as_ref();
+			let Some(stem) = schema_path.file_stem() else {
+				return Err(eyre!("'{}' isn't a file", schema_path.display()));
+			};
+
+			// `with_extension` would replace everything after the last dot of a stem like `files.v2`
+			let mut file_name = stem.to_os_string();
+			file_name.push(".rs");
+			let output = out_dir.join(file_name);
+			if let Some(other) = outputs.insert(output.clone(), schema_path) {
+				return Err(eyre!(
+					"'{}' and '{}' would both be written to '{}'",
+					other.display(),
+					schema_path.display(),
+					output.display()
+				));
+			}
+
+			let verification = verify_schema_from(&RealFileSystem, schema_path, &self.include_paths, &self.lint_config);
+			for file in &verification.files {
+				writeln!(cargo, "cargo:rerun-if-changed={}", file.display())?;
+			}
+
+			for diagnostic in verification.diagnostics {
+				if diagnostic.is_error() {
+					errors.push(render(diagnostic));
+				} else {
+					for line in render(diagnostic).lines() {
+						writeln!(cargo, "cargo:warning={line}")?;
+					}
+				}
+			}
+
+			let Some(schema) = verification.schema else {
+				continue;
+			};
+			match generate_rust_modules(&schema, &self.options) {
+				Ok(src) => std::fs::write(&output, pretty(src))?,
+				Err(err) => errors.push(render(err)),
+			}
+		}
+
+		if errors.is_empty() {
+			Ok(())
+		} else {
+			Err(eyre!("Couldn't compile the olympus schemas:\n{}", errors.join("\n")))
+		}
+	}
+}
+
+fn render(err: OlympusError) -> String {
+	let mut out = Vec::new();
+	let _ = write_olympus_error(err, &mut out, false);
+	String::from_utf8_lossy(&out).trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use olympus_compiler::{
+		generate_rust_modules, generator::rust::pretty, loader::RealFileSystem, verify_schema_from, GenerateOptions,
+	};
+
+	use super::{CompileLanguage, Config, LintConfig, NamingConvention, NamingConventionConfig};
+
+	fn write(path: &Path, contents: &str) {
+		std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+		std::fs::write(path, contents).unwrap();
+	}
+
+	#[test]
+	fn writes_modules_and_tracks_imports() {
+		let dir = tempfile::tempdir().unwrap();
+		let schemas = dir.path().join("schemas");
+		let shared = dir.path().join("shared");
+		let out_dir = dir.path().join("out");
+		std::fs::create_dir(&out_dir).unwrap();
+		write(
+			&schemas.join("files.v2.ol"),
+			"import user;\nstruct File { owner->User; }\nproc GetFile() -> File;",
+		);
+		write(&shared.join("user.ol"), "struct User { name->@string; }");
+
+		let mut cargo = Vec::new();
+		Config::new()
+			.include_path(&shared)
+			.out_dir(&out_dir)
+			.compile_with(&[schemas.join("files.v2.ol")], &mut cargo)
+			.unwrap();
+
+		let cargo = String::from_utf8(cargo).unwrap();
+		assert_eq!(
+			cargo.lines().collect::<Vec<_>>(),
+			[
+				format!("cargo:rerun-if-changed={}", schemas.join("files.v2.ol").display()),
+				format!("cargo:rerun-if-changed={}", shared.join("user.ol").display()),
+			]
+		);
+
+		// the same modules `generate_rust_modules` gives for the schema, under the full stem
+		let schema = verify_schema_from(
+			&RealFileSystem,
+			&schemas.join("files.v2.ol"),
+			&[shared],
+			&LintConfig::default(),
+		)
+		.schema
+		.unwrap();
+		let expected = pretty(generate_rust_modules(&schema, &GenerateOptions::new(CompileLanguage::Rust)).unwrap());
+		assert_eq!(std::fs::read_to_string(out_dir.join("files.v2.rs")).unwrap(), expected);
+		assert!(expected.contains("pub struct User"));
+	}
+
+	#[test]
+	fn passes_warnings_on_and_returns_errors() {
+		let dir = tempfile::tempdir().unwrap();
+		write(&dir.path().join("unused.ol"), "struct Unused { x->@int8; }");
+		write(&dir.path().join("broken.ol"), "struct Broken { x->Missing; }");
+
+		let mut cargo = Vec::new();
+		let err = Config::new()
+			.out_dir(dir.path())
+			.compile_with(
+				&[dir.path().join("unused.ol"), dir.path().join("broken.ol")],
+				&mut cargo,
+			)
+			.unwrap_err();
+		assert!(err.to_string().contains("Type 'Missing' not found"));
+
+		let cargo = String::from_utf8(cargo).unwrap();
+		assert!(cargo.contains("Struct 'Unused' is never used"));
+		assert!(cargo.lines().all(|line| line.starts_with("cargo:")));
+		// the schemas that compiled are still written
+		assert!(dir.path().join("unused.rs").exists());
+		assert!(!dir.path().join("broken.rs").exists());
+	}
+
+	#[test]
+	fn derives_and_renames_under_naming_conventions() {
+		let dir = tempfile::tempdir().unwrap();
+		write(
+			&dir.path().join("files.ol"),
+			"enum Action { Delete->1; }\nstruct File { owner_id->@uint32; self->@string; action->Action; }",
+		);
+
+		let mut naming = NamingConventionConfig::defaults_for(CompileLanguage::Rust);
+		naming.struct_fields = NamingConvention::LowerCamel;
+		Config::new()
+			.naming(naming)
+			.derive("Eq")
+			.type_derive("File", "Hash")
+			.type_derive("File", "PartialEq")
+			.serde(true)
+			.out_dir(dir.path())
+			.compile_with(&[dir.path().join("files.ol")], &mut Vec::new())
+			.unwrap();
+
+		// only the field whose Rust name had to be escaped is renamed, the others are serialized as they are named
+		let src = std::fs::read_to_string(dir.path().join("files.rs")).unwrap();
+		assert!(src.contains("#[derive(Debug, Clone, Copy, Eq)]"));
+		assert!(src.contains(
+			r#"    #[derive(Debug, Clone, Eq, Hash, PartialEq)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(
+            ::olympus_net_common::serde::Serialize,
+            ::olympus_net_common::serde::Deserialize
+        ),
+        serde(crate = "::olympus_net_common::serde")
+    )]
+    pub struct File {
+        pub ownerId: u32,
+        #[cfg_attr(feature = "serde", serde(rename = "self"))]
+        pub self_: ::std::string::String,
+        pub action: Action,
+    }"#
+		));
+	}
+}
@@ -1,8 +1,14 @@
-use std::{collections::HashMap, marker::PhantomData, mem::size_of, net::SocketAddr, sync::Arc};
+use std::{
+	collections::{HashMap, VecDeque},
+	marker::PhantomData,
+	mem::size_of,
+	net::SocketAddr,
+	sync::Arc,
+};
 
 use async_trait::async_trait;
 use futures::{Future, SinkExt, StreamExt};
-use olympus_net_common::{fnv, OlympusPacketCodec, ProcedureInput, ProcedureOutput, Result};
+use olympus_net_common::{error, fnv, OlympusPacketCodec, ProcedureInput, ProcedureOutput, Result};
 use tokio::{
 	io,
 	net::{
@@ -11,7 +17,7 @@ use tokio::{
 	},
 	sync::{
 		mpsc::{UnboundedReceiver, UnboundedSender},
-		Mutex,
+		oneshot, Mutex,
 	},
 };
 use tokio_util::{
@@ -48,11 +54,14 @@ where
 
 type ArcMut<T> = Arc<Mutex<T>>;
 type HandlersMap<Ctx> = HashMap<u64, (Box<dyn ResponseHandler<Ctx>>, &'static str)>;
+/// Calls waiting for a response, per procedure in the order they were sent.
+type PendingMap = HashMap<u64, VecDeque<oneshot::Sender<BytesMut>>>;
 
 #[derive(Clone)]
 pub struct OlympusClient<Ctx> {
 	pub context: Ctx,
 	response_handlers: ArcMut<HandlersMap<Ctx>>,
+	pending_calls: ArcMut<PendingMap>,
 	sender: Arc<Option<UnboundedSender<(&'static str, BytesMut)>>>,
 }
 
@@ -62,6 +71,7 @@ impl<Ctx: Clone + Send + Sync + 'static> OlympusClient<Ctx> {
 		Self {
 			context,
 			response_handlers: Arc::default(),
+			pending_calls: Arc::default(),
 			sender: Arc::new(None),
 		}
 	}
@@ -79,7 +89,7 @@ impl<Ctx: Clone + Send + Sync + 'static> OlympusClient<Ctx> {
 	}
 
 	pub fn send<I: ProcedureOutput + Send + Sync + 'static>(
-		&mut self,
+		&self,
 		procedure_name: &'static str,
 		input: &I,
 	) -> Result<()> {
@@ -90,6 +100,33 @@ impl<Ctx: Clone + Send + Sync + 'static> OlympusClient<Ctx> {
 		Ok(())
 	}
 
+	/// Calls a procedure and waits for its response.
+	///
+	/// The server answers the requests of a connection in order, so responses are matched to calls in the order they
+	/// were sent. Responses to calls aren't passed to handlers registered with [`OlympusClient::on_response`].
+	///
+	/// The server doesn't send anything for procedures that return `()`, use [`OlympusClient::send`] for those. Every
+	/// other procedure is answered, even if its response is empty.
+	pub async fn call<I, O>(&self, procedure_name: &'static str, input: &I) -> Result<O>
+	where
+		I: ProcedureOutput + Send + Sync + 'static,
+		O: ProcedureInput,
+	{
+		let (tx, rx) = oneshot::channel();
+		{
+			// held until the request is queued so concurrent calls can't be sent in a different order than they were
+			// registered in
+			let mut pending_calls = self.pending_calls.lock().await;
+			pending_calls.entry(fnv(procedure_name)).or_default().push_back(tx);
+			self.send(procedure_name, input)?;
+		}
+
+		let mut response = rx
+			.await
+			.map_err(|_| error!("Connection closed before '{procedure_name}' responded"))?;
+		O::deserialize(&mut response)
+	}
+
 	pub async fn connect(&mut self, addr: SocketAddr) -> io::Result<()> {
 		let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
 		self.sender = Arc::new(Some(tx.clone()));
@@ -102,6 +139,7 @@ impl<Ctx: Clone + Send + Sync + 'static> OlympusClient<Ctx> {
 		tokio::spawn(Self::handle_incoming(
 			self.clone(),
 			self.response_handlers.clone(),
+			self.pending_calls.clone(),
 			framed_read,
 		));
 
@@ -127,12 +165,36 @@ impl<Ctx: Clone + Send + Sync + 'static> OlympusClient<Ctx> {
 	async fn handle_incoming(
 		client: OlympusClient<Ctx>,
 		handlers: ArcMut<HandlersMap<Ctx>>,
+		pending_calls: ArcMut<PendingMap>,
+		read: FramedRead<OwnedReadHalf, OlympusPacketCodec>,
+	) -> Result<()> {
+		let result = Self::read_incoming(client, handlers, pending_calls.clone(), read).await;
+
+		// dropping the senders wakes every call that is still waiting with an error
+		pending_calls.lock().await.clear();
+		result
+	}
+
+	async fn read_incoming(
+		client: OlympusClient<Ctx>,
+		handlers: ArcMut<HandlersMap<Ctx>>,
+		pending_calls: ArcMut<PendingMap>,
 		mut read: FramedRead<OwnedReadHalf, OlympusPacketCodec>,
 	) -> Result<()> {
 		while let Some(frame) = read.next().await {
 			let mut frame = frame?;
 			let procedure_name_hash = frame.get_u64();
 
+			let pending_call = pending_calls
+				.lock()
+				.await
+				.get_mut(&procedure_name_hash)
+				.and_then(VecDeque::pop_front);
+			if let Some(pending_call) = pending_call {
+				let _ = pending_call.send(frame);
+				continue;
+			}
+
 			if Self::run_handler(client.clone(), handlers.clone(), procedure_name_hash, frame)
 				.await?
 				.is_none()
@@ -1,11 +1,26 @@
 pub mod compile;
+pub mod explain;
 pub mod verify;
+pub mod watch;
 
-use std::path::{Path, PathBuf};
+use std::{
+	fmt::{Display, Formatter},
+	path::{Path, PathBuf},
+};
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args as ClapArgs, Parser, Subcommand, ValueEnum};
 use eyre::eyre;
-use heck::{AsKebabCase, AsLowerCamelCase, AsPascalCase, AsShoutyKebabCase, AsShoutySnakeCase, AsSnakeCase};
+use olympus_compiler::{
+	diagnostics::{DiagnosticEmitter, MessageFormat},
+	generator::{CompileLanguage, GenerateOptions, RustCrateSide},
+	loader::RealFileSystem,
+	naming::{NamingConvention, NamingConventionConfig, NamingConventionOverrides},
+	project::{
+		find_schemas, parse_lint_selector, LintSelector, OlympusSource, Output, Project, RustConfig, PROJECT_FILE_NAME,
+	},
+	verify_schema_from,
+};
+use olympus_verifier::{LintConfig, LintLevel, ResolvedSchema};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -14,117 +29,519 @@ pub struct Args {
 	pub command: Command,
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
-pub enum CompileLanguage {
-	Rust,
+/// Returned once the diagnostics explaining why a command failed were printed, so nothing else has to be.
+#[derive(Debug)]
+pub struct Failed;
+
+impl Display for Failed {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str("Failed, see the diagnostics above.")
+	}
+}
+
+impl std::error::Error for Failed {}
+
+fn parse_plugin_opt(opt: &str) -> Result<(String, String), String> {
+	opt.split_once('=')
+		.map(|(key, value)| (key.to_string(), value.to_string()))
+		.ok_or_else(|| format!("expected KEY=VALUE, found '{opt}'"))
+}
+
+#[derive(Debug, Clone, ClapArgs)]
+pub struct LintArgs {
+	/// Don't report this lint. Can be repeated.
+	#[arg(short = 'A', long = "allow", value_name = "LINT", value_parser = parse_lint_selector)]
+	pub allow: Vec<LintSelector>,
+	/// Report this lint as a warning. Can be repeated.
+	#[arg(short = 'W', long = "warn", value_name = "LINT", value_parser = parse_lint_selector)]
+	pub warn: Vec<LintSelector>,
+	/// Report this lint as an error. Can be repeated.
+	#[arg(short = 'D', long = "deny", value_name = "LINT", value_parser = parse_lint_selector)]
+	pub deny: Vec<LintSelector>,
+}
+
+impl LintArgs {
+	/// Applies the flags from least to most severe, so `-D` wins over `-W` and `-A` for the same lint.
+	pub fn apply(&self, config: &mut LintConfig) {
+		for (selectors, level) in [
+			(&self.allow, LintLevel::Allow),
+			(&self.warn, LintLevel::Warn),
+			(&self.deny, LintLevel::Deny),
+		] {
+			for selector in selectors {
+				match selector {
+					LintSelector::All => config.set_all(level),
+					LintSelector::Lint(lint) => config.set(*lint, level),
+				}
+			}
+		}
+	}
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
-#[clap(rename_all = "snake_case")]
-pub enum NamingConvention {
-	Pascal,
-	LowerCamel,
-	Snake,
-	ShoutySnake,
-	Kebab,
-	ShoutyKebab,
+#[derive(Debug, Clone, ClapArgs)]
+pub struct CompileArgs {
+	/// A schema file, a directory to compile every schema in or a glob like `schemas/**/*.ol`
+	#[arg(requires = "output")]
+	pub input: Option<PathBuf>,
+	/// The file or directory to write the output to. Several schemas, several languages and plugins always write
+	/// to a directory, which mirrors the schemas' directories. Go, C, C# and Kotlin write every schema into a single
+	/// file there instead.
+	pub output: Option<PathBuf>,
+	/// One or more languages, each gets a subdirectory of the output if there are several.
+	#[arg(value_name = "LANGUAGE", conflicts_with = "plugin")]
+	pub languages: Vec<CompileLanguage>,
+	/// Generate code with the `olympus-gen-<PLUGIN>` executable from your PATH instead of a built-in language.
+	#[arg(long, requires = "input")]
+	pub plugin: Option<String>,
+	/// Passes a parameter to the plugin. Can be repeated.
+	#[arg(long, value_name = "KEY=VALUE", value_parser = parse_plugin_opt, requires = "plugin")]
+	pub plugin_opt: Vec<(String, String)>,
+	/// Overwrites files/directories if they're present instead of exiting.
+	#[arg(short, long)]
+	pub overwrite: bool,
+	/// Also look for imports in this directory. Can be repeated.
+	#[arg(short = 'I', long = "include", value_name = "DIR")]
+	pub include: Vec<PathBuf>,
+	/// Overrides all other naming convention configuration to be this value.
+	#[arg(long)]
+	pub naming_convention: Option<NamingConvention>,
+	/// What naming convention should be used for enums/structs [default: pascal]
+	#[arg(long)]
+	pub type_naming_convention: Option<NamingConvention>,
+	/// What naming convention should be used for enum variants [default: the language's convention]
+	#[arg(long)]
+	pub enum_variant_naming_convention: Option<NamingConvention>,
+	/// What naming convention should be used for struct fields [default: the language's convention]
+	#[arg(long)]
+	pub struct_field_naming_convention: Option<NamingConvention>,
+	/// What naming convention should be used for procedures [default: the language's convention]
+	#[arg(long)]
+	pub proc_naming_convention: Option<NamingConvention>,
+	/// (Rust only) Generate a crate.
+	#[arg(long, requires = "input")]
+	pub rs_crate: bool,
+	/// (Rust only) The name of the crate to generate.
+	#[arg(long, requires = "input")]
+	pub rs_crate_name: Option<String>,
+	/// (Rust only) The version of the crate to generate [default: 0.0.0-OLYMPUS-GENERATED]
+	#[arg(long, requires = "rs_crate")]
+	pub rs_crate_version: Option<String>,
+	/// (Rust only) Only generate the client or the server of the crate [default: both]
+	#[arg(long, requires = "rs_crate")]
+	pub rs_crate_side: Option<RustCrateSide>,
+	/// (Rust only) The directory the runtime crates are in, relative to the generated crate [default: the crates of
+	/// the workspace the crate is generated into]
+	#[arg(long, value_name = "DIR", requires = "rs_crate", conflicts_with_all = ["rs_olympus_git", "rs_olympus_version"])]
+	pub rs_olympus_path: Option<PathBuf>,
+	/// (Rust only) A git repository to get the runtime crates from.
+	#[arg(
+		long,
+		value_name = "URL",
+		requires = "rs_crate",
+		conflicts_with = "rs_olympus_version"
+	)]
+	pub rs_olympus_git: Option<String>,
+	/// (Rust only) The branch, tag or commit of the git repository to use.
+	#[arg(long, value_name = "REV", requires = "rs_olympus_git")]
+	pub rs_olympus_rev: Option<String>,
+	/// (Rust only) The version of the runtime crates to get from crates.io.
+	#[arg(long, value_name = "VERSION", requires = "rs_crate")]
+	pub rs_olympus_version: Option<String>,
+	/// (Rust only) Also derive this trait for every enum and struct, e.g. `PartialEq`. Can be repeated.
+	#[arg(long, value_name = "DERIVE", requires = "input")]
+	pub rs_derive: Vec<String>,
+	/// (Rust only) Derive `Serialize` and `Deserialize` behind a `serde` feature.
+	#[arg(long, requires = "input")]
+	pub rs_serde: bool,
+	/// (Go only) The package of the generated file [default: the name of the directory it is written to]
+	#[arg(long, requires = "input")]
+	pub go_package: Option<String>,
+	/// (C# only) The namespace of the generated file [default: Olympus]
+	#[arg(long, requires = "input")]
+	pub cs_namespace: Option<String>,
+	/// (Kotlin only) The package of the generated file [default: olympus]
+	#[arg(long, requires = "input")]
+	pub kt_package: Option<String>,
+	#[command(flatten)]
+	pub lints: LintArgs,
+	/// How diagnostics should be printed.
+	#[arg(long, value_enum, default_value = "human")]
+	pub message_format: MessageFormat,
 }
 
 #[derive(Subcommand)]
 pub enum Command {
-	/// Verify an olympus definition
-	Verify { file: PathBuf },
-
-	/// Compile an olympus definition
-	Compile {
-		/// Must point to an Olympus definition file
-		input: PathBuf,
-		/// The file or directory to write the output to.
-		output: PathBuf,
-		language: CompileLanguage,
-		/// Overwrites files/directories if they're present instead of exiting.
-		#[arg(short, long)]
-		overwrite: bool,
-		/// Overrides all other naming convention configuration to be this value.
-		#[arg(long)]
-		naming_convention: Option<NamingConvention>,
-		/// What naming convention should be used for enums/structs
-		#[arg(long, default_value = "pascal")]
-		type_naming_convention: NamingConvention,
-		/// What naming convention should be used for enum variants
-		#[arg(long, default_value = "pascal")]
-		enum_variant_naming_convention: NamingConvention,
-		/// What naming convention should be used for struct fields
-		#[arg(long, default_value = "snake")]
-		struct_field_naming_convention: NamingConvention,
-		/// What naming convention should be used for procedures
-		#[arg(long, default_value = "snake")]
-		proc_naming_convention: NamingConvention,
-		/// (Rust only) Generate a crate.
-		#[arg(long)]
-		rs_crate: bool,
-		/// (Rust only) The name of the crate to generate.
-		#[arg(long)]
-		rs_crate_name: Option<String>,
+	/// Verify an olympus definition, or every schema of the project if none is given
+	Verify {
+		/// A schema file, a directory to verify every schema in or a glob like `schemas/**/*.ol`
+		file: Option<PathBuf>,
+		/// Also look for imports in this directory. Can be repeated.
+		#[arg(short = 'I', long = "include", value_name = "DIR")]
+		include: Vec<PathBuf>,
+		#[command(flatten)]
+		lints: LintArgs,
+		/// How diagnostics should be printed.
+		#[arg(long, value_enum, default_value = "human")]
+		message_format: MessageFormat,
+	},
+
+	/// Compile an olympus definition, or every output of the project if none is given
+	Compile(CompileArgs),
+
+	/// Compile like `compile` does and again whenever a schema or one of its imports changes
+	///
+	/// Only the schemas that are affected by a change are verified again and only the files whose contents change are
+	/// written. Existing output files are always overwritten.
+	Watch(CompileArgs),
+
+	/// Explain a diagnostic code in detail, or list every code if none is given
+	Explain {
+		/// A code like `OL0012`, or the name of a lint
+		code: Option<String>,
 	},
 }
 
-#[derive(Debug, Clone)]
-pub struct NamingConventionConfig {
-	pub types: NamingConvention,
-	pub struct_fields: NamingConvention,
-	pub enum_variants: NamingConvention,
-	pub procs: NamingConvention,
+fn ensure_exists(path: &Path) -> eyre::Result<()> {
+	if !path.try_exists()? {
+		return Err(eyre!("The provided path doesn't exist."));
+	}
+
+	Ok(())
+}
+
+pub fn ensure_is_file(path: &Path) -> eyre::Result<()> {
+	ensure_exists(path)?;
+
+	if !path.is_file() {
+		return Err(eyre!("The provided path doesn't lead to a file."));
+	}
+
+	Ok(())
+}
+
+/// Loads, parses, verifies and lints a schema file and its imports, emitting any diagnostics.
+///
+/// Returns `None` if there was an error or a lint that is set to deny fired.
+#[must_use]
+pub fn verify_file(
+	path: &Path,
+	include_paths: &[PathBuf],
+	lint_config: &LintConfig,
+	emitter: &mut DiagnosticEmitter,
+) -> Option<ResolvedSchema> {
+	let verification = verify_schema_from(&RealFileSystem, path, include_paths, lint_config);
+	for diagnostic in verification.diagnostics {
+		emitter.emit(diagnostic);
+	}
+
+	verification.schema
+}
+
+/// Go packages are usually named after their directory, unless its name isn't a valid package name like `go` or
+/// `my-out` is.
+#[must_use]
+pub fn default_go_package(output: &Path) -> String {
+	let package = output
+		.canonicalize()
+		.unwrap_or_else(|_| output.to_path_buf())
+		.parent()
+		.and_then(Path::file_name)
+		.map(|name| name.to_string_lossy().to_lowercase())
+		.unwrap_or_default();
+
+	let is_identifier = package.starts_with(|char: char| char.is_ascii_alphabetic())
+		&& package.chars().all(|char| char.is_ascii_alphanumeric() || char == '_');
+	if is_identifier
+		&& !CompileLanguage::Go
+			.code_generator()
+			.reserved_words()
+			.contains(&package.as_str())
+	{
+		package
+	} else {
+		"olympus".to_string()
+	}
 }
 
-impl NamingConventionConfig {
-	fn apply(conv: NamingConvention, input: &str) -> String {
-		match conv {
-			NamingConvention::Pascal => AsPascalCase(input).to_string(),
-			NamingConvention::LowerCamel => AsLowerCamelCase(input).to_string(),
-			NamingConvention::Snake => AsSnakeCase(input).to_string(),
-			NamingConvention::ShoutySnake => AsShoutySnakeCase(input).to_string(),
-			NamingConvention::Kebab => AsKebabCase(input).to_string(),
-			NamingConvention::ShoutyKebab => AsShoutyKebabCase(input).to_string(),
+/// Runs the command `args` ask for, failing with [`Failed`] once the diagnostics explaining why were printed.
+pub fn run(args: Args) -> eyre::Result<()> {
+	// flags override whatever the project configures, a schema that is given explicitly is verified without it
+	match args.command {
+		Command::Verify {
+			file,
+			include,
+			lints,
+			message_format,
+		} => {
+			let (schemas, project) = if let Some(file) = file {
+				(find_schemas(&file)?, None)
+			} else {
+				let project = Project::discover(Path::new("."))?.ok_or_else(no_project_error)?;
+				(project.schemas()?, Some(project))
+			};
+			let files = schemas.into_iter().map(|schema| schema.path).collect::<Vec<_>>();
+			verify::run(
+				&files,
+				&include_paths(project.as_ref(), include),
+				&lint_config(project.as_ref(), &lints)?,
+				message_format,
+			)?;
 		}
+		Command::Explain { code } => explain::run(code.as_deref())?,
+		Command::Compile(args) => compile(&args, Project::discover(Path::new("."))?)?,
+		Command::Watch(args) => watch(&args, Project::discover(Path::new("."))?)?,
 	}
 
-	pub fn apply_types(&self, input: &str) -> String {
-		Self::apply(self.types, input)
+	Ok(())
+}
+
+fn compile(args: &CompileArgs, project: Option<Project>) -> eyre::Result<()> {
+	let lint_config = lint_config(project.as_ref(), &args.lints)?;
+	let include = include_paths(project.as_ref(), args.include.clone());
+	let overrides = naming_overrides(args);
+
+	// clap requires an output whenever there is an input
+	let (Some(input), Some(output)) = (&args.input, &args.output) else {
+		let project = project.ok_or_else(no_project_error)?;
+		return compile::run_project(
+			&project,
+			&overrides,
+			&include,
+			args.overwrite,
+			&lint_config,
+			args.message_format,
+		);
+	};
+
+	// several schemas or languages are compiled like a project with an output per language
+	if !input.is_file() || args.languages.len() > 1 {
+		let project = args_project(args, project.as_ref())?;
+		return compile::run_project(
+			&project,
+			&overrides,
+			&include,
+			args.overwrite,
+			&lint_config,
+			args.message_format,
+		);
 	}
 
-	pub fn apply_enum_variants(&self, input: &str) -> String {
-		Self::apply(self.enum_variants, input)
+	let rs_crate_name = rs_crate_name(args)?;
+	let rust = rust_config(args, project.as_ref());
+	let overrides = match &project {
+		Some(project) => overrides.or(&project.naming),
+		None => overrides,
+	};
+
+	if let Some(plugin) = &args.plugin {
+		return compile::run_plugin(
+			input,
+			output,
+			plugin,
+			&args.plugin_opt.iter().cloned().collect(),
+			&overrides,
+			&include,
+			args.overwrite,
+			&lint_config,
+			args.message_format,
+		);
 	}
 
-	pub fn apply_struct_fields(&self, input: &str) -> String {
-		Self::apply(self.struct_fields, input)
+	let language = args.languages[0];
+	let options = GenerateOptions {
+		language,
+		naming_convention_config: overrides.resolve(&NamingConventionConfig::defaults_for(language)),
+		file_name: None,
+		rs_crate_name,
+		rs_crate_version: args.rs_crate_version.clone(),
+		rs_crate_side: args.rs_crate_side.unwrap_or_default(),
+		rs_dependencies: olympus_source(args).dependencies()?,
+		rs_derives: rust.derives,
+		rs_type_derives: rust.type_derives,
+		rs_serde: rust.serde,
+		go_package: Some(args.go_package.clone().unwrap_or_else(|| default_go_package(output))),
+		cs_namespace: args.cs_namespace.clone(),
+		kt_package: args.kt_package.clone(),
+	};
+
+	compile::run(
+		input,
+		output,
+		options,
+		&include,
+		args.overwrite,
+		&lint_config,
+		args.message_format,
+	)
+}
+
+fn watch(args: &CompileArgs, project: Option<Project>) -> eyre::Result<()> {
+	let lint_config = lint_config(project.as_ref(), &args.lints)?;
+	let include = include_paths(project.as_ref(), args.include.clone());
+	let overrides = naming_overrides(args);
+
+	let project = match &args.input {
+		Some(_) => args_project(args, project.as_ref())?,
+		None => project.ok_or_else(no_project_error)?,
+	};
+
+	watch::run(&project, &overrides, &include, &lint_config, args.message_format)
+}
+
+/// A project with an output per language or the plugin for the schemas `args` compiles, with the naming conventions
+/// and derives of the project the current directory belongs to.
+fn args_project(args: &CompileArgs, project: Option<&Project>) -> eyre::Result<Project> {
+	let (Some(input), Some(output)) = (&args.input, &args.output) else {
+		unreachable!("only called with an input, which requires an output");
+	};
+	let rs_crate_name = rs_crate_name(args)?;
+	// a single file is written to the output like `compile` does
+	let single_file = input.is_file() && args.languages.len() == 1;
+
+	let outputs = match &args.plugin {
+		Some(plugin) => vec![Output {
+			plugin: Some(plugin.clone()),
+			path: output.clone(),
+			options: args.plugin_opt.iter().cloned().collect(),
+			..Output::default()
+		}],
+		None => args
+			.languages
+			.iter()
+			.map(|&language| {
+				let crate_name = rs_crate_name
+					.clone()
+					.filter(|_| matches!(language, CompileLanguage::Rust));
+				let (path, file_name) = if args.languages.len() > 1 {
+					(output.join(language_dir(language)), None)
+				} else if single_file && crate_name.is_none() {
+					(
+						output.parent().map(Path::to_path_buf).unwrap_or_default(),
+						output.file_name().map(|name| name.to_string_lossy().into_owned()),
+					)
+				} else {
+					(output.clone(), None)
+				};
+
+				let is_crate = crate_name.is_some();
+				Output {
+					language: Some(language),
+					path,
+					file_name,
+					crate_name,
+					crate_version: args.rs_crate_version.clone().filter(|_| is_crate),
+					crate_side: args.rs_crate_side.filter(|_| is_crate),
+					olympus: Some(olympus_source(args)).filter(|_| is_crate),
+					go_package: args.go_package.clone(),
+					cs_namespace: args.cs_namespace.clone(),
+					kt_package: args.kt_package.clone(),
+					..Output::default()
+				}
+			})
+			.collect(),
+	};
+
+	let mut args_project = Project::default();
+	if let Some(project) = project {
+		args_project.naming = project.naming.clone();
 	}
+	args_project.rust = rust_config(args, project);
+	args_project.schemas = vec![input.clone()];
+	args_project.outputs = outputs;
+	args_project.validate()?;
+	Ok(args_project)
+}
 
-	pub fn apply_procs(&self, input: &str) -> String {
-		Self::apply(self.procs, input)
+/// The project's Rust configuration with the flags applied on top.
+fn rust_config(args: &CompileArgs, project: Option<&Project>) -> RustConfig {
+	let mut config = project.map(|project| project.rust.clone()).unwrap_or_default();
+	for derive in &args.rs_derive {
+		if !config.derives.contains(derive) {
+			config.derives.push(derive.clone());
+		}
 	}
+	config.serde |= args.rs_serde;
+	config
 }
 
-pub fn ensure_is_file(path: &Path) -> eyre::Result<()> {
-	if !path.try_exists()? {
-		return Err(eyre!("The provided path doesn't exist."));
+fn olympus_source(args: &CompileArgs) -> OlympusSource {
+	OlympusSource {
+		path: args.rs_olympus_path.clone(),
+		git: args.rs_olympus_git.clone(),
+		rev: args.rs_olympus_rev.clone(),
+		version: args.rs_olympus_version.clone(),
 	}
+}
 
-	if !path.is_file() {
-		return Err(eyre!("The provided path doesn't lead to a file."));
+fn rs_crate_name(args: &CompileArgs) -> eyre::Result<Option<String>> {
+	if args.languages.is_empty() && args.plugin.is_none() {
+		return Err(eyre!("Must specify a language or a plugin. (TIP: --plugin=<name>)"));
 	}
 
-	Ok(())
+	match (args.rs_crate, &args.rs_crate_name) {
+		(true, None) => Err(eyre!("Must specify crate name. (TIP: --rs-crate-name=<name>)")),
+		(true, Some(name)) => Ok(Some(name.clone())),
+		(false, _) => Ok(None),
+	}
 }
 
-pub fn get_filename(file: &Path) -> eyre::Result<String> {
-	let Some(file_name) = file
-		.file_name()
-		.ok_or(eyre!("unreachable because file name cant end in '..'"))?
-		.to_str()
-	else {
-		return Err(eyre!("File name contains invalid UTF-8"));
+fn naming_overrides(args: &CompileArgs) -> NamingConventionOverrides {
+	NamingConventionOverrides {
+		types: args.naming_convention.or(args.type_naming_convention),
+		enum_variants: args.naming_convention.or(args.enum_variant_naming_convention),
+		struct_fields: args.naming_convention.or(args.struct_field_naming_convention),
+		procs: args.naming_convention.or(args.proc_naming_convention),
+	}
+}
+
+/// The project's lint levels with the flags applied on top.
+fn lint_config(project: Option<&Project>, lints: &LintArgs) -> eyre::Result<LintConfig> {
+	let mut config = match project {
+		Some(project) => project.lint_config()?,
+		None => LintConfig::default(),
 	};
-	Ok(file_name.to_string())
+	lints.apply(&mut config);
+	Ok(config)
+}
+
+fn include_paths(project: Option<&Project>, mut include: Vec<PathBuf>) -> Vec<PathBuf> {
+	if let Some(project) = project {
+		include.extend(project.include_paths());
+	}
+	include
+}
+
+fn no_project_error() -> eyre::Report {
+	eyre!("No input given and there is no {PROJECT_FILE_NAME} in the current directory or any of its parents.")
+}
+
+/// The subdirectory of the output a language is written to if several are compiled at once.
+fn language_dir(language: CompileLanguage) -> String {
+	language
+		.to_possible_value()
+		.expect("no language is skipped")
+		.get_name()
+		.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use super::default_go_package;
+
+	#[test]
+	fn go_package_is_named_after_the_output_directory() {
+		assert_eq!(default_go_package(Path::new("out/Models/models.go")), "models");
+		assert_eq!(default_go_package(Path::new("out/gen_v2/models.go")), "gen_v2");
+	}
+
+	#[test]
+	fn go_package_falls_back_for_invalid_names() {
+		assert_eq!(default_go_package(Path::new("out/go/models.go")), "olympus");
+		assert_eq!(default_go_package(Path::new("out/string/models.go")), "olympus");
+		assert_eq!(default_go_package(Path::new("my-out/models.go")), "olympus");
+		assert_eq!(default_go_package(Path::new("1gen/models.go")), "olympus");
+		assert_eq!(default_go_package(Path::new("models.go")), "olympus");
+	}
 }
@@ -1,68 +1,423 @@
-use std::path::Path;
-use std::{fs::OpenOptions, io::Write, path::PathBuf};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use crate::{
-	generator::{rust::RustCodeGenerator, CodeGenerator},
-	verify_src,
-};
 use eyre::eyre;
-use olympus_parser::Parser;
-
-use super::{ensure_is_file, get_filename, CompileLanguage, NamingConventionConfig};
+use olympus_compiler::{
+	diagnostics::{DiagnosticEmitter, MessageFormat},
+	generator::{generate, CompileLanguage, GenerateOptions, GeneratedFile},
+	loader::RealFileSystem,
+	naming::{NamingConventionConfig, NamingConventionOverrides},
+	plugin,
+	project::{Output, Project, SchemaInput},
+	verify_schemas_from, Verification,
+};
+use olympus_spanned::OlympusError;
+use olympus_verifier::{LintConfig, ResolvedSchema};
 
-const GENERATED_COMMENT: &str = "/-- THIS FILE WAS AUTOMATICALLY GENERATED BY OLYMPUS --\\";
+use super::{default_go_package, ensure_is_file, verify_file, Failed};
 
 pub fn run(
-	input: PathBuf,
-	output: PathBuf,
-	language: CompileLanguage,
+	input: &Path,
+	output: &Path,
+	mut options: GenerateOptions,
+	include_paths: &[PathBuf],
 	overwrite: bool,
-	rs_crate: bool,
-	rs_crate_name: &Option<String>,
-	naming_convention_config: &NamingConventionConfig,
+	lint_config: &LintConfig,
+	message_format: MessageFormat,
 ) -> eyre::Result<()> {
-	ensure_is_file(&input)?;
+	ensure_is_file(input)?;
+
+	let crate_name = options.rs_crate_name.clone();
+	if crate_name.is_none() {
+		options.file_name = output.file_name().map(|name| name.to_string_lossy().into_owned());
+	}
+
+	let mut emitter = DiagnosticEmitter::new(message_format);
+	let files = verify_file(input, include_paths, lint_config, &mut emitter).and_then(|schema| {
+		match generate(&schema, &options) {
+			Ok(files) => Some(files),
+			Err(err) => {
+				emitter.emit(err);
+				None
+			}
+		}
+	});
+	let human = emitter.is_human();
+	emitter.finish();
 
-	let filename = get_filename(&input)?;
-	let src = std::fs::read_to_string(input)?;
-	let Some(parser) = verify_src(&src, &filename) else {
-		return Ok(());
+	let Some(files) = files else {
+		return Err(Failed.into());
 	};
 
-	match language {
-		CompileLanguage::Rust => {
-			gen_rust(
-				&parser,
-				output,
-				overwrite,
-				rs_crate,
-				rs_crate_name,
-				naming_convention_config,
-			)?;
+	if let Some(crate_name) = crate_name {
+		prepare_crate_dir(output, &crate_name, overwrite)?;
+		write_files(output, files)?;
+	} else {
+		ensure_can_write_file(output, overwrite)?;
+		write_files(output.parent().unwrap_or(Path::new("")), files)?;
+	}
+
+	if human {
+		println!("Compiled!");
+	}
+
+	Ok(())
+}
+
+/// Verifies `input` and writes the files the plugin `name` generates from it to the `output` directory.
+#[allow(clippy::too_many_arguments)]
+pub fn run_plugin(
+	input: &Path,
+	output: &Path,
+	name: &str,
+	parameters: &BTreeMap<String, String>,
+	naming_conventions: &NamingConventionOverrides,
+	include_paths: &[PathBuf],
+	overwrite: bool,
+	lint_config: &LintConfig,
+	message_format: MessageFormat,
+) -> eyre::Result<()> {
+	ensure_is_file(input)?;
+	if output.exists() && !output.is_dir() {
+		return Err(eyre!("Output must be a directory for plugins."));
+	}
+
+	let mut emitter = DiagnosticEmitter::new(message_format);
+	let schema = verify_file(input, include_paths, lint_config, &mut emitter);
+	let human = emitter.is_human();
+	emitter.finish();
+
+	let Some(schema) = schema else {
+		return Err(Failed.into());
+	};
+
+	let files = plugin::run_plugin(name, input, &schema, parameters, naming_conventions)?;
+	// checked up front so a conflict doesn't leave half of the files written
+	if !overwrite {
+		if let Some(file) = files.iter().find(|file| output.join(&file.path).exists()) {
+			return Err(eyre!(
+				"'{}' exists, specify --overwrite if you want to overwrite existing files.",
+				output.join(&file.path).display()
+			));
 		}
 	}
+	write_files(output, files)?;
 
-	println!("Compiled!");
+	if human {
+		println!("Compiled!");
+	}
 
 	Ok(())
 }
 
-fn gen_rust(
-	parser: &Parser,
-	output: PathBuf,
+/// Files generated for one output of a project.
+#[derive(Debug, Clone)]
+pub struct Generated {
+	pub dir: PathBuf,
+	/// The crate directory that is replaced as a whole, if a crate was generated.
+	pub crate_dir: Option<PathBuf>,
+	pub files: Vec<GeneratedFile>,
+}
+
+/// Compiles every schema of `project` into each of its outputs.
+///
+/// `naming_conventions` take precedence over the ones in the project. Nothing is written unless every schema
+/// verifies and every output could be generated.
+pub fn run_project(
+	project: &Project,
+	naming_conventions: &NamingConventionOverrides,
+	include_paths: &[PathBuf],
 	overwrite: bool,
-	gen_crate: bool,
-	gen_crate_name: &Option<String>,
-	naming_convention_config: &NamingConventionConfig,
+	lint_config: &LintConfig,
+	message_format: MessageFormat,
 ) -> eyre::Result<()> {
-	if gen_crate {
-		return if let Some(crate_name) = gen_crate_name {
-			gen_rust_crate(parser, &output, crate_name, overwrite, naming_convention_config)
-		} else {
-			Err(eyre!("Must specify crate name. (TIP: --rs-crate-name=<name>)"))
+	let schemas = project_schemas(project)?;
+
+	let mut emitter = DiagnosticEmitter::new(message_format);
+	let mut verification = verify_schemas(&schemas, include_paths, lint_config);
+	for diagnostic in std::mem::take(&mut verification.diagnostics) {
+		emitter.emit(diagnostic);
+	}
+
+	let mut generated = Vec::new();
+	let mut verified = Vec::with_capacity(schemas.len());
+	let mut valid = false;
+	if let Some(schema) = &verification.schema {
+		valid = true;
+		match generate_combined(project, naming_conventions, &schemas, schema, &mut emitter) {
+			Some(files) => generated.extend(files),
+			None => valid = false,
+		}
+
+		for (idx, input) in schemas.iter().enumerate() {
+			let schema = verification.root_schema(idx).expect("the schemas verified");
+			match generate_languages(project, naming_conventions, input, &schema, &mut emitter) {
+				Some(files) => generated.extend(files),
+				None => valid = false,
+			}
+			verified.push((input, schema));
+		}
+	}
+	let human = emitter.is_human();
+	emitter.finish();
+
+	if !valid {
+		return Err(Failed.into());
+	}
+
+	for (input, schema) in &verified {
+		generated.extend(generate_plugins(project, naming_conventions, input, schema)?);
+	}
+
+	check_conflicts(&generated, overwrite)?;
+	for output in generated {
+		if let Some(crate_dir) = output.crate_dir.filter(|crate_dir| crate_dir.exists()) {
+			std::fs::remove_dir_all(crate_dir)?;
+		}
+		write_files(&output.dir, output.files)?;
+	}
+
+	if human {
+		println!("Compiled!");
+	}
+
+	Ok(())
+}
+
+/// Verifies every schema into a single schema, so lints look at every procedure and combined outputs can be
+/// generated from it. The schema of each input is cut out of it with [`Verification::root_schema`].
+#[must_use]
+pub fn verify_schemas(schemas: &[SchemaInput], include_paths: &[PathBuf], lint_config: &LintConfig) -> Verification {
+	let paths = schemas.iter().map(|input| input.path.clone()).collect::<Vec<_>>();
+	verify_schemas_from(&RealFileSystem, &paths, include_paths, lint_config)
+}
+
+/// Whether every schema is generated into a single file for `language` instead of a file per schema.
+///
+/// The files of a Go or Kotlin package, a C# namespace or a C program share their declarations, so a file per schema
+/// would declare the runtime and every imported type more than once.
+#[must_use]
+pub fn is_combined(language: CompileLanguage) -> bool {
+	matches!(
+		language,
+		CompileLanguage::Go | CompileLanguage::C | CompileLanguage::CSharp | CompileLanguage::Kotlin
+	)
+}
+
+/// The schemas of `project`, making sure there is something to compile.
+pub fn project_schemas(project: &Project) -> eyre::Result<Vec<SchemaInput>> {
+	let schemas = project.schemas()?;
+	if schemas.is_empty() {
+		return Err(eyre!("The project doesn't list any schemas."));
+	}
+	if project.outputs.is_empty() {
+		return Err(eyre!("The project doesn't have any outputs."));
+	}
+	if schemas.len() > 1 && project.outputs.iter().any(|output| output.crate_name.is_some()) {
+		return Err(eyre!("Only a single schema can be compiled into a crate."));
+	}
+
+	Ok(schemas)
+}
+
+/// Generates `schema` for every output of `project` with a built-in language that gets a file per schema, emitting any
+/// errors. The file is named after the schema and mirrors where it was found.
+///
+/// Returns `None` if any output couldn't be generated.
+pub fn generate_languages(
+	project: &Project,
+	naming_conventions: &NamingConventionOverrides,
+	input: &SchemaInput,
+	schema: &ResolvedSchema,
+	emitter: &mut DiagnosticEmitter,
+) -> Option<Vec<Generated>> {
+	let outputs = language_outputs(project).filter(|(_, language)| !is_combined(*language));
+	generate_outputs(outputs, emitter, |output, language| {
+		let dir = match output.crate_name {
+			Some(_) => project.resolve(&output.path),
+			None => project.resolve(&output.path).join(&input.relative_dir),
 		};
+		let file_name = output
+			.file_name
+			.clone()
+			.unwrap_or_else(|| schema_file_name(&input.path, language));
+		generate_output(project, output, naming_conventions, dir, &file_name, schema)
+	})
+}
+
+/// Generates the schema every schema of `project` was verified into for the outputs of `project` with a built-in
+/// language that get a single file, see [`is_combined`]. Errors are emitted.
+///
+/// The file is named after the schema if there is only one.
+///
+/// Returns `None` if any output couldn't be generated.
+pub fn generate_combined(
+	project: &Project,
+	naming_conventions: &NamingConventionOverrides,
+	schemas: &[SchemaInput],
+	schema: &ResolvedSchema,
+	emitter: &mut DiagnosticEmitter,
+) -> Option<Vec<Generated>> {
+	let outputs = language_outputs(project).filter(|(_, language)| is_combined(*language));
+	generate_outputs(outputs, emitter, |output, language| {
+		let file_name = output.file_name.clone().unwrap_or_else(|| match schemas {
+			[input] => schema_file_name(&input.path, language),
+			_ => language.default_file_name().to_string(),
+		});
+		generate_output(
+			project,
+			output,
+			naming_conventions,
+			project.resolve(&output.path),
+			&file_name,
+			schema,
+		)
+	})
+}
+
+/// The outputs of `project` with a built-in language.
+fn language_outputs(project: &Project) -> impl Iterator<Item = (&Output, CompileLanguage)> {
+	project
+		.outputs
+		.iter()
+		.filter_map(|output| Some((output, output.language?)))
+}
+
+/// Generates every output with `generate`, emitting any errors. Returns `None` if any output couldn't be generated.
+fn generate_outputs<'a>(
+	outputs: impl Iterator<Item = (&'a Output, CompileLanguage)>,
+	emitter: &mut DiagnosticEmitter,
+	mut generate: impl FnMut(&Output, CompileLanguage) -> Result<Generated, OlympusError>,
+) -> Option<Vec<Generated>> {
+	let mut generated = Some(Vec::new());
+	for (output, language) in outputs {
+		match generate(output, language) {
+			Ok(output) => {
+				if let Some(generated) = &mut generated {
+					generated.push(output);
+				}
+			}
+			Err(err) => {
+				emitter.emit(err);
+				generated = None;
+			}
+		}
 	}
 
+	generated
+}
+
+/// Runs the plugin of every output of `project` that has one for `schema`.
+pub fn generate_plugins(
+	project: &Project,
+	naming_conventions: &NamingConventionOverrides,
+	input: &SchemaInput,
+	schema: &ResolvedSchema,
+) -> eyre::Result<Vec<Generated>> {
+	let mut generated = Vec::new();
+	for output in &project.outputs {
+		let Some(name) = &output.plugin else {
+			continue;
+		};
+
+		let naming_conventions = naming_conventions.or(&output.naming.or(&project.naming));
+		generated.push(Generated {
+			dir: project.resolve(&output.path).join(&input.relative_dir),
+			crate_dir: None,
+			files: plugin::run_plugin(name, &input.path, schema, &output.options, &naming_conventions)?,
+		});
+	}
+
+	Ok(generated)
+}
+
+/// Makes sure no file is generated twice and, unless `overwrite` is set, that nothing exists yet.
+///
+/// Checked up front so a conflict doesn't leave half of the files written.
+pub fn check_conflicts(generated: &[Generated], overwrite: bool) -> eyre::Result<()> {
+	let mut written = HashSet::new();
+	for output in generated {
+		if let Some(crate_dir) = &output.crate_dir {
+			if crate_dir.exists() && !overwrite {
+				return Err(eyre!(
+					"'{}' exists, specify --overwrite if you want to overwrite existing files.",
+					crate_dir.display()
+				));
+			}
+		}
+
+		for file in &output.files {
+			let path = output.dir.join(&file.path);
+			if !written.insert(path.clone()) {
+				return Err(eyre!("'{}' would be generated more than once.", path.display()));
+			}
+			if output.crate_dir.is_none() && path.exists() && !overwrite {
+				return Err(eyre!(
+					"'{}' exists, specify --overwrite if you want to overwrite existing files.",
+					path.display()
+				));
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Generates `schema` for an output with a language into `file_name` in `dir`.
+fn generate_output(
+	project: &Project,
+	output: &Output,
+	naming_conventions: &NamingConventionOverrides,
+	dir: PathBuf,
+	file_name: &str,
+	schema: &ResolvedSchema,
+) -> Result<Generated, OlympusError> {
+	let language = output.language.expect("only called for outputs with a language");
+	let naming_conventions = naming_conventions.or(&output.naming.or(&project.naming));
+
+	let options = GenerateOptions {
+		language,
+		naming_convention_config: naming_conventions.resolve(&NamingConventionConfig::defaults_for(language)),
+		file_name: Some(file_name.to_string()),
+		rs_crate_name: output.crate_name.clone(),
+		rs_crate_version: output.crate_version.clone(),
+		rs_crate_side: output.crate_side.unwrap_or_default(),
+		rs_dependencies: output
+			.olympus
+			.as_ref()
+			.map(|olympus| olympus.dependencies().expect("checked by Project::validate"))
+			.unwrap_or_default(),
+		rs_derives: project.rust.derives.clone(),
+		rs_type_derives: project.rust.type_derives.clone(),
+		rs_serde: project.rust.serde,
+		go_package: Some(
+			output
+				.go_package
+				.clone()
+				.unwrap_or_else(|| default_go_package(&dir.join(file_name))),
+		),
+		cs_namespace: output.cs_namespace.clone(),
+		kt_package: output.kt_package.clone(),
+	};
+
+	Ok(Generated {
+		crate_dir: output.crate_name.as_ref().map(|crate_name| dir.join(crate_name)),
+		dir,
+		files: generate(schema, &options)?,
+	})
+}
+
+/// `files.ol` becomes `files.ts` for TypeScript.
+fn schema_file_name(path: &Path, language: CompileLanguage) -> String {
+	let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+	let extension = Path::new(language.default_file_name())
+		.extension()
+		.unwrap_or_default()
+		.to_string_lossy();
+	format!("{stem}.{extension}")
+}
+
+fn ensure_can_write_file(output: &Path, overwrite: bool) -> eyre::Result<()> {
 	if output.is_dir() {
 		return Err(eyre!("You cannot output compiled source to a directory"));
 	}
@@ -73,33 +428,15 @@ fn gen_rust(
 		));
 	}
 
-	let mut models_src = String::with_capacity(4096);
-	RustCodeGenerator.generate_models(parser, &mut models_src, naming_convention_config);
-
-	let mut output_file = OpenOptions::new()
-		.write(true)
-		.truncate(true)
-		.create(true)
-		.open(output)?;
-	output_file.write_all(models_src.as_bytes())?;
-
 	Ok(())
 }
 
-fn gen_rust_crate(
-	parser: &Parser,
-	output_dir: &Path,
-	crate_name: &str,
-	overwrite: bool,
-	naming_convention_config: &NamingConventionConfig,
-) -> eyre::Result<()> {
+/// Makes sure the crate can be written to `output_dir`, removing an existing one if `overwrite` is set.
+fn prepare_crate_dir(output_dir: &Path, crate_name: &str, overwrite: bool) -> eyre::Result<()> {
 	if !output_dir.is_dir() {
 		return Err(eyre!("Output must be a directory for crate generation."));
 	}
 
-	let mut models_src = String::with_capacity(4096);
-	RustCodeGenerator.generate_models(parser, &mut models_src, naming_convention_config);
-
 	let crate_path = output_dir.join(crate_name);
 	if crate_path.exists() {
 		if !crate_path.is_dir() {
@@ -117,49 +454,112 @@ fn gen_rust_crate(
 		}
 	}
 
-	std::fs::create_dir_all(&crate_path)?;
-
-	let cargo_toml_path = crate_path.join("Cargo.toml");
-	std::fs::write(
-		cargo_toml_path,
-		format!(
-			"[package]
-name = \"{crate_name}\"
-edition = \"2021\"
-version = \"0.0.0-OLYMPUS-GENERATED\"
-publish = false
+	Ok(())
+}
 
-[dependencies]
-olympus-net-common.workspace = true
-olympus-server.workspace = true
-"
-		),
-	)?;
-
-	let src_path = crate_path.join("src");
-	std::fs::create_dir(&src_path)?;
-
-	let src_lib_path = src_path.join("lib.rs");
-	std::fs::write(
-		src_lib_path,
-		format!("// {GENERATED_COMMENT}\npub mod models;\npub mod server;"),
-	)?;
-
-	let src_models_path = src_path.join("models.rs");
-	let mut models_src = String::with_capacity(4096);
-	RustCodeGenerator.generate_file_header(&mut models_src);
-	RustCodeGenerator.generate_models(parser, &mut models_src, naming_convention_config);
-	RustCodeGenerator.generate_file_footer(&mut models_src);
-	std::fs::write(src_models_path, format!("// {GENERATED_COMMENT}\n{models_src}"))?;
-
-	let src_server_path = src_path.join("server.rs");
-	let mut server_src = String::with_capacity(4096);
-	RustCodeGenerator.generate_file_header(&mut server_src);
-	server_src.push_str("use crate::models::*;\n");
-	RustCodeGenerator.generate_abstract_server_impl(&parser.procedures, &mut server_src, naming_convention_config);
-	RustCodeGenerator.generate_server_registration_fn(&parser.procedures, &mut server_src, naming_convention_config);
-	RustCodeGenerator.generate_file_footer(&mut server_src);
-	std::fs::write(src_server_path, format!("// {GENERATED_COMMENT}\n{server_src}"))?;
+/// Writes every file below `dir`, creating directories as needed.
+pub fn write_files(dir: &Path, files: Vec<GeneratedFile>) -> eyre::Result<()> {
+	for file in files {
+		let path = dir.join(&file.path);
+		if let Some(parent) = path.parent() {
+			std::fs::create_dir_all(parent)?;
+		}
+		std::fs::write(path, file.contents)?;
+	}
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use olympus_compiler::{
+		diagnostics::{DiagnosticEmitter, MessageFormat},
+		naming::NamingConventionOverrides,
+		project::Project,
+		verify_schema,
+	};
+	use olympus_verifier::{EnumId, LintConfig, ResolvedType};
+
+	use super::{generate_combined, generate_languages, verify_schemas};
+
+	fn write(dir: &Path, path: &str, contents: &str) {
+		let path = dir.join(path);
+		std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+		std::fs::write(path, contents).unwrap();
+	}
+
+	#[test]
+	fn combines_shared_namespaces_and_mirrors_the_rest() {
+		let dir = tempfile::tempdir().unwrap();
+		write(
+			dir.path(),
+			"olympus.toml",
+			r#"
+schemas = ["schemas"]
+
+[[output]]
+language = "go"
+path = "out/go"
+
+[[output]]
+language = "typescript"
+path = "out/ts"
+"#,
+		);
+		write(
+			dir.path(),
+			"schemas/files.ol",
+			"import user;\nstruct File { owner->User; }\nproc GetFile() -> File;",
+		);
+		write(dir.path(), "schemas/user.ol", "struct User { name->@string; }");
+		write(
+			dir.path(),
+			"schemas/nested/action.ol",
+			"enum Action { Delete->1; }\nproc Act(action->Action);",
+		);
+
+		let project = Project::load(&dir.path().join("olympus.toml")).unwrap();
+		let schemas = project.schemas().unwrap();
+		let find = |name: &str| schemas.iter().find(|input| input.path.ends_with(name)).unwrap();
+		let naming_conventions = NamingConventionOverrides::default();
+		let mut emitter = DiagnosticEmitter::new(MessageFormat::Human);
+
+		// `User` is only used by files.ol, which isn't a root when user.ol is verified on its own
+		assert!(verify_schema(&find("user.ol").path, &LintConfig::default())
+			.diagnostics
+			.iter()
+			.any(|diagnostic| diagnostic.lint == Some("unused_type")));
+		let verification = verify_schemas(&schemas, &[], &LintConfig::default());
+		assert!(verification.diagnostics.is_empty(), "{:?}", verification.diagnostics);
+
+		let schema = verification.schema.as_ref().unwrap();
+		let combined = generate_combined(&project, &naming_conventions, &schemas, schema, &mut emitter).unwrap();
+		assert_eq!(combined.len(), 1);
+		assert_eq!(combined[0].dir, dir.path().join("out/go"));
+		assert_eq!(combined[0].files.len(), 1);
+		let go = &combined[0].files[0];
+		assert_eq!(go.path, Path::new("olympus.go"));
+		assert_eq!(go.contents.matches("type User struct").count(), 1);
+		assert_eq!(go.contents.matches("package olympus\n").count(), 1);
+		assert!(go.contents.contains("type Action uint16"));
+
+		// cut out of the verification of every schema, as if action.ol was verified on its own
+		let position = |name: &str| schemas.iter().position(|input| input.path.ends_with(name)).unwrap();
+		let files = verification.root_schema(position("files.ol")).unwrap();
+		let structs = files.structs.iter().map(|r#struct| r#struct.ident.value.as_str());
+		assert_eq!(structs.collect::<Vec<_>>(), ["File", "User"]);
+		assert!(files.enums.is_empty());
+
+		let action = find("action.ol");
+		let schema = verification.root_schema(position("action.ol")).unwrap();
+		assert!(schema.structs.is_empty());
+		assert_eq!(schema.procedures.len(), 1);
+		assert_eq!(schema.procedures[0].params[0].kind.value, ResolvedType::Enum(EnumId(0)));
+		let mirrored = generate_languages(&project, &naming_conventions, action, &schema, &mut emitter).unwrap();
+		assert_eq!(mirrored.len(), 1);
+		assert_eq!(mirrored[0].dir, dir.path().join("out/ts/nested"));
+		assert_eq!(mirrored[0].files[0].path, Path::new("action.ts"));
+	}
+}
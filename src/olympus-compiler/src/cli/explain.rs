@@ -0,0 +1,22 @@
+use eyre::eyre;
+use olympus_spanned::ErrorCode;
+use olympus_verifier::Lint;
+
+pub fn run(code: Option<&str>) -> eyre::Result<()> {
+	let Some(code) = code else {
+		for code in ErrorCode::ALL {
+			println!("{code}  {}", code.title());
+		}
+		return Ok(());
+	};
+
+	let resolved = ErrorCode::from_code(code).or_else(|| Lint::from_name(code).map(Lint::code));
+	let Some(resolved) = resolved else {
+		return Err(eyre!(
+			"'{code}' isn't a known diagnostic code or lint, run `olympusc explain` to list every code."
+		));
+	};
+
+	print!("{}", resolved.explanation());
+	Ok(())
+}
@@ -1,18 +1,34 @@
-use std::{path::PathBuf, process::exit};
+use std::path::{Path, PathBuf};
 
-use crate::verify_src;
+use olympus_compiler::diagnostics::{DiagnosticEmitter, MessageFormat};
+use olympus_verifier::LintConfig;
 
-use super::{ensure_is_file, get_filename};
+use super::{ensure_is_file, verify_file, Failed};
 
-pub fn run(file: PathBuf) -> eyre::Result<()> {
-	ensure_is_file(&file)?;
+pub fn run(
+	files: &[impl AsRef<Path>],
+	include_paths: &[PathBuf],
+	lint_config: &LintConfig,
+	message_format: MessageFormat,
+) -> eyre::Result<()> {
+	for file in files {
+		ensure_is_file(file.as_ref())?;
+	}
+
+	let mut emitter = DiagnosticEmitter::new(message_format);
+	let mut valid = true;
+	for file in files {
+		valid &= verify_file(file.as_ref(), include_paths, lint_config, &mut emitter).is_some();
+	}
+	let human = emitter.is_human();
+	emitter.finish();
+
+	if !valid {
+		return Err(Failed.into());
+	}
 
-	let filename = get_filename(&file)?;
-	let src = std::fs::read_to_string(file)?;
-	if verify_src(&src, &filename).is_some() {
+	if human {
 		println!("Valid!");
-	} else {
-		exit(-1);
 	}
 
 	Ok(())
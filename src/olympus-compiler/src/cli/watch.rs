@@ -0,0 +1,581 @@
+use std::{
+	collections::{HashMap, HashSet},
+	path::{Path, PathBuf},
+	sync::mpsc,
+	time::Duration,
+};
+
+use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode, DebouncedEvent, Debouncer};
+use olympus_spanned::OlympusError;
+use olympus_verifier::{lint_unused_types, Lint, LintConfig, LintLevel, ResolvedSchema};
+
+use olympus_compiler::{
+	diagnostics::{DiagnosticEmitter, MessageFormat},
+	naming::NamingConventionOverrides,
+	project::{schema_search_dir, Project, SchemaInput},
+};
+
+use super::compile::{
+	check_conflicts, generate_combined, generate_languages, generate_plugins, project_schemas, verify_schemas,
+	Generated,
+};
+
+/// How long to wait for more changes before compiling, editors often save a file in several steps.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// What the last compilation of a schema depended on and resulted in.
+#[derive(Default)]
+struct Compiled {
+	/// Every file the schema was loaded from, canonicalized to match the paths of events.
+	files: HashSet<PathBuf>,
+	/// Failed schemas are compiled again after any change, a missing import might have been created.
+	valid: bool,
+	/// The schema of the file and its imports, reused for the combined outputs until one of `files` changes. `None` if
+	/// it didn't verify.
+	schema: Option<ResolvedSchema>,
+	/// Every file generated for the schema on its own, removed once it isn't generated anymore.
+	outputs: HashSet<PathBuf>,
+}
+
+/// Everything compiled since watching started.
+#[derive(Default)]
+struct Compilation {
+	schemas: HashMap<PathBuf, Compiled>,
+	/// The files of the outputs every schema is generated into together.
+	combined: HashSet<PathBuf>,
+	/// Files generated for schemas that were removed, deleted once everything compiles again.
+	orphaned: HashSet<PathBuf>,
+}
+
+/// Compiles every schema of `project` and then again whenever it or one of its imports changes, until killed.
+pub fn run(
+	project: &Project,
+	naming_conventions: &NamingConventionOverrides,
+	include_paths: &[PathBuf],
+	lint_config: &LintConfig,
+	message_format: MessageFormat,
+) -> eyre::Result<()> {
+	let (sender, receiver) = mpsc::channel();
+	let mut debouncer = new_debouncer(DEBOUNCE, sender)?;
+	let mut watched = HashSet::new();
+	let mut compilation = Compilation::default();
+	// `None` compiles everything
+	let mut changed = None::<HashSet<PathBuf>>;
+
+	loop {
+		match project_schemas(project) {
+			Ok(schemas) => {
+				let before = compilation.schemas.len();
+				compilation.schemas.retain(|path, compiled| {
+					let kept = schemas.iter().any(|input| &input.path == path);
+					if !kept {
+						compilation.orphaned.extend(compiled.outputs.drain());
+					}
+					kept
+				});
+				let removed = compilation.schemas.len() < before;
+				let stale = stale_schemas(&schemas, &compilation.schemas, changed.as_ref());
+
+				// combined outputs still have the types of a schema that was removed
+				if !stale.is_empty() || removed {
+					let human = message_format == MessageFormat::Human;
+					match compile(
+						project,
+						naming_conventions,
+						include_paths,
+						lint_config,
+						message_format,
+						&schemas,
+						&stale,
+						&mut compilation,
+					) {
+						Ok(Some(written)) if human => println!("Compiled! ({written} files changed)"),
+						Ok(_) => {}
+						Err(err) => eprintln!("{err:#}"),
+					}
+				}
+
+				watch(
+					&mut debouncer,
+					&mut watched,
+					project,
+					include_paths,
+					&compilation.schemas,
+				);
+			}
+			Err(err) => eprintln!("{err:#}"),
+		}
+
+		changed = Some(wait_for_changes(&receiver)?);
+	}
+}
+
+/// The schemas that have to be compiled again after the files in `changed` changed, `None` meaning every file did.
+///
+/// Those are new schemas, schemas that failed last time and schemas that were loaded from a changed file.
+fn stale_schemas<'a>(
+	schemas: &'a [SchemaInput],
+	compiled: &HashMap<PathBuf, Compiled>,
+	changed: Option<&HashSet<PathBuf>>,
+) -> Vec<&'a SchemaInput> {
+	schemas
+		.iter()
+		.filter(|input| {
+			let (Some(changed), Some(compiled)) = (changed, compiled.get(&input.path)) else {
+				return true;
+			};
+			!compiled.valid || compiled.files.iter().any(|file| changed.contains(file))
+		})
+		.collect()
+}
+
+/// Verifies the `stale` schemas, generates the combined outputs from them and the cached schemas of the others and
+/// the outputs of `stale`, and writes the files that changed.
+///
+/// Returns how many files were written or removed, or `None` if a schema failed. Nothing is written if the schemas
+/// don't verify together, otherwise the schemas that didn't fail are still written.
+#[allow(clippy::too_many_arguments)]
+fn compile(
+	project: &Project,
+	naming_conventions: &NamingConventionOverrides,
+	include_paths: &[PathBuf],
+	lint_config: &LintConfig,
+	message_format: MessageFormat,
+	schemas: &[SchemaInput],
+	stale: &[&SchemaInput],
+	compilation: &mut Compilation,
+) -> eyre::Result<Option<usize>> {
+	let mut emitter = DiagnosticEmitter::new(message_format);
+	// whether a type is used depends on the schemas that aren't verified again, it's checked once they are merged
+	let mut stale_lint_config = lint_config.clone();
+	stale_lint_config.set(Lint::UnusedType, LintLevel::Allow);
+	let stale_inputs = stale.iter().map(|input| (*input).clone()).collect::<Vec<_>>();
+	let mut verification = verify_schemas(&stale_inputs, include_paths, &stale_lint_config);
+	for diagnostic in std::mem::take(&mut verification.diagnostics) {
+		emitter.emit(diagnostic);
+	}
+
+	for (idx, input) in stale.iter().enumerate() {
+		let compiled = compilation.schemas.entry(input.path.clone()).or_default();
+		compiled.schema = verification.root_schema(idx);
+		compiled.valid = compiled.schema.is_some();
+		compiled.files = match verification.root_files.get(idx) {
+			Some(files) => canonicalize(files),
+			// any file that was read might be the one that has to be fixed
+			None => canonicalize(&verification.files),
+		};
+	}
+
+	let merged = verification
+		.schema
+		.is_some()
+		.then(|| merge(schemas, lint_config, compilation, &mut emitter));
+	let Some(Some(schema)) = merged else {
+		emitter.finish();
+		for input in stale {
+			compilation.schemas.get_mut(&input.path).expect("compiled above").valid = false;
+		}
+		return Ok(None);
+	};
+
+	let mut generated = Vec::new();
+	let mut plugin_errors = Vec::new();
+	let combined = generate_combined(project, naming_conventions, schemas, &schema, &mut emitter);
+	let mut all_valid = combined.is_some();
+	let combined = combined.map(|combined| {
+		generated.extend(combined.iter().cloned());
+		output_paths(&combined)
+	});
+
+	let mut outputs = Vec::new();
+	for input in stale {
+		let compiled = compilation.schemas.get_mut(&input.path).expect("compiled above");
+		let schema = compiled.schema.as_ref().expect("every schema verified");
+		let Some(mut files) = generate_languages(project, naming_conventions, input, schema, &mut emitter) else {
+			compiled.valid = false;
+			all_valid = false;
+			continue;
+		};
+		match generate_plugins(project, naming_conventions, input, schema) {
+			Ok(plugin_files) => files.extend(plugin_files),
+			Err(err) => {
+				plugin_errors.push(err);
+				compiled.valid = false;
+				all_valid = false;
+				continue;
+			}
+		}
+
+		outputs.push((&input.path, output_paths(&files)));
+		generated.extend(files);
+	}
+	emitter.finish();
+
+	for err in plugin_errors {
+		eprintln!("{err:#}");
+	}
+
+	check_conflicts(&generated, true)?;
+	let mut previous = std::mem::take(&mut compilation.orphaned);
+	if let Some(combined) = combined {
+		previous.extend(std::mem::replace(&mut compilation.combined, combined));
+	}
+	for (path, files) in outputs {
+		let compiled = compilation.schemas.get_mut(path).expect("compiled above");
+		previous.extend(std::mem::replace(&mut compiled.outputs, files));
+	}
+
+	let written = write_changed(generated, previous)?;
+	Ok(all_valid.then_some(written))
+}
+
+/// Merges the schema of every schema file, emitting an error if they don't fit together or whether any of their types
+/// isn't used. `None` if that was denied.
+fn merge(
+	schemas: &[SchemaInput],
+	lint_config: &LintConfig,
+	compilation: &Compilation,
+	emitter: &mut DiagnosticEmitter,
+) -> Option<ResolvedSchema> {
+	let cached = schemas
+		.iter()
+		.map(|input| compilation.schemas[&input.path].schema.as_ref())
+		.collect::<Option<Vec<_>>>()?;
+	let schema = match ResolvedSchema::merge(&cached) {
+		Ok(schema) => schema,
+		Err(err) => {
+			emitter.emit(err);
+			return None;
+		}
+	};
+
+	// the name of a schema file in diagnostics is its path
+	let roots = schemas
+		.iter()
+		.map(|input| input.path.to_string_lossy())
+		.collect::<Vec<_>>();
+	let roots = roots.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+	let diagnostics = lint_unused_types(&schema, lint_config, &roots);
+	let denied = diagnostics.iter().any(OlympusError::is_error);
+	for diagnostic in diagnostics {
+		emitter.emit(diagnostic);
+	}
+
+	(!denied).then_some(schema)
+}
+
+/// The path of every file of `generated`.
+fn output_paths(generated: &[Generated]) -> HashSet<PathBuf> {
+	generated
+		.iter()
+		.flat_map(|output| output.files.iter().map(|file| output.dir.join(&file.path)))
+		.collect()
+}
+
+/// Events carry canonical paths.
+fn canonicalize(files: &[PathBuf]) -> HashSet<PathBuf> {
+	files.iter().filter_map(|file| file.canonicalize().ok()).collect()
+}
+
+/// Only writes files whose contents differ, so tools watching the output aren't triggered for nothing.
+///
+/// Files in `previous` and in a generated crate directory that aren't generated anymore are removed.
+fn write_changed(generated: Vec<Generated>, previous: HashSet<PathBuf>) -> eyre::Result<usize> {
+	let paths = output_paths(&generated);
+	let mut stale = previous;
+	for crate_dir in generated.iter().filter_map(|output| output.crate_dir.as_ref()) {
+		crate_files(crate_dir, &mut stale)?;
+	}
+
+	let mut written = 0;
+	for output in generated {
+		for file in output.files {
+			let path = output.dir.join(&file.path);
+			if std::fs::read_to_string(&path).is_ok_and(|contents| contents == file.contents) {
+				continue;
+			}
+
+			if let Some(parent) = path.parent() {
+				std::fs::create_dir_all(parent)?;
+			}
+			std::fs::write(path, file.contents)?;
+			written += 1;
+		}
+	}
+
+	for path in stale.difference(&paths) {
+		if path.is_file() {
+			std::fs::remove_file(path)?;
+			written += 1;
+		}
+	}
+
+	Ok(written)
+}
+
+/// Adds every file below `dir` to `files`.
+fn crate_files(dir: &Path, files: &mut HashSet<PathBuf>) -> std::io::Result<()> {
+	let Ok(entries) = std::fs::read_dir(dir) else {
+		return Ok(());
+	};
+	for entry in entries {
+		let path = entry?.path();
+		if path.is_dir() {
+			crate_files(&path, files)?;
+		} else {
+			files.insert(path);
+		}
+	}
+
+	Ok(())
+}
+
+/// Watches the directories schemas are looked for in and every directory a schema was loaded from.
+fn watch(
+	debouncer: &mut Debouncer<impl notify_debouncer_mini::notify::Watcher>,
+	watched: &mut HashSet<PathBuf>,
+	project: &Project,
+	include_paths: &[PathBuf],
+	compiled: &HashMap<PathBuf, Compiled>,
+) {
+	let searched = project
+		.schemas
+		.iter()
+		.map(|entry| schema_search_dir(&project.resolve(entry)));
+	let loaded = compiled
+		.values()
+		.flat_map(|compiled| &compiled.files)
+		.filter_map(|file| Some((file.parent()?.to_path_buf(), false)))
+		.chain(include_paths.iter().map(|include| (include.clone(), false)));
+
+	for (dir, recursive) in searched.chain(loaded) {
+		let dir = if dir.as_os_str().is_empty() {
+			Path::new(".")
+		} else {
+			&dir
+		};
+		let Ok(dir) = dir.canonicalize() else {
+			continue;
+		};
+		// the directories schemas are searched in come first, so they are the ones watched recursively
+		if watched.contains(&dir) {
+			continue;
+		}
+
+		let mode = if recursive {
+			RecursiveMode::Recursive
+		} else {
+			RecursiveMode::NonRecursive
+		};
+		match debouncer.watcher().watch(&dir, mode) {
+			Ok(()) => {
+				watched.insert(dir);
+			}
+			Err(err) => eprintln!("Couldn't watch '{}': {err}", dir.display()),
+		}
+	}
+}
+
+/// Blocks until at least one schema file changed and returns every changed schema file.
+fn wait_for_changes(
+	receiver: &mpsc::Receiver<notify_debouncer_mini::DebounceEventResult>,
+) -> eyre::Result<HashSet<PathBuf>> {
+	loop {
+		let events = receiver
+			.recv()?
+			.map_err(|err| eyre::eyre!("Couldn't watch for changes: {err}"))?;
+		let changed = events
+			.into_iter()
+			.map(|DebouncedEvent { path, .. }| path)
+			.filter(|path| path.extension().is_some_and(|extension| extension == "ol"))
+			.collect::<HashSet<_>>();
+
+		if !changed.is_empty() {
+			return Ok(changed);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		collections::{HashMap, HashSet},
+		path::{Path, PathBuf},
+	};
+
+	use olympus_verifier::{LintConfig, ResolvedSchema};
+
+	use olympus_compiler::{generator::GeneratedFile, project::SchemaInput, verify_schemas_from, MemoryFileSystem};
+
+	use super::{stale_schemas, write_changed, Compiled};
+	use crate::cli::compile::Generated;
+
+	fn input(path: &str) -> SchemaInput {
+		SchemaInput {
+			path: PathBuf::from(path),
+			relative_dir: PathBuf::new(),
+		}
+	}
+
+	fn compiled(files: &[&str], valid: bool) -> Compiled {
+		Compiled {
+			files: files.iter().map(PathBuf::from).collect(),
+			valid,
+			..Compiled::default()
+		}
+	}
+
+	fn paths<'a>(stale: &[&'a SchemaInput]) -> Vec<&'a Path> {
+		stale.iter().map(|input| input.path.as_path()).collect()
+	}
+
+	#[test]
+	fn recompiles_schemas_loaded_from_changed_files() {
+		let schemas = [input("files.ol"), input("user.ol"), input("new.ol")];
+		let compiled = HashMap::from([
+			(
+				PathBuf::from("files.ol"),
+				compiled(&["/s/files.ol", "/s/user.ol"], true),
+			),
+			(PathBuf::from("user.ol"), compiled(&["/s/user.ol"], true)),
+		]);
+
+		// everything is compiled at first
+		assert_eq!(
+			paths(&stale_schemas(&schemas, &compiled, None)),
+			["files.ol", "user.ol", "new.ol"]
+		);
+
+		let changed = HashSet::from([PathBuf::from("/s/user.ol")]);
+		assert_eq!(
+			paths(&stale_schemas(&schemas, &compiled, Some(&changed))),
+			["files.ol", "user.ol", "new.ol"]
+		);
+
+		let changed = HashSet::from([PathBuf::from("/s/files.ol")]);
+		assert_eq!(
+			paths(&stale_schemas(&schemas, &compiled, Some(&changed))),
+			["files.ol", "new.ol"]
+		);
+	}
+
+	#[test]
+	fn retries_failed_schemas_after_any_change() {
+		let schemas = [input("files.ol"), input("user.ol")];
+		let compiled = HashMap::from([
+			(PathBuf::from("files.ol"), compiled(&["/s/files.ol"], false)),
+			(PathBuf::from("user.ol"), compiled(&["/s/user.ol"], true)),
+		]);
+
+		// a missing import might have been created by a change to an unrelated file
+		let changed = HashSet::from([PathBuf::from("/s/action.ol")]);
+		assert_eq!(paths(&stale_schemas(&schemas, &compiled, Some(&changed))), ["files.ol"]);
+	}
+
+	#[test]
+	fn only_writes_files_that_changed() {
+		let dir = tempfile::tempdir().unwrap();
+		let generated = |contents: &str| {
+			vec![Generated {
+				dir: dir.path().to_path_buf(),
+				crate_dir: None,
+				files: vec![
+					GeneratedFile {
+						path: PathBuf::from("same.ts"),
+						contents: "same".to_string(),
+					},
+					GeneratedFile {
+						path: PathBuf::from("nested/changed.ts"),
+						contents: contents.to_string(),
+					},
+				],
+			}]
+		};
+
+		assert_eq!(write_changed(generated("before"), HashSet::new()).unwrap(), 2);
+		let same = dir.path().join("same.ts");
+		let modified = std::fs::metadata(&same).unwrap().modified().unwrap();
+
+		assert_eq!(write_changed(generated("before"), HashSet::new()).unwrap(), 0);
+		assert_eq!(write_changed(generated("after"), HashSet::new()).unwrap(), 1);
+		assert_eq!(
+			std::fs::read_to_string(dir.path().join("nested/changed.ts")).unwrap(),
+			"after"
+		);
+		assert_eq!(std::fs::metadata(&same).unwrap().modified().unwrap(), modified);
+	}
+
+	#[test]
+	fn merges_schemas_like_verifying_them_together() {
+		let mut fs = MemoryFileSystem::new();
+		fs.insert(
+			"files.ol",
+			"import user;\nstruct File { owner->User; }\nproc GetFile() -> File;",
+		);
+		fs.insert("user.ol", "struct User { name->@string; }");
+		fs.insert(
+			"action.ol",
+			"import user;\nenum Action { Delete->1; }\nproc Act(action->Action, by->User);",
+		);
+
+		let paths = ["files.ol", "action.ol"].map(PathBuf::from);
+		let together = verify_schemas_from(&fs, &paths, &[], &LintConfig::default())
+			.schema
+			.unwrap();
+		let alone = paths.map(|path| {
+			verify_schemas_from(&fs, &[path], &[], &LintConfig::default())
+				.schema
+				.unwrap()
+		});
+		let merged = ResolvedSchema::merge(&[&alone[0], &alone[1]]).unwrap();
+
+		let structs = |schema: &ResolvedSchema| {
+			let structs = schema.structs.iter().map(|r#struct| {
+				let fields = r#struct.fields.iter().map(|field| field.kind.value.clone());
+				(r#struct.ident.value.clone(), fields.collect::<Vec<_>>())
+			});
+			structs.collect::<Vec<_>>()
+		};
+		let procedures = |schema: &ResolvedSchema| {
+			let procedures = schema.procedures.iter().map(|proc| {
+				let params = proc.params.iter().map(|param| param.kind.value.clone());
+				(proc.ident.value.clone(), params.collect::<Vec<_>>())
+			});
+			procedures.collect::<Vec<_>>()
+		};
+		// user.ol is shared and only taken once
+		assert_eq!(structs(&merged), structs(&together));
+		assert_eq!(procedures(&merged), procedures(&together));
+		assert_eq!(merged.enums.len(), 1);
+
+		fs.insert("other.ol", "struct User { id->@uint32; }");
+		let other = verify_schemas_from(&fs, &[PathBuf::from("other.ol")], &[], &LintConfig::default());
+		assert!(ResolvedSchema::merge(&[&alone[0], &other.schema.unwrap()]).is_err());
+	}
+
+	#[test]
+	fn removes_files_that_are_not_generated_anymore() {
+		let dir = tempfile::tempdir().unwrap();
+		let removed = dir.path().join("removed.ts");
+		let crate_dir = dir.path().join("common");
+		let stale = crate_dir.join("src/stale.rs");
+		for path in [&removed, &stale] {
+			std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+			std::fs::write(path, "").unwrap();
+		}
+
+		let generated = vec![Generated {
+			dir: dir.path().to_path_buf(),
+			crate_dir: Some(crate_dir.clone()),
+			files: vec![GeneratedFile {
+				path: PathBuf::from("common/src/lib.rs"),
+				contents: "lib".to_string(),
+			}],
+		}];
+		let previous = HashSet::from([removed.clone(), crate_dir.join("src/lib.rs")]);
+		assert_eq!(write_changed(generated, previous).unwrap(), 3);
+		assert!(!removed.exists());
+		assert!(!stale.exists());
+		assert!(crate_dir.join("src/lib.rs").exists());
+	}
+}
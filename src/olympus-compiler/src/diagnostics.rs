@@ -0,0 +1,405 @@
+use std::io::Write;
+
+use ariadne::{sources, Config, Label, Report, ReportKind};
+use clap::ValueEnum;
+use olympus_spanned::{ErrorCode, ErrorColor, OlympusError, OlympusErrorLabel, Severity};
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MessageFormat {
+	/// Coloured reports on stderr.
+	Human,
+	/// One JSON object per diagnostic on stdout.
+	Json,
+	/// A single SARIF 2.1.0 log on stdout, written once every diagnostic is known.
+	Sarif,
+}
+
+/// Writes diagnostics in the requested format.
+///
+/// [`DiagnosticEmitter::finish`] has to be called once all diagnostics were emitted, SARIF output is only written
+/// then.
+pub struct DiagnosticEmitter {
+	format: MessageFormat,
+	sarif_results: Vec<Value>,
+	sarif_rules: Vec<ErrorCode>,
+}
+
+impl DiagnosticEmitter {
+	#[must_use]
+	pub fn new(format: MessageFormat) -> Self {
+		Self {
+			format,
+			sarif_results: Vec::new(),
+			sarif_rules: Vec::new(),
+		}
+	}
+
+	/// Whether human readable status messages like "Compiled!" should be printed.
+	#[must_use]
+	pub fn is_human(&self) -> bool {
+		self.format == MessageFormat::Human
+	}
+
+	pub fn emit(&mut self, err: OlympusError) {
+		match self.format {
+			MessageFormat::Human => print_olympus_error(err),
+			MessageFormat::Json => println!("{}", diagnostic_to_json(&err)),
+			MessageFormat::Sarif => {
+				if let Some(code) = err.code.filter(|code| !self.sarif_rules.contains(code)) {
+					self.sarif_rules.push(code);
+				}
+				self.sarif_results.push(diagnostic_to_sarif(&err));
+			}
+		}
+	}
+
+	pub fn finish(self) {
+		if self.format == MessageFormat::Sarif {
+			println!("{}", self.sarif_log());
+		}
+	}
+
+	fn sarif_log(self) -> Value {
+		json!({
+			"$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+			"version": "2.1.0",
+			"runs": [{
+				"tool": {
+					"driver": {
+						"name": "olympusc",
+						"version": env!("CARGO_PKG_VERSION"),
+						"rules": self.sarif_rules.into_iter().map(code_to_sarif_rule).collect::<Vec<_>>(),
+					}
+				},
+				"columnKind": "unicodeCodePoints",
+				"results": self.sarif_results,
+			}]
+		})
+	}
+}
+
+pub fn print_olympus_error(err: OlympusError) {
+	let _ = write_olympus_error(err, std::io::stderr(), true);
+}
+
+/// Writes the same report [`print_olympus_error`] prints, `color` controls whether it contains ANSI escape codes.
+pub fn write_olympus_error(err: OlympusError, mut out: impl Write, color: bool) -> std::io::Result<()> {
+	let kind = match err.severity {
+		Severity::Error => ReportKind::Error,
+		Severity::Warning => ReportKind::Warning,
+	};
+
+	let Some(first) = err.labels.first() else {
+		// like ariadne puts the code in front
+		return match err.code {
+			Some(code) => writeln!(out, "[{code}] {kind}: {}", err.subject),
+			None => writeln!(out, "{kind}: {}", err.subject),
+		};
+	};
+
+	let filename = first.source.file_name.clone();
+	let mut lowest_start = usize::MAX;
+	for label in err.labels.iter().filter(|label| label.source.file_name == filename) {
+		if label.span.start < lowest_start {
+			lowest_start = label.span.start;
+		}
+	}
+
+	let files = err
+		.labels
+		.iter()
+		.map(|label| (label.source.file_name.clone(), label.source.src.clone()))
+		.collect::<Vec<_>>();
+
+	let labels = err
+		.labels
+		.into_iter()
+		.map(|label| {
+			Label::new((label.source.file_name.clone(), label.span))
+				.with_message(label.message)
+				.with_color(label.color)
+		})
+		.collect::<Vec<_>>();
+
+	let mut report = Report::build(kind, filename, lowest_start)
+		.with_config(Config::default().with_color(color))
+		.with_message(err.subject)
+		.with_labels(labels);
+	if let Some(code) = err.code {
+		report = report.with_code(code);
+	}
+	if let Some(lint) = err.lint {
+		let level = match err.severity {
+			Severity::Error => "deny",
+			Severity::Warning => "warn",
+		};
+		report = report.with_note(format!("Lint '{lint}' is set to {level}"));
+	}
+
+	report.finish().write(sources(files), out)
+}
+
+/// 1-based line and column (in characters) of a byte offset.
+fn line_column(src: &str, offset: usize) -> (usize, usize) {
+	let offset = offset.min(src.len());
+	let before = src.get(..offset).unwrap_or(src);
+	let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+	let line = before.matches('\n').count() + 1;
+	let column = before[line_start..].chars().count() + 1;
+	(line, column)
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+	match severity {
+		Severity::Error => "error",
+		Severity::Warning => "warning",
+	}
+}
+
+fn label_to_json(label: &OlympusErrorLabel) -> Value {
+	let (start_line, start_column) = line_column(&label.source.src, label.span.start);
+	let (end_line, end_column) = line_column(&label.source.src, label.span.end);
+	json!({
+		"file": label.source.file_name,
+		"message": label.message,
+		"byte_start": label.span.start,
+		"byte_end": label.span.end,
+		"line_start": start_line,
+		"column_start": start_column,
+		"line_end": end_line,
+		"column_end": end_column,
+	})
+}
+
+fn diagnostic_to_json(err: &OlympusError) -> Value {
+	json!({
+		"severity": severity_name(err.severity),
+		"code": err.code.map(ErrorCode::code),
+		"lint": err.lint,
+		"message": err.subject,
+		"labels": err.labels.iter().map(label_to_json).collect::<Vec<_>>(),
+	})
+}
+
+fn label_to_sarif_location(label: &OlympusErrorLabel) -> Value {
+	let (start_line, start_column) = line_column(&label.source.src, label.span.start);
+	let (end_line, end_column) = line_column(&label.source.src, label.span.end);
+	json!({
+		"physicalLocation": {
+			"artifactLocation": { "uri": label.source.file_name },
+			"region": {
+				"startLine": start_line,
+				"startColumn": start_column,
+				"endLine": end_line,
+				"endColumn": end_column,
+				"byteOffset": label.span.start,
+				"byteLength": label.span.len(),
+			}
+		},
+		"message": { "text": label.message },
+	})
+}
+
+fn diagnostic_to_sarif(err: &OlympusError) -> Value {
+	// the red label is the one pointing at the problem, the others give context
+	let primary = err
+		.labels
+		.iter()
+		.position(|label| label.color == ErrorColor::Red)
+		.unwrap_or(0);
+
+	let (locations, related_locations): (Vec<_>, Vec<_>) =
+		err.labels.iter().enumerate().partition(|(idx, _)| *idx == primary);
+	let mut result = json!({
+		"level": severity_name(err.severity),
+		"message": { "text": err.subject },
+		"locations": locations.into_iter().map(|(_, label)| label_to_sarif_location(label)).collect::<Vec<_>>(),
+		"relatedLocations": related_locations
+			.into_iter()
+			.map(|(_, label)| label_to_sarif_location(label))
+			.collect::<Vec<_>>(),
+	});
+
+	if let Some(code) = err.code {
+		result["ruleId"] = json!(code.code());
+	}
+
+	result
+}
+
+fn code_to_sarif_rule(code: ErrorCode) -> Value {
+	json!({
+		"id": code.code(),
+		"shortDescription": { "text": code.title() },
+		"fullDescription": { "text": code.explanation() },
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use olympus_spanned::{ErrorCode, OlympusError};
+	use olympus_verifier::LintConfig;
+	use serde_json::{json, Value};
+
+	use super::{diagnostic_to_json, DiagnosticEmitter, MessageFormat};
+	use crate::{loader::MemoryFileSystem, verify_schema_from};
+
+	fn diagnostics(src: &str) -> Vec<OlympusError> {
+		let mut fs = MemoryFileSystem::new();
+		fs.insert("test.ol", src);
+		verify_schema_from(&fs, Path::new("test.ol"), &[], &LintConfig::default()).diagnostics
+	}
+
+	fn rule(code: &str) -> Value {
+		let code = ErrorCode::from_code(code).unwrap();
+		json!({
+			"id": code.code(),
+			"shortDescription": { "text": code.title() },
+			"fullDescription": { "text": code.explanation() },
+		})
+	}
+
+	fn location(message: &str, line: usize, columns: (usize, usize), bytes: (usize, usize)) -> Value {
+		json!({
+			"physicalLocation": {
+				"artifactLocation": { "uri": "test.ol" },
+				"region": {
+					"startLine": line,
+					"startColumn": columns.0,
+					"endLine": line,
+					"endColumn": columns.1,
+					"byteOffset": bytes.0,
+					"byteLength": bytes.1,
+				}
+			},
+			"message": { "text": message },
+		})
+	}
+
+	#[test]
+	fn json_diagnostics() {
+		// lints only run on schemas that verify
+		let json = diagnostics("struct unused {\n    x->@int8;\n}\nstruct A {\n    b->Missing;\n}\n")
+			.iter()
+			.map(diagnostic_to_json)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			json,
+			[json!({
+				"severity": "error",
+				"code": "OL0016",
+				"lint": null,
+				"message": "Type 'Missing' not found",
+				"labels": [{
+					"file": "test.ol",
+					"message": "Type 'Missing' not found",
+					"byte_start": 50,
+					"byte_end": 57,
+					"line_start": 5,
+					"column_start": 8,
+					"line_end": 5,
+					"column_end": 15,
+				}],
+			})]
+		);
+
+		let json = diagnostics("struct unused {\n    x->@int8;\n}\n")
+			.iter()
+			.map(diagnostic_to_json)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			json,
+			[
+				json!({
+					"severity": "warning",
+					"code": "OL0103",
+					"lint": "non_pascal_case_name",
+					"message": "Struct 'unused' should be PascalCase",
+					"labels": [{
+						"file": "test.ol",
+						"message": "Consider renaming it to 'Unused'",
+						"byte_start": 7,
+						"byte_end": 13,
+						"line_start": 1,
+						"column_start": 8,
+						"line_end": 1,
+						"column_end": 14,
+					}],
+				}),
+				json!({
+					"severity": "warning",
+					"code": "OL0101",
+					"lint": "unused_type",
+					"message": "Struct 'unused' is never used",
+					"labels": [{
+						"file": "test.ol",
+						"message": "Declared here",
+						"byte_start": 7,
+						"byte_end": 13,
+						"line_start": 1,
+						"column_start": 8,
+						"line_end": 1,
+						"column_end": 14,
+					}],
+				}),
+			]
+		);
+	}
+
+	#[test]
+	fn sarif_log() {
+		let mut emitter = DiagnosticEmitter::new(MessageFormat::Sarif);
+		let cycle = "struct A { b->B; }\nstruct B { a->A; }\nproc Get() -> A;\n";
+		// the rule is only listed once, however often it fires
+		for diagnostic in diagnostics(cycle).into_iter().chain(diagnostics(cycle)) {
+			emitter.emit(diagnostic);
+		}
+		for diagnostic in diagnostics("struct Unused { x->@int8; }") {
+			emitter.emit(diagnostic);
+		}
+
+		let cycle = json!({
+			"level": "error",
+			"message": { "text": "Recursive type has infinite size: A -> B -> A" },
+			"locations": [location(
+				"'A' contains 'B' here, wrap it in @option or @array to break the cycle",
+				1,
+				(15, 16),
+				(14, 1),
+			)],
+			"relatedLocations": [location("'B' contains 'A' here", 2, (15, 16), (33, 1))],
+			"ruleId": "OL0018",
+		});
+		assert_eq!(
+			emitter.sarif_log(),
+			json!({
+				"$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+				"version": "2.1.0",
+				"runs": [{
+					"tool": {
+						"driver": {
+							"name": "olympusc",
+							"version": env!("CARGO_PKG_VERSION"),
+							"rules": [rule("OL0018"), rule("OL0101")],
+						}
+					},
+					"columnKind": "unicodeCodePoints",
+					"results": [
+						cycle,
+						cycle,
+						{
+							"level": "warning",
+							"message": { "text": "Struct 'Unused' is never used" },
+							"locations": [location("Declared here", 1, (8, 14), (7, 6))],
+							"relatedLocations": [],
+							"ruleId": "OL0101",
+						},
+					],
+				}]
+			})
+		);
+	}
+}
@@ -0,0 +1,830 @@
+use std::{collections::HashSet, fmt::Write};
+
+use crate::naming::NamingConventionConfig;
+use heck::AsShoutySnakeCase;
+use olympus_net_common::fnv;
+use olympus_verifier::{
+	IntKind, ResolvedEnum, ResolvedProcedure, ResolvedSchema, ResolvedStruct, ResolvedType, StructId,
+};
+
+use super::CodeGenerator;
+
+pub struct CCodeGenerator;
+
+const RUNTIME_DECLARATIONS: &str = include_str!("runtime/olympus.h");
+const RUNTIME_IMPLEMENTATION: &str = include_str!("runtime/olympus.c");
+
+/// A struct or procedure params, both become a C struct with an encode and decode function.
+struct Record<'a> {
+	ident: String,
+	fields: Vec<(String, &'a ResolvedType)>,
+}
+
+/// Arrays and options don't have a name in the schema, so they become structs named after what they contain, e.g.
+/// `olympus_array_option_u8`. The same goes for structs referenced through a pointer to break recursion.
+struct Composites<'a> {
+	seen: HashSet<&'a ResolvedType>,
+	kinds: Vec<&'a ResolvedType>,
+}
+
+impl<'a> Composites<'a> {
+	fn collect(&mut self, kind: &'a ResolvedType) {
+		match kind {
+			ResolvedType::Array(ty) | ResolvedType::Option(ty) => self.collect(ty),
+			ResolvedType::Struct { indirect: true, .. } => {}
+			_ => return,
+		}
+
+		if self.seen.insert(kind) {
+			self.kinds.push(kind);
+		}
+	}
+}
+
+impl CCodeGenerator {
+	fn type_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		CCodeGenerator.escape_ident(&naming_convention_config.apply_types(ident))
+	}
+
+	/// Constants share a namespace with everything else, so variants are prefixed with their enum.
+	fn variant_ident(naming_convention_config: &NamingConventionConfig, enum_ident: &str, ident: &str) -> String {
+		format!(
+			"{}_{}",
+			AsShoutySnakeCase(enum_ident),
+			naming_convention_config.apply_enum_variants(ident)
+		)
+	}
+
+	fn field_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		CCodeGenerator.escape_ident(&naming_convention_config.apply_struct_fields(ident))
+	}
+
+	fn proc_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		CCodeGenerator.escape_ident(&naming_convention_config.apply_procs(ident))
+	}
+
+	fn proc_hash_ident(ident: &str) -> String {
+		format!("OLYMPUS_PROC_{}", AsShoutySnakeCase(ident))
+	}
+
+	fn int_ident(kind: IntKind) -> &'static str {
+		match kind {
+			IntKind::Int8 => "i8",
+			IntKind::Int16 => "i16",
+			IntKind::Int32 => "i32",
+			IntKind::Int64 => "i64",
+			IntKind::UInt8 => "u8",
+			IntKind::UInt16 => "u16",
+			IntKind::UInt32 => "u32",
+			IntKind::UInt64 => "u64",
+		}
+	}
+
+	fn int_type(kind: IntKind) -> &'static str {
+		match kind {
+			IntKind::Int8 => "int8_t",
+			IntKind::Int16 => "int16_t",
+			IntKind::Int32 => "int32_t",
+			IntKind::Int64 => "int64_t",
+			IntKind::UInt8 => "uint8_t",
+			IntKind::UInt16 => "uint16_t",
+			IntKind::UInt32 => "uint32_t",
+			IntKind::UInt64 => "uint64_t",
+		}
+	}
+
+	/// The suffix of the functions encoding and decoding `kind`, e.g. `olympus_write_vu32` or `olympus_encode_File`.
+	///
+	/// Variable and fixed width integers share a C type but not an encoding, so they need different names.
+	fn codec(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) => Self::int_ident(*int).to_string(),
+			ResolvedType::VariableInt(int) => format!("v{}", Self::int_ident(*int)),
+			ResolvedType::String => "string".to_string(),
+			ResolvedType::Array(ty) => format!("array_{}", Self::codec(schema, ty, naming_convention_config)),
+			ResolvedType::Option(ty) => format!("option_{}", Self::codec(schema, ty, naming_convention_config)),
+			ResolvedType::Struct { indirect: true, .. } => {
+				format!("ref_{}", Self::declared_ident(schema, kind, naming_convention_config))
+			}
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => {
+				Self::declared_ident(schema, kind, naming_convention_config)
+			}
+		}
+	}
+
+	fn declared_ident(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Enum(id) => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			ResolvedType::Struct { id, .. } => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			_ => unreachable!("only enums and structs are declared"),
+		}
+	}
+
+	fn resolved_type_to_c(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) | ResolvedType::VariableInt(int) => Self::int_type(*int).to_string(),
+			ResolvedType::String => "olympus_string".to_string(),
+			ResolvedType::Array(_) | ResolvedType::Option(_) => {
+				format!("olympus_{}", Self::codec(schema, kind, naming_convention_config))
+			}
+			ResolvedType::Struct { indirect: true, .. } => {
+				format!("{} *", Self::declared_ident(schema, kind, naming_convention_config))
+			}
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => {
+				Self::declared_ident(schema, kind, naming_convention_config)
+			}
+		}
+	}
+
+	/// Declares `ident` with the type of `kind`, without a space after pointers.
+	fn declaration(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		ident: &str,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		let ty = Self::resolved_type_to_c(schema, kind, naming_convention_config);
+		if ty.ends_with('*') {
+			format!("{ty}{ident}")
+		} else {
+			format!("{ty} {ident}")
+		}
+	}
+
+	/// The casts from the arena's `void *` keep the implementation compiling as C++.
+	fn pointer_to(ty: &str) -> String {
+		if ty.ends_with('*') {
+			format!("{ty}*")
+		} else {
+			format!("{ty} *")
+		}
+	}
+
+	fn is_primitive(kind: &ResolvedType) -> bool {
+		matches!(
+			kind,
+			ResolvedType::Int(_) | ResolvedType::VariableInt(_) | ResolvedType::String
+		)
+	}
+
+	fn address_of(lvalue: &str) -> String {
+		lvalue
+			.strip_prefix('*')
+			.map_or_else(|| format!("&{lvalue}"), ToString::to_string)
+	}
+
+	/// A call writing `lvalue` to the `olympus_writer` in scope as `w`.
+	fn encode_call(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		lvalue: &str,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		let codec = Self::codec(schema, kind, naming_convention_config);
+		if Self::is_primitive(kind) {
+			format!("olympus_write_{codec}(w, {lvalue})")
+		} else {
+			format!("olympus_encode_{codec}(w, {})", Self::address_of(lvalue))
+		}
+	}
+
+	/// A call reading into `lvalue` from the `olympus_reader` in scope as `r`.
+	fn decode_call(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		lvalue: &str,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		let codec = Self::codec(schema, kind, naming_convention_config);
+		let function = if Self::is_primitive(kind) { "read" } else { "decode" };
+		format!("olympus_{function}_{codec}(r, {})", Self::address_of(lvalue))
+	}
+
+	fn records<'a>(schema: &'a ResolvedSchema, naming_convention_config: &NamingConventionConfig) -> Vec<Record<'a>> {
+		let structs = schema.structs.iter().map(|r#struct| Record {
+			ident: Self::type_ident(naming_convention_config, &r#struct.ident.value),
+			fields: r#struct
+				.fields
+				.iter()
+				.map(|field| {
+					(
+						Self::field_ident(naming_convention_config, &field.ident.value),
+						&field.kind.value,
+					)
+				})
+				.collect(),
+		});
+		let params = schema
+			.procedures
+			.iter()
+			.filter(|proc| !proc.params.is_empty())
+			.map(|proc| Record {
+				ident: CCodeGenerator.procedure_params_ident(proc, naming_convention_config),
+				fields: proc
+					.params
+					.iter()
+					.map(|param| {
+						(
+							Self::field_ident(naming_convention_config, &param.ident.value),
+							&param.kind.value,
+						)
+					})
+					.collect(),
+			});
+
+		structs.chain(params).collect()
+	}
+
+	fn generate_record_definition(
+		schema: &ResolvedSchema,
+		record: &Record,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let fields = if record.fields.is_empty() {
+			"\t/* C doesn't allow empty structs */\n\tuint8_t unused;".to_string()
+		} else {
+			record
+				.fields
+				.iter()
+				.map(|(field, kind)| {
+					format!(
+						"\t{};",
+						Self::declaration(schema, kind, field, naming_convention_config)
+					)
+				})
+				.collect::<Vec<String>>()
+				.join("\n")
+		};
+
+		write!(output, "\nstruct {} {{\n{fields}\n}};\n", record.ident).unwrap();
+	}
+
+	/// Structs hold other structs and options by value, so those have to be defined first.
+	///
+	/// Recursion always goes through an array or a pointer, which only need the forward declaration.
+	fn generate_definition_of(
+		schema: &ResolvedSchema,
+		records: &[Record],
+		kind: &ResolvedType,
+		defined: &mut HashSet<String>,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let codec = Self::codec(schema, kind, naming_convention_config);
+		match kind {
+			ResolvedType::Struct { id, indirect: false } => {
+				if !defined.insert(codec) {
+					return;
+				}
+
+				let record = &records[id.0];
+				for (_, field) in &record.fields {
+					Self::generate_definition_of(schema, records, field, defined, output, naming_convention_config);
+				}
+				Self::generate_record_definition(schema, record, output, naming_convention_config);
+			}
+			ResolvedType::Option(ty) => {
+				Self::generate_definition_of(schema, records, ty, defined, output, naming_convention_config);
+				if defined.insert(codec.clone()) {
+					write!(
+						output,
+						"\nstruct olympus_{codec} {{\n\tbool present;\n\t{};\n}};\n",
+						Self::declaration(schema, ty, "value", naming_convention_config)
+					)
+					.unwrap();
+				}
+			}
+			ResolvedType::Array(ty) => {
+				let items = Self::pointer_to(&Self::resolved_type_to_c(schema, ty, naming_convention_config));
+				if defined.insert(codec.clone()) {
+					write!(
+						output,
+						"\nstruct olympus_{codec} {{\n\t{items}items;\n\tuint32_t len;\n}};\n"
+					)
+					.unwrap();
+				}
+			}
+			_ => {}
+		}
+	}
+
+	fn generate_record_codec(
+		schema: &ResolvedSchema,
+		record: &Record,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let ident = &record.ident;
+		let (encode_fields, decode_fields) = if record.fields.is_empty() {
+			(
+				"\t(void)w;\n\t(void)value;".to_string(),
+				"\t(void)r;\n\t(void)out;".to_string(),
+			)
+		} else {
+			let encode = record
+				.fields
+				.iter()
+				.map(|(field, kind)| {
+					format!(
+						"\tOLYMPUS_TRY({});",
+						Self::encode_call(schema, kind, &format!("value->{field}"), naming_convention_config)
+					)
+				})
+				.collect::<Vec<String>>()
+				.join("\n");
+			let decode = record
+				.fields
+				.iter()
+				.map(|(field, kind)| {
+					format!(
+						"\tOLYMPUS_TRY({});",
+						Self::decode_call(schema, kind, &format!("out->{field}"), naming_convention_config)
+					)
+				})
+				.collect::<Vec<String>>()
+				.join("\n");
+			(encode, decode)
+		};
+
+		write!(
+			output,
+			"
+olympus_result olympus_encode_{ident}(olympus_writer *w, const {ident} *value) {{
+{encode_fields}
+	return OLYMPUS_OK;
+}}
+
+olympus_result olympus_decode_{ident}(olympus_reader *r, {ident} *out) {{
+{decode_fields}
+	return OLYMPUS_OK;
+}}
+"
+		)
+		.unwrap();
+	}
+
+	fn composite_signatures(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> (String, String) {
+		let codec = Self::codec(schema, kind, naming_convention_config);
+		let ty = Self::resolved_type_to_c(schema, kind, naming_convention_config);
+		let (value, out) = if ty.ends_with('*') {
+			(format!("{ty}const *value"), format!("{ty}*out"))
+		} else {
+			(format!("const {ty} *value"), format!("{ty} *out"))
+		};
+
+		(
+			format!("static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_{codec}(olympus_writer *w, {value})"),
+			format!("static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_{codec}(olympus_reader *r, {out})"),
+		)
+	}
+
+	fn generate_composite_codec(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let (encode_signature, decode_signature) = Self::composite_signatures(schema, kind, naming_convention_config);
+		let (encode, decode) = match kind {
+			ResolvedType::Array(ty) => (
+				format!(
+					"\tOLYMPUS_TRY(olympus_write_u32(w, value->len));
+	for (uint32_t idx = 0; idx < value->len; idx++) {{
+		OLYMPUS_TRY({});
+	}}",
+					Self::encode_call(schema, ty, "value->items[idx]", naming_convention_config)
+				),
+				format!(
+					"\tvoid *items;
+	OLYMPUS_TRY(olympus_read_u32(r, &out->len));
+	OLYMPUS_TRY(olympus_arena_alloc(r, sizeof(*out->items), out->len, &items));
+	out->items = ({})items;
+	for (uint32_t idx = 0; idx < out->len; idx++) {{
+		OLYMPUS_TRY({});
+	}}",
+					Self::pointer_to(&Self::resolved_type_to_c(schema, ty, naming_convention_config)),
+					Self::decode_call(schema, ty, "out->items[idx]", naming_convention_config)
+				),
+			),
+			ResolvedType::Option(ty) => (
+				format!(
+					"\tOLYMPUS_TRY(olympus_write_u8(w, value->present ? 1 : 0));
+	if (value->present) {{
+		OLYMPUS_TRY({});
+	}}",
+					Self::encode_call(schema, ty, "value->value", naming_convention_config)
+				),
+				format!(
+					"\tuint8_t present;
+	OLYMPUS_TRY(olympus_read_u8(r, &present));
+	memset(out, 0, sizeof(*out));
+	out->present = present != 0;
+	if (out->present) {{
+		OLYMPUS_TRY({});
+	}}",
+					Self::decode_call(schema, ty, "out->value", naming_convention_config)
+				),
+			),
+			ResolvedType::Struct { indirect: true, .. } => {
+				let ident = Self::declared_ident(schema, kind, naming_convention_config);
+				(
+					format!("\tOLYMPUS_TRY(olympus_encode_{ident}(w, *value));"),
+					format!(
+						"\tvoid *item;
+	OLYMPUS_TRY(olympus_arena_alloc(r, sizeof(**out), 1, &item));
+	*out = ({ident} *)item;
+	OLYMPUS_TRY(olympus_decode_{ident}(r, *out));"
+					),
+				)
+			}
+			_ => unreachable!("only arrays, options and indirect structs are composites"),
+		};
+
+		write!(
+			output,
+			"
+{encode_signature} {{
+{encode}
+	return OLYMPUS_OK;
+}}
+
+{decode_signature} {{
+{decode}
+	return OLYMPUS_OK;
+}}
+"
+		)
+		.unwrap();
+	}
+
+	fn generate_enum_codec(
+		resolved: &ResolvedEnum,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let ident = Self::type_ident(naming_convention_config, &resolved.ident.value);
+		let cases = if resolved.variants.is_empty() {
+			"\t(void)out;\n\tswitch (tag) {\n".to_string()
+		} else {
+			let cases = resolved
+				.variants
+				.iter()
+				.map(|variant| {
+					format!(
+						"\tcase {}:",
+						Self::variant_ident(naming_convention_config, &ident, &variant.ident.value)
+					)
+				})
+				.collect::<Vec<String>>()
+				.join("\n");
+			format!("\tswitch (tag) {{\n{cases}\n\t\t*out = tag;\n\t\treturn OLYMPUS_OK;\n")
+		};
+
+		write!(
+			output,
+			"
+olympus_result olympus_encode_{ident}(olympus_writer *w, const {ident} *value) {{
+	return olympus_write_u16(w, *value);
+}}
+
+olympus_result olympus_decode_{ident}(olympus_reader *r, {ident} *out) {{
+	uint16_t tag;
+	OLYMPUS_TRY(olympus_read_u16(r, &tag));
+{cases}\tdefault:
+		return OLYMPUS_ERR_INVALID_DATA;
+	}}
+}}
+"
+		)
+		.unwrap();
+	}
+
+	fn codec_prototypes(ident: &str) -> String {
+		format!(
+			"olympus_result olympus_encode_{ident}(olympus_writer *w, const {ident} *value);
+olympus_result olympus_decode_{ident}(olympus_reader *r, {ident} *out);"
+		)
+	}
+}
+
+impl CodeGenerator for CCodeGenerator {
+	fn reserved_words(&self) -> &'static [&'static str] {
+		&[
+			"auto",
+			"break",
+			"case",
+			"char",
+			"const",
+			"continue",
+			"default",
+			"do",
+			"double",
+			"else",
+			"enum",
+			"extern",
+			"float",
+			"for",
+			"goto",
+			"if",
+			"inline",
+			"int",
+			"long",
+			"register",
+			"restrict",
+			"return",
+			"short",
+			"signed",
+			"sizeof",
+			"static",
+			"struct",
+			"switch",
+			"typedef",
+			"union",
+			"unsigned",
+			"void",
+			"volatile",
+			"while",
+			"bool",
+			"true",
+			"false",
+			"NULL",
+			// C++ keywords, so the header can be included from C++
+			"class",
+			"delete",
+			"explicit",
+			"friend",
+			"mutable",
+			"namespace",
+			"new",
+			"operator",
+			"private",
+			"protected",
+			"public",
+			"template",
+			"this",
+			"throw",
+			"try",
+			"catch",
+			"typename",
+			"using",
+			"virtual",
+		]
+	}
+
+	fn generate_file_header(&self, output: &mut String) {
+		output.push_str("#pragma once\n\n");
+		output.push_str(RUNTIME_DECLARATIONS);
+		output.push_str("\n#ifdef __cplusplus\nextern \"C\" {\n#endif\n");
+	}
+
+	fn generate_file_footer(&self, output: &mut String) {
+		output.push_str("\n#ifdef __cplusplus\n}\n#endif\n");
+	}
+
+	/// Orders everything the way C needs it: forward declarations, definitions once the types they hold by value are
+	/// complete, then the codec prototypes and finally their implementation.
+	fn generate_models(
+		&self,
+		schema: &ResolvedSchema,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		for r#enum in &schema.enums {
+			self.generate_enum(r#enum, output, naming_convention_config);
+		}
+
+		let records = Self::records(schema, naming_convention_config);
+		let mut composites = Composites {
+			seen: HashSet::new(),
+			kinds: Vec::new(),
+		};
+		for (_, kind) in records.iter().flat_map(|record| &record.fields) {
+			composites.collect(kind);
+		}
+		for proc in &schema.procedures {
+			if let Some(kind) = &proc.return_kind {
+				composites.collect(&kind.value);
+			}
+		}
+
+		let mut forward = records
+			.iter()
+			.map(|record| format!("typedef struct {0} {0};", record.ident))
+			.collect::<Vec<String>>();
+		forward.extend(
+			composites
+				.kinds
+				.iter()
+				.filter(|kind| matches!(kind, ResolvedType::Array(_) | ResolvedType::Option(_)))
+				.map(|kind| {
+					format!(
+						"typedef struct olympus_{0} olympus_{0};",
+						Self::codec(schema, kind, naming_convention_config)
+					)
+				}),
+		);
+		if !forward.is_empty() {
+			write!(output, "\n{}\n", forward.join("\n")).unwrap();
+		}
+
+		let mut defined = HashSet::new();
+		for r#struct in &schema.structs {
+			self.generate_struct(schema, r#struct, output, naming_convention_config);
+		}
+		for (idx, _) in schema.structs.iter().enumerate() {
+			Self::generate_definition_of(
+				schema,
+				&records,
+				&ResolvedType::Struct {
+					id: StructId(idx),
+					indirect: false,
+				},
+				&mut defined,
+				output,
+				naming_convention_config,
+			);
+		}
+		for kind in &composites.kinds {
+			Self::generate_definition_of(schema, &records, kind, &mut defined, output, naming_convention_config);
+		}
+		for record in &records[schema.structs.len()..] {
+			for (_, kind) in &record.fields {
+				Self::generate_definition_of(schema, &records, kind, &mut defined, output, naming_convention_config);
+			}
+			Self::generate_record_definition(schema, record, output, naming_convention_config);
+		}
+
+		let prototypes = schema
+			.enums
+			.iter()
+			.map(|r#enum| Self::type_ident(naming_convention_config, &r#enum.ident.value))
+			.chain(records.iter().map(|record| record.ident.clone()))
+			.map(|ident| Self::codec_prototypes(&ident))
+			.collect::<Vec<String>>()
+			.join("\n");
+		if !prototypes.is_empty() {
+			write!(output, "\n{prototypes}\n").unwrap();
+		}
+
+		output.push_str("\n#ifdef OLYMPUS_IMPLEMENTATION\n\n");
+		output.push_str(RUNTIME_IMPLEMENTATION);
+
+		let composite_prototypes = composites
+			.kinds
+			.iter()
+			.map(|kind| {
+				let (encode, decode) = Self::composite_signatures(schema, kind, naming_convention_config);
+				format!("{encode};\n{decode};")
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+		if !composite_prototypes.is_empty() {
+			write!(output, "\n{composite_prototypes}\n").unwrap();
+		}
+
+		for r#enum in &schema.enums {
+			Self::generate_enum_codec(r#enum, output, naming_convention_config);
+		}
+		for record in &records {
+			Self::generate_record_codec(schema, record, output, naming_convention_config);
+		}
+		for kind in &composites.kinds {
+			Self::generate_composite_codec(schema, kind, output, naming_convention_config);
+		}
+
+		output.push_str("\n#endif /* OLYMPUS_IMPLEMENTATION */\n");
+	}
+
+	fn generate_enum(
+		&self,
+		resolved: &ResolvedEnum,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let ident = Self::type_ident(naming_convention_config, &resolved.ident.value);
+		// the enum itself stays anonymous so values have the same size as on the wire
+		let variants = resolved
+			.variants
+			.iter()
+			.map(|variant| {
+				format!(
+					"\t{} = {},",
+					Self::variant_ident(naming_convention_config, &ident, &variant.ident.value),
+					variant.value
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		write!(output, "\ntypedef uint16_t {ident};\n").unwrap();
+		if !variants.is_empty() {
+			write!(output, "enum {{\n{variants}\n}};\n").unwrap();
+		}
+	}
+
+	/// Structs are defined by [`CodeGenerator::generate_models`], which has to order them by their dependencies.
+	fn generate_struct(
+		&self,
+		_schema: &ResolvedSchema,
+		_resolved: &ResolvedStruct,
+		_output: &mut String,
+		_naming_convention_config: &NamingConventionConfig,
+	) {
+	}
+
+	/// Procedure params are defined by [`CodeGenerator::generate_models`] together with the structs.
+	fn generate_procedure_params(
+		&self,
+		_schema: &ResolvedSchema,
+		_resolved: &ResolvedProcedure,
+		_output: &mut String,
+		_naming_convention_config: &NamingConventionConfig,
+	) {
+	}
+
+	/// There is no socket to wrap on bare metal, so instead of a client every procedure gets a function writing its
+	/// request frame and one decoding the payload of its response frame.
+	fn generate_client_impl(
+		&self,
+		schema: &ResolvedSchema,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let mut declarations = Vec::new();
+		let mut definitions = Vec::new();
+		for proc in &schema.procedures {
+			let name = &proc.ident.value;
+			let ident = Self::proc_ident(naming_convention_config, name);
+			let hash_ident = Self::proc_hash_ident(name);
+			declarations.push(format!("\n#define {hash_ident} UINT64_C({:#018x})", fnv(name)));
+
+			let (params, encode_params) = if proc.params.is_empty() {
+				(String::new(), String::new())
+			} else {
+				let params_ident = self.procedure_params_ident(proc, naming_convention_config);
+				(
+					format!(", const {params_ident} *params"),
+					format!("\tOLYMPUS_TRY(olympus_encode_{params_ident}(w, params));\n"),
+				)
+			};
+			let request = format!("olympus_result olympus_request_{ident}(olympus_writer *w{params})");
+			declarations.push(format!("/* Writes the request frame of `{name}`. */\n{request};"));
+			definitions.push(format!(
+				"
+{request} {{
+	size_t start;
+	OLYMPUS_TRY(olympus_begin_request(w, {hash_ident}, &start));
+{encode_params}	return olympus_end_request(w, start);
+}}"
+			));
+
+			// the server doesn't respond to procedures without a return value
+			let Some(kind) = &proc.return_kind else {
+				continue;
+			};
+			let response = format!(
+				"olympus_result olympus_response_{ident}(olympus_reader *r, {})",
+				Self::declaration(schema, &kind.value, "*out", naming_convention_config)
+			);
+			declarations.push(format!(
+				"/* Decodes the payload of a response frame of `{name}`, see olympus_read_frame. */\n{response};"
+			));
+			definitions.push(format!(
+				"
+{response} {{
+	return {};
+}}",
+				Self::decode_call(schema, &kind.value, "*out", naming_convention_config)
+			));
+		}
+
+		if declarations.is_empty() {
+			return;
+		}
+
+		write!(
+			output,
+			"{}\n\n#ifdef OLYMPUS_IMPLEMENTATION\n{}\n\n#endif /* OLYMPUS_IMPLEMENTATION */\n",
+			declarations.join("\n"),
+			definitions.join("\n")
+		)
+		.unwrap();
+	}
+}
@@ -0,0 +1,169 @@
+use std::{collections::HashMap, sync::Arc};
+
+use olympus_spanned::{CodeSource, ErrorCode, ErrorColor, OlympusError, Spanned};
+use olympus_verifier::ResolvedSchema;
+
+use super::CodeGenerator;
+use crate::naming::NamingConventionConfig;
+
+struct GeneratedName<'a> {
+	original: &'a Spanned<String>,
+	source: &'a Arc<CodeSource>,
+	/// Overrides the label of the original ident, for names the generator derives from it.
+	note: Option<String>,
+}
+
+fn find_collision<'a>(
+	kind: &str,
+	names: impl Iterator<Item = (String, GeneratedName<'a>)>,
+) -> Result<(), OlympusError> {
+	let mut seen = HashMap::<String, GeneratedName>::new();
+	for (generated, name) in names {
+		let Some(original) = seen.get(&generated) else {
+			seen.insert(generated, name);
+			continue;
+		};
+
+		let message = |name: &GeneratedName| {
+			name.note.clone().unwrap_or_else(|| {
+				if name.original.value == generated {
+					"Declared here".to_string()
+				} else {
+					format!("'{}' becomes '{generated}'", name.original.value)
+				}
+			})
+		};
+
+		return Err(OlympusError::new(&format!(
+			"Generated {kind} name '{generated}' collides after applying naming conventions"
+		))
+		.label(
+			original.source.clone(),
+			&message(original),
+			original.original.span.clone(),
+			ErrorColor::Yellow,
+		)
+		.label(
+			name.source.clone(),
+			&message(&name),
+			name.original.span.clone(),
+			ErrorColor::Red,
+		)
+		.code(ErrorCode::NameCollision));
+	}
+
+	Ok(())
+}
+
+/// Checks that no two schema idents end up with the same name in the generated code.
+///
+/// Idents that are unique in the schema can still collide once naming conventions and escaping are applied, for
+/// example `fooBar` and `foo_bar` both become `foo_bar` in snake case.
+pub fn check_name_collisions(
+	generator: &dyn CodeGenerator,
+	schema: &ResolvedSchema,
+	naming_convention_config: &NamingConventionConfig,
+) -> Result<(), OlympusError> {
+	let escape = |name: String| generator.escape_ident(&name);
+	let escape_member = |name: String| generator.escape_member(&name);
+
+	let enum_types = schema.enums.iter().map(|r#enum| {
+		(
+			escape(naming_convention_config.apply_types(&r#enum.ident.value)),
+			GeneratedName {
+				original: &r#enum.ident,
+				source: &r#enum.source,
+				note: None,
+			},
+		)
+	});
+	let struct_types = schema.structs.iter().map(|r#struct| {
+		(
+			escape(naming_convention_config.apply_types(&r#struct.ident.value)),
+			GeneratedName {
+				original: &r#struct.ident,
+				source: &r#struct.source,
+				note: None,
+			},
+		)
+	});
+	let params_types = schema
+		.procedures
+		.iter()
+		.filter(|proc| !proc.params.is_empty())
+		.map(|proc| {
+			let generated = generator.procedure_params_ident(proc, naming_convention_config);
+			let note = Some(format!("The params of '{}' generate '{generated}'", proc.ident.value));
+			(
+				generated,
+				GeneratedName {
+					original: &proc.ident,
+					source: &proc.source,
+					note,
+				},
+			)
+		});
+	find_collision("type", enum_types.chain(struct_types).chain(params_types))?;
+
+	for r#enum in &schema.enums {
+		find_collision(
+			"enum variant",
+			r#enum.variants.iter().map(|variant| {
+				(
+					escape_member(naming_convention_config.apply_enum_variants(&variant.ident.value)),
+					GeneratedName {
+						original: &variant.ident,
+						source: &r#enum.source,
+						note: None,
+					},
+				)
+			}),
+		)?;
+	}
+
+	for r#struct in &schema.structs {
+		find_collision(
+			"struct field",
+			r#struct.fields.iter().map(|field| {
+				(
+					escape_member(naming_convention_config.apply_struct_fields(&field.ident.value)),
+					GeneratedName {
+						original: &field.ident,
+						source: &r#struct.source,
+						note: None,
+					},
+				)
+			}),
+		)?;
+	}
+
+	for proc in &schema.procedures {
+		find_collision(
+			"proc param",
+			proc.params.iter().map(|param| {
+				(
+					escape_member(naming_convention_config.apply_struct_fields(&param.ident.value)),
+					GeneratedName {
+						original: &param.ident,
+						source: &proc.source,
+						note: None,
+					},
+				)
+			}),
+		)?;
+	}
+
+	find_collision(
+		"proc",
+		schema.procedures.iter().map(|proc| {
+			(
+				escape_member(naming_convention_config.apply_procs(&proc.ident.value)),
+				GeneratedName {
+					original: &proc.ident,
+					source: &proc.source,
+					note: None,
+				},
+			)
+		}),
+	)
+}
@@ -0,0 +1,472 @@
+use std::fmt::Write;
+
+use crate::naming::NamingConventionConfig;
+use olympus_spanned::Spanned;
+use olympus_verifier::{IntKind, ResolvedEnum, ResolvedProcedure, ResolvedSchema, ResolvedStruct, ResolvedType};
+
+use super::CodeGenerator;
+
+pub struct CSharpCodeGenerator;
+
+const RUNTIME: &str = include_str!("runtime/olympus.cs");
+
+impl CSharpCodeGenerator {
+	fn type_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		CSharpCodeGenerator.escape_ident(&naming_convention_config.apply_types(ident))
+	}
+
+	fn variant_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		CSharpCodeGenerator.escape_ident(&naming_convention_config.apply_enum_variants(ident))
+	}
+
+	fn field_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		CSharpCodeGenerator.escape_ident(&naming_convention_config.apply_struct_fields(ident))
+	}
+
+	fn proc_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		CSharpCodeGenerator.escape_ident(&format!("{}Async", naming_convention_config.apply_procs(ident)))
+	}
+
+	fn int_suffix(kind: IntKind) -> &'static str {
+		match kind {
+			IntKind::Int8 => "I8",
+			IntKind::Int16 => "I16",
+			IntKind::Int32 => "I32",
+			IntKind::Int64 => "I64",
+			IntKind::UInt8 => "U8",
+			IntKind::UInt16 => "U16",
+			IntKind::UInt32 => "U32",
+			IntKind::UInt64 => "U64",
+		}
+	}
+
+	fn int_type(kind: IntKind) -> &'static str {
+		match kind {
+			IntKind::Int8 => "sbyte",
+			IntKind::Int16 => "short",
+			IntKind::Int32 => "int",
+			IntKind::Int64 => "long",
+			IntKind::UInt8 => "byte",
+			IntKind::UInt16 => "ushort",
+			IntKind::UInt32 => "uint",
+			IntKind::UInt64 => "ulong",
+		}
+	}
+
+	fn declared_ident(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Enum(id) => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			ResolvedType::Struct { id, .. } => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			_ => unreachable!("only enums and structs are declared"),
+		}
+	}
+
+	/// Whether `kind` is a struct in C#, which decides if an option of it is a `Nullable<T>`.
+	///
+	/// An option nested in an option is wrapped in `Some<T>`, a struct.
+	fn is_value_type(kind: &ResolvedType) -> bool {
+		matches!(
+			kind,
+			ResolvedType::Int(_) | ResolvedType::VariableInt(_) | ResolvedType::Enum(_) | ResolvedType::Option(_)
+		)
+	}
+
+	fn resolved_type_to_cs(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) | ResolvedType::VariableInt(int) => Self::int_type(*int).to_string(),
+			ResolvedType::String => "string".to_string(),
+			ResolvedType::Array(ty) => format!(
+				"List<{}>",
+				Self::resolved_type_to_cs(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) if matches!(**ty, ResolvedType::Option(_)) => format!(
+				"Some<{}>?",
+				Self::resolved_type_to_cs(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) => format!("{}?", Self::resolved_type_to_cs(schema, ty, naming_convention_config)),
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => {
+				Self::declared_ident(schema, kind, naming_convention_config)
+			}
+		}
+	}
+
+	/// An expression writing `value` to the `Writer` in scope as `w`.
+	///
+	/// `depth` keeps the item names of nested arrays and options apart.
+	fn encode_expr(kind: &ResolvedType, value: &str, depth: usize) -> String {
+		match kind {
+			ResolvedType::Int(int) => format!("w.Write{}({value})", Self::int_suffix(*int)),
+			ResolvedType::VariableInt(int) if int.is_signed() => format!("w.WriteVarInt({value}, {})", int.bits()),
+			ResolvedType::VariableInt(_) => format!("w.WriteVarUint({value})"),
+			ResolvedType::String => format!("w.WriteString({value})"),
+			ResolvedType::Array(ty) => {
+				let item = format!("item{depth}");
+				format!(
+					"w.WriteArray({value}, {item} => {})",
+					Self::encode_expr(ty, &item, depth + 1)
+				)
+			}
+			ResolvedType::Option(ty) => {
+				let item = format!("item{depth}");
+				let (method, inner) = match **ty {
+					ResolvedType::Option(_) => ("WriteNullable", format!("{item}.Value")),
+					_ if Self::is_value_type(ty) => ("WriteNullable", item.clone()),
+					_ => ("WriteOption", item.clone()),
+				};
+				format!(
+					"w.{method}({value}, {item} => {})",
+					Self::encode_expr(ty, &inner, depth + 1)
+				)
+			}
+			ResolvedType::Enum(_) => format!("w.WriteU16((ushort){value})"),
+			ResolvedType::Struct { .. } => format!("{value}.Encode(w)"),
+		}
+	}
+
+	/// An expression reading a value from the `Reader` in scope as `r`.
+	fn decode_expr(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) => format!("r.Read{}()", Self::int_suffix(*int)),
+			ResolvedType::VariableInt(IntKind::Int64) => "r.ReadVarInt(64)".to_string(),
+			ResolvedType::VariableInt(IntKind::UInt64) => "r.ReadVarUint()".to_string(),
+			ResolvedType::VariableInt(int) if int.is_signed() => {
+				format!("({})r.ReadVarInt({})", Self::int_type(*int), int.bits())
+			}
+			ResolvedType::VariableInt(int) => format!("({})r.ReadVarUint()", Self::int_type(*int)),
+			ResolvedType::String => "r.ReadString()".to_string(),
+			ResolvedType::Array(ty) => format!(
+				"r.ReadArray(() => {})",
+				Self::decode_expr(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) => {
+				let inner = Self::decode_expr(schema, ty, naming_convention_config);
+				match **ty {
+					ResolvedType::Option(_) => format!(
+						"r.ReadNullable(() => new Some<{}>({inner}))",
+						Self::resolved_type_to_cs(schema, ty, naming_convention_config)
+					),
+					_ if Self::is_value_type(ty) => format!("r.ReadNullable(() => {inner})"),
+					_ => format!("r.ReadOption(() => {inner})"),
+				}
+			}
+			ResolvedType::Enum(_) => format!(
+				"r.ReadEnum<{}>()",
+				Self::declared_ident(schema, kind, naming_convention_config)
+			),
+			ResolvedType::Struct { .. } => format!(
+				"{}.Decode(r)",
+				Self::declared_ident(schema, kind, naming_convention_config)
+			),
+		}
+	}
+
+	/// A positional record with `Encode` and `Decode` methods, used for structs and procedure params.
+	fn generate_record<'a>(
+		schema: &ResolvedSchema,
+		ident: &str,
+		fields: impl Iterator<Item = (&'a Spanned<String>, &'a ResolvedType)>,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let fields = fields
+			.map(|(field, kind)| (Self::field_ident(naming_convention_config, &field.value), kind))
+			.collect::<Vec<_>>();
+
+		let (decl, encode, decode) = if fields.is_empty() {
+			(
+				format!("public sealed record {ident}"),
+				String::new(),
+				"new()".to_string(),
+			)
+		} else {
+			let params = fields
+				.iter()
+				.map(|(field, kind)| {
+					format!(
+						"{} {field}",
+						Self::resolved_type_to_cs(schema, kind, naming_convention_config)
+					)
+				})
+				.collect::<Vec<String>>()
+				.join(", ");
+			let encode = fields
+				.iter()
+				.map(|(field, kind)| format!("        {};", Self::encode_expr(kind, field, 0)))
+				.collect::<Vec<String>>()
+				.join("\n");
+			// named arguments are evaluated in the order they are written, which is the order on the wire
+			let decode = fields
+				.iter()
+				.map(|(field, kind)| {
+					format!(
+						"        {field}: {}",
+						Self::decode_expr(schema, kind, naming_convention_config)
+					)
+				})
+				.collect::<Vec<String>>()
+				.join(",\n");
+			(
+				format!("public sealed record {ident}({params})"),
+				format!("{encode}\n"),
+				format!("new(\n{decode})"),
+			)
+		};
+
+		write!(
+			output,
+			"
+{decl}
+{{
+    public void Encode(Writer w)
+    {{
+{encode}    }}
+
+    public static {ident} Decode(Reader r) => {decode};
+}}
+"
+		)
+		.unwrap();
+	}
+}
+
+impl CodeGenerator for CSharpCodeGenerator {
+	fn reserved_words(&self) -> &'static [&'static str] {
+		&[
+			"abstract",
+			"as",
+			"base",
+			"bool",
+			"break",
+			"byte",
+			"case",
+			"catch",
+			"char",
+			"checked",
+			"class",
+			"const",
+			"continue",
+			"decimal",
+			"default",
+			"delegate",
+			"do",
+			"double",
+			"else",
+			"enum",
+			"event",
+			"explicit",
+			"extern",
+			"false",
+			"finally",
+			"fixed",
+			"float",
+			"for",
+			"foreach",
+			"goto",
+			"if",
+			"implicit",
+			"in",
+			"int",
+			"interface",
+			"internal",
+			"is",
+			"lock",
+			"long",
+			"namespace",
+			"new",
+			"null",
+			"object",
+			"operator",
+			"out",
+			"override",
+			"params",
+			"private",
+			"protected",
+			"public",
+			"readonly",
+			"record",
+			"ref",
+			"return",
+			"sbyte",
+			"sealed",
+			"short",
+			"sizeof",
+			"stackalloc",
+			"static",
+			"string",
+			"struct",
+			"switch",
+			"this",
+			"throw",
+			"true",
+			"try",
+			"typeof",
+			"uint",
+			"ulong",
+			"unchecked",
+			"unsafe",
+			"ushort",
+			"using",
+			"virtual",
+			"void",
+			"volatile",
+			"while",
+			// the methods of every record and their parameters
+			"Encode",
+			"Decode",
+			"w",
+			"r",
+			// declared by the runtime
+			"OlympusException",
+			"Writer",
+			"Reader",
+			"OlympusConnection",
+			// imported by the runtime and used without type arguments, generic names can't clash
+			"Array",
+			"Enum",
+			"Exception",
+			"Math",
+			"Encoding",
+			"Task",
+			"ValueTask",
+			"CancellationToken",
+		]
+	}
+
+	fn generate_file_header(&self, output: &mut String) {
+		output.push_str(RUNTIME);
+	}
+
+	fn generate_enum(
+		&self,
+		resolved: &ResolvedEnum,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let ident = Self::type_ident(naming_convention_config, &resolved.ident.value);
+		let mut variants = resolved
+			.variants
+			.iter()
+			.map(|variant| {
+				format!(
+					"    {} = {},",
+					Self::variant_ident(naming_convention_config, &variant.ident.value),
+					variant.value
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+		if !variants.is_empty() {
+			variants.push('\n');
+		}
+
+		write!(output, "\npublic enum {ident} : ushort\n{{\n{variants}}}\n").unwrap();
+	}
+
+	fn generate_struct(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedStruct,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		Self::generate_record(
+			schema,
+			&Self::type_ident(naming_convention_config, &resolved.ident.value),
+			resolved.fields.iter().map(|field| (&field.ident, &field.kind.value)),
+			output,
+			naming_convention_config,
+		);
+	}
+
+	fn generate_procedure_params(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedProcedure,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		if resolved.params.is_empty() {
+			return;
+		}
+
+		Self::generate_record(
+			schema,
+			&self.procedure_params_ident(resolved, naming_convention_config),
+			resolved.params.iter().map(|param| (&param.ident, &param.kind.value)),
+			output,
+			naming_convention_config,
+		);
+	}
+
+	fn generate_client_impl(
+		&self,
+		schema: &ResolvedSchema,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let methods = schema
+			.procedures
+			.iter()
+			.map(|proc| {
+				let name = &proc.ident.value;
+				let method = Self::proc_ident(naming_convention_config, name);
+
+				let (params, write) = if proc.params.is_empty() {
+					(String::new(), "_ => { }".to_string())
+				} else {
+					let params_ident = self.procedure_params_ident(proc, naming_convention_config);
+					(format!("{params_ident} @params, "), "@params.Encode".to_string())
+				};
+
+				// the server doesn't respond to procedures without a return value, so there is nothing to wait for
+				match &proc.return_kind {
+					Some(kind) => format!(
+						"
+    /// <summary>Calls <c>{name}</c> and waits for its response.</summary>
+    public Task<{}> {method}({params}CancellationToken cancellationToken = default) =>
+        connection.CallAsync(\"{name}\", {write}, r => {}, cancellationToken);",
+						Self::resolved_type_to_cs(schema, &kind.value, naming_convention_config),
+						Self::decode_expr(schema, &kind.value, naming_convention_config)
+					),
+					None => format!(
+						"
+    /// <summary>Sends <c>{name}</c> without waiting for the server, it doesn't return anything.</summary>
+    public Task {method}({params}CancellationToken cancellationToken = default) =>
+        connection.SendAsync(\"{name}\", {write}, cancellationToken);"
+					),
+				}
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		write!(
+			output,
+			"
+public sealed class {0}
+{{
+    private readonly OlympusConnection connection;
+
+    public {0}(OlympusConnection connection)
+    {{
+        this.connection = connection;
+    }}
+{methods}
+}}
+",
+			naming_convention_config.apply_types("ClientRpc")
+		)
+		.unwrap();
+	}
+}
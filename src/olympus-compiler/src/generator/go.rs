@@ -0,0 +1,458 @@
+use std::fmt::Write;
+
+use crate::naming::NamingConventionConfig;
+use olympus_spanned::Spanned;
+use olympus_verifier::{IntKind, ResolvedEnum, ResolvedProcedure, ResolvedSchema, ResolvedStruct, ResolvedType};
+
+use super::CodeGenerator;
+
+pub struct GoCodeGenerator;
+
+const RUNTIME: &str = include_str!("runtime/olympus.go");
+
+impl GoCodeGenerator {
+	fn type_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		GoCodeGenerator.escape_ident(&naming_convention_config.apply_types(ident))
+	}
+
+	/// Constants share a namespace with everything else in the package, so variants are prefixed with their enum.
+	fn variant_ident(naming_convention_config: &NamingConventionConfig, enum_ident: &str, ident: &str) -> String {
+		format!("{enum_ident}{}", naming_convention_config.apply_enum_variants(ident))
+	}
+
+	fn field_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		GoCodeGenerator.escape_ident(&naming_convention_config.apply_struct_fields(ident))
+	}
+
+	fn proc_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		GoCodeGenerator.escape_ident(&naming_convention_config.apply_procs(ident))
+	}
+
+	fn int_suffix(kind: IntKind) -> &'static str {
+		match kind {
+			IntKind::Int8 => "I8",
+			IntKind::Int16 => "I16",
+			IntKind::Int32 => "I32",
+			IntKind::Int64 => "I64",
+			IntKind::UInt8 => "U8",
+			IntKind::UInt16 => "U16",
+			IntKind::UInt32 => "U32",
+			IntKind::UInt64 => "U64",
+		}
+	}
+
+	fn int_type(kind: IntKind) -> &'static str {
+		match kind {
+			IntKind::Int8 => "int8",
+			IntKind::Int16 => "int16",
+			IntKind::Int32 => "int32",
+			IntKind::Int64 => "int64",
+			IntKind::UInt8 => "uint8",
+			IntKind::UInt16 => "uint16",
+			IntKind::UInt32 => "uint32",
+			IntKind::UInt64 => "uint64",
+		}
+	}
+
+	fn declared_ident(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Enum(id) => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			ResolvedType::Struct { id, .. } => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			_ => unreachable!("only enums and structs are declared"),
+		}
+	}
+
+	fn resolved_type_to_go(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) | ResolvedType::VariableInt(int) => Self::int_type(*int).to_string(),
+			ResolvedType::String => "string".to_string(),
+			ResolvedType::Array(ty) => format!("[]{}", Self::resolved_type_to_go(schema, ty, naming_convention_config)),
+			ResolvedType::Option(ty) => format!("*{}", Self::resolved_type_to_go(schema, ty, naming_convention_config)),
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => {
+				Self::declared_ident(schema, kind, naming_convention_config)
+			}
+		}
+	}
+
+	/// A statement writing `value` to the `Writer` in scope as `w`.
+	///
+	/// `depth` keeps the item names of nested arrays and options apart.
+	fn encode_stmt(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		value: &str,
+		depth: usize,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) => format!("w.Write{}({value})", Self::int_suffix(*int)),
+			ResolvedType::VariableInt(int) if int.is_signed() => {
+				format!("w.WriteVarInt(int64({value}), {})", int.bits())
+			}
+			ResolvedType::VariableInt(_) => format!("w.WriteVarUint(uint64({value}))"),
+			ResolvedType::String => format!("w.WriteString({value})"),
+			ResolvedType::Array(ty) | ResolvedType::Option(ty) => {
+				let function = if matches!(kind, ResolvedType::Array(_)) {
+					"WriteArray"
+				} else {
+					"WriteOption"
+				};
+				let item = format!("item{depth}");
+				format!(
+					"{function}(w, {value}, func({item} {}) {{ {} }})",
+					Self::resolved_type_to_go(schema, ty, naming_convention_config),
+					Self::encode_stmt(schema, ty, &item, depth + 1, naming_convention_config)
+				)
+			}
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => format!("{value}.MarshalOlympus(w)"),
+		}
+	}
+
+	/// An expression reading a value from the `Reader` in scope as `r`, evaluating to the value and an error.
+	fn decode_expr(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) => format!("r.Read{}()", Self::int_suffix(*int)),
+			ResolvedType::VariableInt(int) if int.is_signed() => format!("ReadVarInt[{}](r)", Self::int_type(*int)),
+			ResolvedType::VariableInt(int) => format!("ReadVarUint[{}](r)", Self::int_type(*int)),
+			ResolvedType::String => "r.ReadString()".to_string(),
+			ResolvedType::Array(ty) | ResolvedType::Option(ty) => {
+				let function = if matches!(kind, ResolvedType::Array(_)) {
+					"ReadArray"
+				} else {
+					"ReadOption"
+				};
+				format!(
+					"{function}(r, func() ({}, error) {{ return {} }})",
+					Self::resolved_type_to_go(schema, ty, naming_convention_config),
+					Self::decode_expr(schema, ty, naming_convention_config)
+				)
+			}
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => format!(
+				"ReadValue[{}](r)",
+				Self::declared_ident(schema, kind, naming_convention_config)
+			),
+		}
+	}
+
+	/// A struct with marshal and unmarshal methods, used for structs and procedure params.
+	fn generate_record<'a>(
+		schema: &ResolvedSchema,
+		ident: &str,
+		fields: impl Iterator<Item = (&'a Spanned<String>, &'a ResolvedType)> + Clone,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let fields = fields
+			.map(|(field, kind)| (Self::field_ident(naming_convention_config, &field.value), kind))
+			.collect::<Vec<_>>();
+
+		// aligned the way gofmt would
+		let width = fields.iter().map(|(field, _)| field.len()).max().unwrap_or_default();
+		let mut decl_fields = fields
+			.iter()
+			.map(|(field, kind)| {
+				format!(
+					"\t{field:width$} {}",
+					Self::resolved_type_to_go(schema, kind, naming_convention_config)
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		let mut encode_fields = fields
+			.iter()
+			.map(|(field, kind)| {
+				format!(
+					"\t{}",
+					Self::encode_stmt(schema, kind, &format!("v.{field}"), 0, naming_convention_config)
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		let mut decode_fields = fields
+			.iter()
+			.map(|(field, kind)| {
+				format!(
+					"\tif v.{field}, err = {}; err != nil {{\n\t\treturn err\n\t}}",
+					Self::decode_expr(schema, kind, naming_convention_config)
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		for block in [&mut decl_fields, &mut encode_fields, &mut decode_fields] {
+			if !block.is_empty() {
+				block.push('\n');
+			}
+		}
+
+		write!(
+			output,
+			"
+type {ident} struct {{
+{decl_fields}}}
+
+func (v {ident}) MarshalOlympus(w *Writer) {{
+{encode_fields}}}
+
+func (v *{ident}) UnmarshalOlympus(r *Reader) (err error) {{
+{decode_fields}\treturn nil
+}}
+"
+		)
+		.unwrap();
+	}
+}
+
+impl CodeGenerator for GoCodeGenerator {
+	fn reserved_words(&self) -> &'static [&'static str] {
+		&[
+			"break",
+			"case",
+			"chan",
+			"const",
+			"continue",
+			"default",
+			"defer",
+			"else",
+			"fallthrough",
+			"for",
+			"func",
+			"go",
+			"goto",
+			"if",
+			"import",
+			"interface",
+			"map",
+			"package",
+			"range",
+			"return",
+			"select",
+			"struct",
+			"switch",
+			"type",
+			"var",
+			"any",
+			"bool",
+			"byte",
+			"error",
+			"string",
+			"rune",
+			"int",
+			"uint",
+			"int8",
+			"int16",
+			"int32",
+			"int64",
+			"uint8",
+			"uint16",
+			"uint32",
+			"uint64",
+			"true",
+			"false",
+			"nil",
+			"len",
+			"append",
+			"make",
+			"new",
+			"min",
+			// declared by the runtime
+			"ErrInvalidData",
+			"ErrConnectionClosed",
+			"Marshaler",
+			"Unmarshaler",
+			"Writer",
+			"Reader",
+			"NewReader",
+			"WriteArray",
+			"WriteOption",
+			"ReadVarUint",
+			"ReadVarInt",
+			"ReadArray",
+			"ReadOption",
+			"ReadValue",
+			"Fnv1a",
+			"Connection",
+			"Dial",
+			"NewConnection",
+			"Call",
+		]
+	}
+
+	fn generate_file_header(&self, output: &mut String) {
+		output.push_str(RUNTIME);
+	}
+
+	fn generate_enum(
+		&self,
+		resolved: &ResolvedEnum,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let ident = Self::type_ident(naming_convention_config, &resolved.ident.value);
+		let variants = resolved
+			.variants
+			.iter()
+			.map(|variant| Self::variant_ident(naming_convention_config, &ident, &variant.ident.value))
+			.collect::<Vec<_>>();
+
+		let width = variants.iter().map(String::len).max().unwrap_or_default();
+		let consts = variants
+			.iter()
+			.zip(&resolved.variants)
+			.map(|(variant, resolved)| format!("\t{variant:width$} {ident} = {}", resolved.value))
+			.collect::<Vec<String>>()
+			.join("\n");
+		let (consts, cases) = if variants.is_empty() {
+			(String::new(), String::new())
+		} else {
+			(
+				format!("\nconst (\n{consts}\n)\n"),
+				format!(
+					"\tcase {}:\n\t\t*v = {ident}(tag)\n\t\treturn nil\n",
+					variants.join(", ")
+				),
+			)
+		};
+
+		write!(
+			output,
+			"
+type {ident} uint16
+{consts}
+func (v {ident}) MarshalOlympus(w *Writer) {{
+	w.WriteU16(uint16(v))
+}}
+
+func (v *{ident}) UnmarshalOlympus(r *Reader) error {{
+	tag, err := r.ReadU16()
+	if err != nil {{
+		return err
+	}}
+
+	switch {ident}(tag) {{
+{cases}\tdefault:
+		return fmt.Errorf(\"%w: invalid {ident} tag: %d\", ErrInvalidData, tag)
+	}}
+}}
+"
+		)
+		.unwrap();
+	}
+
+	fn generate_struct(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedStruct,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		Self::generate_record(
+			schema,
+			&Self::type_ident(naming_convention_config, &resolved.ident.value),
+			resolved.fields.iter().map(|field| (&field.ident, &field.kind.value)),
+			output,
+			naming_convention_config,
+		);
+	}
+
+	fn generate_procedure_params(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedProcedure,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		if resolved.params.is_empty() {
+			return;
+		}
+
+		Self::generate_record(
+			schema,
+			&self.procedure_params_ident(resolved, naming_convention_config),
+			resolved.params.iter().map(|param| (&param.ident, &param.kind.value)),
+			output,
+			naming_convention_config,
+		);
+	}
+
+	fn generate_client_impl(
+		&self,
+		schema: &ResolvedSchema,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let client_ident = naming_convention_config.apply_types("ClientRpc");
+		let methods = schema
+			.procedures
+			.iter()
+			.map(|proc| {
+				let name = &proc.ident.value;
+				let (params, write) = if proc.params.is_empty() {
+					(String::new(), "func(w *Writer) {}".to_string())
+				} else {
+					let params_ident = self.procedure_params_ident(proc, naming_convention_config);
+					(format!("params {params_ident}"), "params.MarshalOlympus".to_string())
+				};
+
+				let method = Self::proc_ident(naming_convention_config, name);
+				// the server doesn't respond to procedures without a return value, so there is nothing to wait for
+				match &proc.return_kind {
+					Some(kind) => {
+						let return_ty = Self::resolved_type_to_go(schema, &kind.value, naming_convention_config);
+						let params = if params.is_empty() {
+							"ctx context.Context".to_string()
+						} else {
+							format!("ctx context.Context, {params}")
+						};
+						format!(
+							"
+// {method} calls `{name}` and waits for its response.
+func (c *{client_ident}) {method}({params}) ({return_ty}, error) {{
+	return Call(ctx, c.Connection, \"{name}\", {write}, func(r *Reader) ({return_ty}, error) {{ return {} }})
+}}",
+							Self::decode_expr(schema, &kind.value, naming_convention_config)
+						)
+					}
+					None => format!(
+						"
+// {method} sends `{name}` without waiting for the server, it doesn't return anything.
+func (c *{client_ident}) {method}({params}) error {{
+	return c.Connection.Send(\"{name}\", {write})
+}}"
+					),
+				}
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		write!(
+			output,
+			"
+// {client_ident} has a method for every procedure.
+type {client_ident} struct {{
+	Connection *Connection
+}}
+
+func New{client_ident}(connection *Connection) *{client_ident} {{
+	return &{client_ident}{{Connection: connection}}
+}}
+{methods}
+"
+		)
+		.unwrap();
+	}
+}
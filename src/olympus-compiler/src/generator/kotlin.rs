@@ -0,0 +1,467 @@
+use std::fmt::Write;
+
+use crate::naming::NamingConventionConfig;
+use olympus_spanned::Spanned;
+use olympus_verifier::{IntKind, ResolvedEnum, ResolvedProcedure, ResolvedSchema, ResolvedStruct, ResolvedType};
+
+use super::CodeGenerator;
+
+pub struct KotlinCodeGenerator;
+
+const RUNTIME: &str = include_str!("runtime/olympus.kt");
+
+impl KotlinCodeGenerator {
+	fn type_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		KotlinCodeGenerator.escape_ident(&naming_convention_config.apply_types(ident))
+	}
+
+	fn variant_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		KotlinCodeGenerator.escape_ident(&naming_convention_config.apply_enum_variants(ident))
+	}
+
+	fn field_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		KotlinCodeGenerator.escape_ident(&naming_convention_config.apply_struct_fields(ident))
+	}
+
+	fn proc_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		KotlinCodeGenerator.escape_ident(&naming_convention_config.apply_procs(ident))
+	}
+
+	fn int_method(kind: IntKind) -> &'static str {
+		match kind {
+			IntKind::Int8 => "i8",
+			IntKind::Int16 => "i16",
+			IntKind::Int32 => "i32",
+			IntKind::Int64 => "i64",
+			IntKind::UInt8 => "u8",
+			IntKind::UInt16 => "u16",
+			IntKind::UInt32 => "u32",
+			IntKind::UInt64 => "u64",
+		}
+	}
+
+	fn int_type(kind: IntKind) -> &'static str {
+		match kind {
+			IntKind::Int8 => "Byte",
+			IntKind::Int16 => "Short",
+			IntKind::Int32 => "Int",
+			IntKind::Int64 => "Long",
+			IntKind::UInt8 => "UByte",
+			IntKind::UInt16 => "UShort",
+			IntKind::UInt32 => "UInt",
+			IntKind::UInt64 => "ULong",
+		}
+	}
+
+	fn declared_ident(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Enum(id) => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			ResolvedType::Struct { id, .. } => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			_ => unreachable!("only enums and structs are declared"),
+		}
+	}
+
+	fn resolved_type_to_kt(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) | ResolvedType::VariableInt(int) => Self::int_type(*int).to_string(),
+			ResolvedType::String => "String".to_string(),
+			ResolvedType::Array(ty) => format!(
+				"List<{}>",
+				Self::resolved_type_to_kt(schema, ty, naming_convention_config)
+			),
+			// `T??` would be the same type as `T?`, so the inner option is wrapped
+			ResolvedType::Option(ty) if matches!(**ty, ResolvedType::Option(_)) => format!(
+				"Some<{}>?",
+				Self::resolved_type_to_kt(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) => format!("{}?", Self::resolved_type_to_kt(schema, ty, naming_convention_config)),
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => {
+				Self::declared_ident(schema, kind, naming_convention_config)
+			}
+		}
+	}
+
+	/// An expression writing `value` to the `Writer` in scope as `w`.
+	///
+	/// `depth` keeps the item names of nested arrays and options apart.
+	fn encode_expr(kind: &ResolvedType, value: &str, depth: usize) -> String {
+		match kind {
+			ResolvedType::Int(int) => format!("w.{}({value})", Self::int_method(*int)),
+			ResolvedType::VariableInt(IntKind::Int64) => format!("w.varInt({value}, 64)"),
+			ResolvedType::VariableInt(IntKind::UInt64) => format!("w.varUint({value})"),
+			ResolvedType::VariableInt(int) if int.is_signed() => {
+				format!("w.varInt({value}.toLong(), {})", int.bits())
+			}
+			ResolvedType::VariableInt(_) => format!("w.varUint({value}.toULong())"),
+			ResolvedType::String => format!("w.string({value})"),
+			ResolvedType::Array(ty) => {
+				let item = format!("item{depth}");
+				format!(
+					"w.array({value}) {{ {item} -> {} }}",
+					Self::encode_expr(ty, &item, depth + 1)
+				)
+			}
+			ResolvedType::Option(ty) => {
+				let item = format!("item{depth}");
+				let inner = if matches!(**ty, ResolvedType::Option(_)) {
+					format!("{item}.value")
+				} else {
+					item.clone()
+				};
+				format!(
+					"w.option({value}) {{ {item} -> {} }}",
+					Self::encode_expr(ty, &inner, depth + 1)
+				)
+			}
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => format!("{value}.encode(w)"),
+		}
+	}
+
+	/// An expression reading a value from the `Reader` in scope as `r`.
+	fn decode_expr(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) => format!("r.{}()", Self::int_method(*int)),
+			ResolvedType::VariableInt(IntKind::Int64) => "r.varInt(64)".to_string(),
+			ResolvedType::VariableInt(IntKind::UInt64) => "r.varUint()".to_string(),
+			ResolvedType::VariableInt(int) if int.is_signed() => {
+				format!("r.varInt({}).to{}()", int.bits(), Self::int_type(*int))
+			}
+			ResolvedType::VariableInt(int) => format!("r.varUint().to{}()", Self::int_type(*int)),
+			ResolvedType::String => "r.string()".to_string(),
+			ResolvedType::Array(ty) => format!(
+				"r.array {{ {} }}",
+				Self::decode_expr(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) if matches!(**ty, ResolvedType::Option(_)) => format!(
+				"r.option {{ Some({}) }}",
+				Self::decode_expr(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) => format!(
+				"r.option {{ {} }}",
+				Self::decode_expr(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => format!(
+				"{}.decode(r)",
+				Self::declared_ident(schema, kind, naming_convention_config)
+			),
+		}
+	}
+
+	/// A data class with `encode` and `decode` functions, used for structs and procedure params.
+	fn generate_record<'a>(
+		schema: &ResolvedSchema,
+		ident: &str,
+		fields: impl Iterator<Item = (&'a Spanned<String>, &'a ResolvedType)>,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let fields = fields
+			.map(|(field, kind)| (Self::field_ident(naming_convention_config, &field.value), kind))
+			.collect::<Vec<_>>();
+
+		// data classes need at least one property, so empty records compare equal by hand
+		if fields.is_empty() {
+			write!(
+				output,
+				"
+class {ident} {{
+    fun encode(w: Writer) {{}}
+
+    override fun equals(other: Any?): Boolean = other is {ident}
+
+    override fun hashCode(): Int = 0
+
+    override fun toString(): String = \"{ident}()\"
+
+    companion object {{
+        fun decode(r: Reader): {ident} = {ident}()
+    }}
+}}
+"
+			)
+			.unwrap();
+			return;
+		}
+
+		let params = fields
+			.iter()
+			.map(|(field, kind)| {
+				format!(
+					"    val {field}: {},",
+					Self::resolved_type_to_kt(schema, kind, naming_convention_config)
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+		let encode = fields
+			.iter()
+			.map(|(field, kind)| format!("        {}", Self::encode_expr(kind, field, 0)))
+			.collect::<Vec<String>>()
+			.join("\n");
+		// arguments are evaluated in the order they are written, which is the order on the wire
+		let decode = fields
+			.iter()
+			.map(|(field, kind)| {
+				format!(
+					"            {field} = {},",
+					Self::decode_expr(schema, kind, naming_convention_config)
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		write!(
+			output,
+			"
+data class {ident}(
+{params}
+) {{
+    fun encode(w: Writer) {{
+{encode}
+    }}
+
+    companion object {{
+        fun decode(r: Reader): {ident} = {ident}(
+{decode}
+        )
+    }}
+}}
+"
+		)
+		.unwrap();
+	}
+}
+
+impl CodeGenerator for KotlinCodeGenerator {
+	fn reserved_words(&self) -> &'static [&'static str] {
+		&[
+			"as",
+			"break",
+			"class",
+			"continue",
+			"do",
+			"else",
+			"false",
+			"for",
+			"fun",
+			"if",
+			"in",
+			"interface",
+			"is",
+			"null",
+			"object",
+			"package",
+			"return",
+			"super",
+			"this",
+			"throw",
+			"true",
+			"try",
+			"typealias",
+			"typeof",
+			"val",
+			"var",
+			"when",
+			"while",
+			// the members of every record and enum, and their parameters
+			"encode",
+			"decode",
+			"tag",
+			"w",
+			"r",
+			// used by the runtime and generated code, a declaration in the same package would shadow them
+			"Any",
+			"Boolean",
+			"Byte",
+			"ByteArray",
+			"Exception",
+			"Int",
+			"List",
+			"Long",
+			"Nothing",
+			"Short",
+			"String",
+			"Throwable",
+			"UByte",
+			"UInt",
+			"ULong",
+			"Unit",
+			"UShort",
+			"ArrayDeque",
+			"ArrayList",
+			"HashMap",
+			"AutoCloseable",
+			// imported or declared by the runtime
+			"DataInputStream",
+			"IOException",
+			"InetSocketAddress",
+			"Socket",
+			"ByteBuffer",
+			"CharacterCodingException",
+			"CodingErrorAction",
+			"CompletableDeferred",
+			"CoroutineScope",
+			"Dispatchers",
+			"SupervisorJob",
+			"Mutex",
+			"OlympusException",
+			"Some",
+			"Writer",
+			"Reader",
+			"OlympusConnection",
+		]
+	}
+
+	fn generate_file_header(&self, output: &mut String) {
+		output.push_str(RUNTIME);
+	}
+
+	fn generate_enum(
+		&self,
+		resolved: &ResolvedEnum,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let ident = Self::type_ident(naming_convention_config, &resolved.ident.value);
+		let variants = resolved
+			.variants
+			.iter()
+			.map(|variant| {
+				format!(
+					"    {}({}u)",
+					Self::variant_ident(naming_convention_config, &variant.ident.value),
+					variant.value
+				)
+			})
+			.collect::<Vec<String>>()
+			.join(",\n");
+		// the semicolon ends the entries, it is required before the members even if there are none
+		let variants = if variants.is_empty() {
+			"    ;".to_string()
+		} else {
+			format!("{variants};")
+		};
+
+		write!(
+			output,
+			"
+enum class {ident}(val tag: UShort) {{
+{variants}
+
+    fun encode(w: Writer) = w.u16(tag)
+
+    companion object {{
+        private val byTag = values().associateBy {{ it.tag }}
+
+        fun decode(r: Reader): {ident} {{
+            val tag = r.u16()
+            return byTag[tag] ?: throw OlympusException(\"Invalid {ident} tag: $tag\")
+        }}
+    }}
+}}
+"
+		)
+		.unwrap();
+	}
+
+	fn generate_struct(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedStruct,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		Self::generate_record(
+			schema,
+			&Self::type_ident(naming_convention_config, &resolved.ident.value),
+			resolved.fields.iter().map(|field| (&field.ident, &field.kind.value)),
+			output,
+			naming_convention_config,
+		);
+	}
+
+	fn generate_procedure_params(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedProcedure,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		if resolved.params.is_empty() {
+			return;
+		}
+
+		Self::generate_record(
+			schema,
+			&self.procedure_params_ident(resolved, naming_convention_config),
+			resolved.params.iter().map(|param| (&param.ident, &param.kind.value)),
+			output,
+			naming_convention_config,
+		);
+	}
+
+	fn generate_client_impl(
+		&self,
+		schema: &ResolvedSchema,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let methods = schema
+			.procedures
+			.iter()
+			.map(|proc| {
+				let name = &proc.ident.value;
+				let method = Self::proc_ident(naming_convention_config, name);
+
+				let (params, write) = if proc.params.is_empty() {
+					(String::new(), "{}".to_string())
+				} else {
+					let params_ident = self.procedure_params_ident(proc, naming_convention_config);
+					(format!("params: {params_ident}"), "params::encode".to_string())
+				};
+
+				// the server doesn't respond to procedures without a return value, so there is nothing to wait for
+				match &proc.return_kind {
+					Some(kind) => format!(
+						"
+    /** Calls `{name}` and waits for its response. */
+    suspend fun {method}({params}): {} =
+        connection.call(\"{name}\", {write}) {{ r -> {} }}",
+						Self::resolved_type_to_kt(schema, &kind.value, naming_convention_config),
+						Self::decode_expr(schema, &kind.value, naming_convention_config)
+					),
+					None => format!(
+						"
+    /** Sends `{name}` without waiting for the server, it doesn't return anything. */
+    suspend fun {method}({params}) {{
+        connection.send(\"{name}\", {write})
+    }}"
+					),
+				}
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		write!(
+			output,
+			"
+class {}(private val connection: OlympusConnection) {{{methods}
+}}
+",
+			naming_convention_config.apply_types("ClientRpc")
+		)
+		.unwrap();
+	}
+}
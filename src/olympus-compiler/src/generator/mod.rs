@@ -1,20 +1,409 @@
-use crate::cli::NamingConventionConfig;
-use olympus_parser::{ParsedEnum, ParsedProcedure, ParsedStruct, Parser};
+use std::{collections::BTreeMap, path::PathBuf};
 
+use crate::naming::NamingConventionConfig;
+use clap::ValueEnum;
+use olympus_spanned::{ErrorCode, OlympusError};
+use olympus_verifier::{ResolvedEnum, ResolvedProcedure, ResolvedSchema, ResolvedStruct};
+use serde::Deserialize;
+
+use self::{
+	c::CCodeGenerator, collisions::check_name_collisions, csharp::CSharpCodeGenerator, go::GoCodeGenerator,
+	kotlin::KotlinCodeGenerator, python::PythonCodeGenerator, rust::RustCodeGenerator,
+	typescript::TypeScriptCodeGenerator,
+};
+
+pub mod c;
+pub mod collisions;
+pub mod csharp;
+pub mod go;
+pub mod kotlin;
+pub mod python;
 pub mod rust;
+pub mod typescript;
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompileLanguage {
+	Rust,
+	#[value(name = "typescript", alias = "ts")]
+	#[serde(alias = "ts")]
+	TypeScript,
+	#[value(alias = "py")]
+	#[serde(alias = "py")]
+	Python,
+	Go,
+	C,
+	#[value(name = "csharp", alias = "cs")]
+	#[serde(alias = "cs")]
+	CSharp,
+	#[value(alias = "kt")]
+	#[serde(alias = "kt")]
+	Kotlin,
+}
+
+impl CompileLanguage {
+	#[must_use]
+	pub fn code_generator(self) -> &'static dyn CodeGenerator {
+		match self {
+			CompileLanguage::Rust => &DEFAULT_RUST_CODE_GENERATOR,
+			CompileLanguage::TypeScript => &TypeScriptCodeGenerator,
+			CompileLanguage::Python => &PythonCodeGenerator,
+			CompileLanguage::Go => &GoCodeGenerator,
+			CompileLanguage::C => &CCodeGenerator,
+			CompileLanguage::CSharp => &CSharpCodeGenerator,
+			CompileLanguage::Kotlin => &KotlinCodeGenerator,
+		}
+	}
+
+	/// The name of the generated file if none is given.
+	#[must_use]
+	pub fn default_file_name(self) -> &'static str {
+		match self {
+			CompileLanguage::Rust => "models.rs",
+			CompileLanguage::TypeScript => "olympus.ts",
+			CompileLanguage::Python => "olympus.py",
+			CompileLanguage::Go => "olympus.go",
+			CompileLanguage::C => "olympus.h",
+			CompileLanguage::CSharp => "Olympus.cs",
+			CompileLanguage::Kotlin => "Olympus.kt",
+		}
+	}
+}
+
+static DEFAULT_RUST_CODE_GENERATOR: RustCodeGenerator = RustCodeGenerator {
+	derives: Vec::new(),
+	type_derives: BTreeMap::new(),
+	serde: false,
+};
+
+/// Which side of the protocol a generated Rust crate contains, the models are always included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RustCrateSide {
+	#[default]
+	Both,
+	Client,
+	Server,
+}
+
+impl RustCrateSide {
+	fn has_client(self) -> bool {
+		matches!(self, RustCrateSide::Both | RustCrateSide::Client)
+	}
+
+	fn has_server(self) -> bool {
+		matches!(self, RustCrateSide::Both | RustCrateSide::Server)
+	}
+}
+
+/// Where a generated Rust crate gets the olympus runtime crates from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RustDependencies {
+	/// Inherited from the workspace the crate is generated into, which has to declare them.
+	#[default]
+	Workspace,
+	/// The directory the runtime crates are in, relative to the generated crate.
+	Path(PathBuf),
+	/// A git repository with the runtime crates, at `rev` if there is one.
+	Git { url: String, rev: Option<String> },
+	/// A version of the runtime crates from crates.io.
+	Version(String),
+}
+
+impl RustDependencies {
+	/// The `[dependencies]` line for the runtime crate `name`.
+	fn dependency(&self, name: &str) -> String {
+		let string = |value: &str| toml::Value::String(value.to_string()).to_string();
+		match self {
+			RustDependencies::Workspace => format!("{name}.workspace = true"),
+			RustDependencies::Path(dir) => {
+				format!("{name} = {{ path = {} }}", string(&dir.join(name).to_string_lossy()))
+			}
+			RustDependencies::Git { url, rev: None } => format!("{name} = {{ git = {} }}", string(url)),
+			RustDependencies::Git { url, rev: Some(rev) } => {
+				format!("{name} = {{ git = {}, rev = {} }}", string(url), string(rev))
+			}
+			RustDependencies::Version(version) => format!("{name} = {}", string(version)),
+		}
+	}
+}
+
+pub const GENERATED_COMMENT: &str = "/-- THIS FILE WAS AUTOMATICALLY GENERATED BY OLYMPUS --\\";
+
+/// What to generate and how, start with [`GenerateOptions::new`].
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+	pub language: CompileLanguage,
+	pub naming_convention_config: NamingConventionConfig,
+	/// The name of the generated file [default: [`CompileLanguage::default_file_name`]]
+	pub file_name: Option<String>,
+	/// (Rust only) Generate a crate with this name instead of a single file with the models.
+	pub rs_crate_name: Option<String>,
+	/// (Rust only) The version of the generated crate [default: 0.0.0-OLYMPUS-GENERATED]
+	pub rs_crate_version: Option<String>,
+	/// (Rust only) Whether the generated crate contains the client, the server or both.
+	pub rs_crate_side: RustCrateSide,
+	/// (Rust only) Where the generated crate gets the runtime crates from.
+	pub rs_dependencies: RustDependencies,
+	/// (Rust only) Derived for every enum and struct on top of the ones the generated code needs.
+	pub rs_derives: Vec<String>,
+	/// (Rust only) Derived for the enum or struct with the schema name of the key on top of `rs_derives`.
+	pub rs_type_derives: BTreeMap<String, Vec<String>>,
+	/// (Rust only) Derive `Serialize` and `Deserialize` for the enums and structs behind a `serde` feature, which the
+	/// crate including a single generated file has to declare.
+	pub rs_serde: bool,
+	/// (Go only) [default: olympus]
+	pub go_package: Option<String>,
+	/// (C# only) [default: Olympus]
+	pub cs_namespace: Option<String>,
+	/// (Kotlin only) [default: olympus]
+	pub kt_package: Option<String>,
+}
+
+impl GenerateOptions {
+	/// The naming conventions `language` usually follows and defaults for everything else.
+	#[must_use]
+	pub fn new(language: CompileLanguage) -> Self {
+		Self {
+			language,
+			naming_convention_config: NamingConventionConfig::defaults_for(language),
+			file_name: None,
+			rs_crate_name: None,
+			rs_crate_version: None,
+			rs_crate_side: RustCrateSide::Both,
+			rs_dependencies: RustDependencies::Workspace,
+			rs_derives: Vec::new(),
+			rs_type_derives: BTreeMap::new(),
+			rs_serde: false,
+			go_package: None,
+			cs_namespace: None,
+			kt_package: None,
+		}
+	}
+}
+
+/// A generated file, `path` is relative to the directory the files are written to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedFile {
+	pub path: PathBuf,
+	pub contents: String,
+}
+
+/// Generates the code for `schema` without writing anything.
+///
+/// Fails if two names in the schema collide once naming conventions and escaping are applied.
+pub fn generate(schema: &ResolvedSchema, options: &GenerateOptions) -> Result<Vec<GeneratedFile>, OlympusError> {
+	let generator = options.language.code_generator();
+	let naming_convention_config = &options.naming_convention_config;
+	check_name_collisions(generator, schema, naming_convention_config)?;
+
+	let preamble = match options.language {
+		CompileLanguage::Rust => {
+			check_derives(options)?;
+			let generator = rust_generator(options);
+			if let Some(crate_name) = &options.rs_crate_name {
+				return Ok(generate_rust_crate(&generator, schema, crate_name, options));
+			}
+
+			let mut models_src = String::with_capacity(4096);
+			generator.generate_models(schema, &mut models_src, naming_convention_config);
+			return Ok(vec![GeneratedFile {
+				path: PathBuf::from(file_name(options)),
+				contents: models_src,
+			}]);
+		}
+		CompileLanguage::TypeScript => format!("// {GENERATED_COMMENT}"),
+		CompileLanguage::Python => format!("# {GENERATED_COMMENT}"),
+		CompileLanguage::Go => format!(
+			"// {GENERATED_COMMENT}\n// Code generated by olympusc. DO NOT EDIT.\n\npackage {}\n",
+			options.go_package.as_deref().unwrap_or("olympus")
+		),
+		// a line comment ending in a backslash would continue onto the next line
+		CompileLanguage::C => format!("/* {GENERATED_COMMENT} */"),
+		// the runtime's using directives follow the namespace, so they are scoped to it
+		CompileLanguage::CSharp => format!(
+			"// {GENERATED_COMMENT}\n// <auto-generated />\n\n#nullable enable\n\nnamespace {};\n",
+			options.cs_namespace.as_deref().unwrap_or("Olympus")
+		),
+		// the runtime's imports follow the package clause
+		CompileLanguage::Kotlin => format!(
+			"// {GENERATED_COMMENT}\n\npackage {}\n",
+			options.kt_package.as_deref().unwrap_or("olympus")
+		),
+	};
+
+	// a single module with the runtime, every type with its codec and the client
+	let mut src = String::with_capacity(16384);
+	generator.generate_file_header(&mut src);
+	generator.generate_models(schema, &mut src, naming_convention_config);
+	generator.generate_client_impl(schema, &mut src, naming_convention_config);
+	generator.generate_file_footer(&mut src);
+	Ok(vec![GeneratedFile {
+		path: PathBuf::from(file_name(options)),
+		contents: format!("{preamble}\n{src}"),
+	}])
+}
+
+/// The `models`, `server` and `client` modules a generated crate has, for code that is included into another crate
+/// instead. This is how `olympus-build` and `include_schema!` generate Rust, `options` is used like for a crate.
+///
+/// Fails like [`generate`] does.
+pub fn generate_rust_modules(
+	schema: &ResolvedSchema,
+	options: &GenerateOptions,
+) -> Result<proc_macro2::TokenStream, OlympusError> {
+	let naming_convention_config = &options.naming_convention_config;
+	check_name_collisions(&DEFAULT_RUST_CODE_GENERATOR, schema, naming_convention_config)?;
+	check_derives(options)?;
+	Ok(rust_generator(options).generate_modules(schema, naming_convention_config))
+}
+
+fn rust_generator(options: &GenerateOptions) -> RustCodeGenerator {
+	RustCodeGenerator {
+		derives: options.rs_derives.clone(),
+		type_derives: options.rs_type_derives.clone(),
+		serde: options.rs_serde,
+	}
+}
+
+/// Derives are written into the generated code as they are, so they have to be paths.
+fn check_derives(options: &GenerateOptions) -> Result<(), OlympusError> {
+	let derives = options
+		.rs_derives
+		.iter()
+		.chain(options.rs_type_derives.values().flatten());
+	for derive in derives {
+		if syn::parse_str::<syn::Path>(derive).is_err() {
+			return Err(OlympusError::new(&format!(
+				"'{derive}' can't be derived, derives have to be paths like `PartialEq` or `serde::Serialize`"
+			))
+			.code(ErrorCode::InvalidDerive));
+		}
+	}
+
+	Ok(())
+}
+
+fn file_name(options: &GenerateOptions) -> &str {
+	options
+		.file_name
+		.as_deref()
+		.unwrap_or_else(|| options.language.default_file_name())
+}
+
+fn generate_rust_crate(
+	generator: &RustCodeGenerator,
+	schema: &ResolvedSchema,
+	crate_name: &str,
+	options: &GenerateOptions,
+) -> Vec<GeneratedFile> {
+	let naming_convention_config = &options.naming_convention_config;
+	let side = options.rs_crate_side;
+	let crate_path = PathBuf::from(crate_name);
+
+	let mut dependencies = Vec::with_capacity(3);
+	if side.has_client() {
+		dependencies.push(options.rs_dependencies.dependency("olympus-client"));
+	}
+	dependencies.push(options.rs_dependencies.dependency("olympus-net-common"));
+	if side.has_server() {
+		dependencies.push(options.rs_dependencies.dependency("olympus-server"));
+	}
+
+	let mut cargo_toml = format!(
+		"[package]
+name = \"{crate_name}\"
+edition = \"2021\"
+version = \"{}\"
+publish = false
+
+[dependencies]
+{}
+",
+		options.rs_crate_version.as_deref().unwrap_or("0.0.0-OLYMPUS-GENERATED"),
+		dependencies.join("\n")
+	);
+	if options.rs_serde {
+		cargo_toml.push_str("\n[features]\nserde = [\"olympus-net-common/serde\"]\n");
+	}
+
+	// the modules of the crate with their items
+	let mut modules = Vec::with_capacity(3);
+	if side.has_client() {
+		modules.push(("client", generator.client_module(schema, naming_convention_config)));
+	}
+	modules.push(("models", generator.models_module(schema, naming_convention_config)));
+	if side.has_server() {
+		modules.push(("server", generator.server_module(schema, naming_convention_config)));
+	}
+
+	let lib_src = modules
+		.iter()
+		.map(|(module, _)| format!("pub mod {module};"))
+		.collect::<Vec<_>>()
+		.join("\n");
+
+	let mut files = vec![
+		GeneratedFile {
+			path: crate_path.join("Cargo.toml"),
+			contents: cargo_toml,
+		},
+		GeneratedFile {
+			path: crate_path.join("src").join("lib.rs"),
+			contents: format!("// {GENERATED_COMMENT}\n{lib_src}\n"),
+		},
+	];
+	files.extend(modules.into_iter().map(|(module, tokens)| GeneratedFile {
+		path: crate_path.join("src").join(format!("{module}.rs")),
+		contents: format!("// {GENERATED_COMMENT}\n{}", rust::pretty(tokens)),
+	}));
+	files
+}
 
 pub trait CodeGenerator {
-	fn generate_models(&self, parser: &Parser, output: &mut String, naming_convention_config: &NamingConventionConfig) {
-		for r#enum in &parser.enums {
+	/// Words that can't be used as identifiers in the generated language.
+	fn reserved_words(&self) -> &'static [&'static str];
+
+	/// Makes an identifier usable in the generated language, escaping it if it is a reserved word.
+	fn escape_ident(&self, ident: &str) -> String {
+		if self.reserved_words().contains(&ident) {
+			format!("{ident}_")
+		} else {
+			ident.to_string()
+		}
+	}
+
+	/// Makes an identifier usable as a field, enum variant or procedure name. These are members in most languages,
+	/// which allow more words than [`CodeGenerator::escape_ident`], by default it escapes the same words.
+	fn escape_member(&self, ident: &str) -> String {
+		self.escape_ident(ident)
+	}
+
+	/// The identifier of the type generated to hold the params of a procedure.
+	fn procedure_params_ident(
+		&self,
+		resolved: &ResolvedProcedure,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		self.escape_ident(&naming_convention_config.apply_types(&format!("{}Params", resolved.ident.value)))
+	}
+
+	fn generate_models(
+		&self,
+		schema: &ResolvedSchema,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		for r#enum in &schema.enums {
 			self.generate_enum(r#enum, output, naming_convention_config);
 		}
 
-		for r#struct in &parser.structs {
-			self.generate_struct(r#struct, output, naming_convention_config);
+		for r#struct in &schema.structs {
+			self.generate_struct(schema, r#struct, output, naming_convention_config);
 		}
 
-		for proc in &parser.procedures {
-			self.generate_procedure_params(proc, output, naming_convention_config);
+		for proc in &schema.procedures {
+			self.generate_procedure_params(schema, proc, output, naming_convention_config);
 		}
 	}
 
@@ -22,31 +411,44 @@ pub trait CodeGenerator {
 	fn generate_file_footer(&self, _output: &mut String) {}
 	fn generate_enum(
 		&self,
-		parsed: &ParsedEnum,
+		resolved: &ResolvedEnum,
 		output: &mut String,
 		naming_convention_config: &NamingConventionConfig,
 	);
 	fn generate_struct(
 		&self,
-		parsed: &ParsedStruct,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedStruct,
 		output: &mut String,
 		naming_convention_config: &NamingConventionConfig,
 	);
+	/// Generators for languages without a server runtime leave this and
+	/// [`CodeGenerator::generate_server_registration_fn`] empty.
 	fn generate_abstract_server_impl(
 		&self,
-		parsed: &[ParsedProcedure],
-		output: &mut String,
-		naming_convention_config: &NamingConventionConfig,
-	);
+		_schema: &ResolvedSchema,
+		_output: &mut String,
+		_naming_convention_config: &NamingConventionConfig,
+	) {
+	}
 	fn generate_server_registration_fn(
 		&self,
-		parsed: &[ParsedProcedure],
+		_schema: &ResolvedSchema,
+		_output: &mut String,
+		_naming_convention_config: &NamingConventionConfig,
+	) {
+	}
+	fn generate_procedure_params(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedProcedure,
 		output: &mut String,
 		naming_convention_config: &NamingConventionConfig,
 	);
-	fn generate_procedure_params(
+	/// A client with one method per procedure, so callers can't get procedure names or types wrong.
+	fn generate_client_impl(
 		&self,
-		parsed: &ParsedProcedure,
+		schema: &ResolvedSchema,
 		output: &mut String,
 		naming_convention_config: &NamingConventionConfig,
 	);
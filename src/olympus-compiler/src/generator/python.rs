@@ -0,0 +1,440 @@
+use std::fmt::Write;
+
+use crate::naming::NamingConventionConfig;
+use olympus_spanned::Spanned;
+use olympus_verifier::{IntKind, ResolvedEnum, ResolvedProcedure, ResolvedSchema, ResolvedStruct, ResolvedType};
+
+use super::CodeGenerator;
+
+pub struct PythonCodeGenerator;
+
+const RUNTIME: &str = include_str!("runtime/olympus.py");
+
+impl PythonCodeGenerator {
+	fn type_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		PythonCodeGenerator.escape_ident(&naming_convention_config.apply_types(ident))
+	}
+
+	fn variant_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		PythonCodeGenerator.escape_ident(&naming_convention_config.apply_enum_variants(ident))
+	}
+
+	fn field_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		PythonCodeGenerator.escape_ident(&naming_convention_config.apply_struct_fields(ident))
+	}
+
+	fn proc_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		PythonCodeGenerator.escape_ident(&naming_convention_config.apply_procs(ident))
+	}
+
+	fn int_method(kind: IntKind) -> &'static str {
+		match kind {
+			IntKind::Int8 => "i8",
+			IntKind::Int16 => "i16",
+			IntKind::Int32 => "i32",
+			IntKind::Int64 => "i64",
+			IntKind::UInt8 => "u8",
+			IntKind::UInt16 => "u16",
+			IntKind::UInt32 => "u32",
+			IntKind::UInt64 => "u64",
+		}
+	}
+
+	fn declared_ident(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Enum(id) => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			ResolvedType::Struct { id, .. } => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			_ => unreachable!("only enums and structs are declared"),
+		}
+	}
+
+	fn resolved_type_to_py(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(_) | ResolvedType::VariableInt(_) => "int".to_string(),
+			ResolvedType::String => "str".to_string(),
+			ResolvedType::Array(ty) => format!(
+				"List[{}]",
+				Self::resolved_type_to_py(schema, ty, naming_convention_config)
+			),
+			// `Optional[Optional[T]]` would be the same type as `Optional[T]`, so the inner option is wrapped
+			ResolvedType::Option(ty) if matches!(**ty, ResolvedType::Option(_)) => format!(
+				"Optional[Some[{}]]",
+				Self::resolved_type_to_py(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) => format!(
+				"Optional[{}]",
+				Self::resolved_type_to_py(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => {
+				Self::declared_ident(schema, kind, naming_convention_config)
+			}
+		}
+	}
+
+	fn return_type_to_py(
+		schema: &ResolvedSchema,
+		kind: Option<&Spanned<ResolvedType>>,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		kind.map_or_else(
+			|| "None".to_string(),
+			|kind| Self::resolved_type_to_py(schema, &kind.value, naming_convention_config),
+		)
+	}
+
+	/// An expression writing `value` to the `Writer` in scope as `w`.
+	///
+	/// `depth` keeps the item names of nested arrays and options apart.
+	fn encode_expr(kind: &ResolvedType, value: &str, depth: usize) -> String {
+		match kind {
+			ResolvedType::Int(int) => format!("w.{}({value})", Self::int_method(*int)),
+			ResolvedType::VariableInt(int) if int.is_signed() => format!("w.var_int({value}, {})", int.bits()),
+			ResolvedType::VariableInt(_) => format!("w.var_uint({value})"),
+			ResolvedType::String => format!("w.string({value})"),
+			ResolvedType::Array(ty) => {
+				let item = format!("item{depth}");
+				format!(
+					"w.array({value}, lambda {item}: {})",
+					Self::encode_expr(ty, &item, depth + 1)
+				)
+			}
+			ResolvedType::Option(ty) => {
+				let item = format!("item{depth}");
+				let inner = if matches!(**ty, ResolvedType::Option(_)) {
+					format!("{item}.value")
+				} else {
+					item.clone()
+				};
+				format!(
+					"w.option({value}, lambda {item}: {})",
+					Self::encode_expr(ty, &inner, depth + 1)
+				)
+			}
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => format!("{value}.encode(w)"),
+		}
+	}
+
+	/// An expression reading a value from the `Reader` in scope as `r`.
+	fn decode_expr(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) => format!("r.{}()", Self::int_method(*int)),
+			ResolvedType::VariableInt(int) if int.is_signed() => format!("r.var_int({})", int.bits()),
+			ResolvedType::VariableInt(_) => "r.var_uint()".to_string(),
+			ResolvedType::String => "r.string()".to_string(),
+			ResolvedType::Array(ty) => format!(
+				"r.array(lambda: {})",
+				Self::decode_expr(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) if matches!(**ty, ResolvedType::Option(_)) => format!(
+				"r.option(lambda: Some({}))",
+				Self::decode_expr(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) => format!(
+				"r.option(lambda: {})",
+				Self::decode_expr(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => format!(
+				"{}.decode(r)",
+				Self::declared_ident(schema, kind, naming_convention_config)
+			),
+		}
+	}
+
+	/// A dataclass with encode and decode methods, used for structs and procedure params.
+	fn generate_record<'a>(
+		schema: &ResolvedSchema,
+		ident: &str,
+		fields: impl Iterator<Item = (&'a Spanned<String>, &'a ResolvedType)> + Clone,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let fields = fields
+			.map(|(field, kind)| (Self::field_ident(naming_convention_config, &field.value), kind))
+			.collect::<Vec<_>>();
+
+		let mut decl_fields = fields
+			.iter()
+			.map(|(field, kind)| {
+				format!(
+					"    {field}: {}",
+					Self::resolved_type_to_py(schema, kind, naming_convention_config)
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+		if !decl_fields.is_empty() {
+			decl_fields.push('\n');
+		}
+
+		let encode_fields = if fields.is_empty() {
+			"        pass".to_string()
+		} else {
+			fields
+				.iter()
+				.map(|(field, kind)| format!("        {}", Self::encode_expr(kind, &format!("self.{field}"), 0)))
+				.collect::<Vec<String>>()
+				.join("\n")
+		};
+
+		// keyword arguments are evaluated from left to right, so the fields are read in order
+		let decode_fields = fields
+			.iter()
+			.map(|(field, kind)| {
+				format!(
+					"            {field}={},",
+					Self::decode_expr(schema, kind, naming_convention_config)
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+		let decode = if fields.is_empty() {
+			"        return cls()".to_string()
+		} else {
+			format!("        return cls(\n{decode_fields}\n        )")
+		};
+
+		write!(
+			output,
+			"
+
+@dataclass
+class {ident}:
+{decl_fields}
+    def encode(self, w: Writer) -> None:
+{encode_fields}
+
+    @classmethod
+    def decode(cls, r: Reader) -> {ident}:
+{decode}
+"
+		)
+		.unwrap();
+	}
+}
+
+impl CodeGenerator for PythonCodeGenerator {
+	fn reserved_words(&self) -> &'static [&'static str] {
+		&[
+			"False",
+			"None",
+			"True",
+			"and",
+			"as",
+			"assert",
+			"async",
+			"await",
+			"break",
+			"class",
+			"continue",
+			"def",
+			"del",
+			"elif",
+			"else",
+			"except",
+			"finally",
+			"for",
+			"from",
+			"global",
+			"if",
+			"import",
+			"in",
+			"is",
+			"lambda",
+			"nonlocal",
+			"not",
+			"or",
+			"pass",
+			"raise",
+			"return",
+			"try",
+			"while",
+			"with",
+			"yield",
+			"match",
+			"case",
+			"type",
+			"self",
+			"cls",
+			"int",
+			"str",
+			"bytes",
+			// methods of the generated classes
+			"encode",
+			"decode",
+			// imported or declared by the runtime
+			"annotations",
+			"asyncio",
+			"struct",
+			"deque",
+			"dataclass",
+			"IntEnum",
+			"Callable",
+			"Deque",
+			"Dict",
+			"Generic",
+			"List",
+			"Optional",
+			"TypeVar",
+			"T",
+			"MAX_PACKET_SIZE",
+			"OlympusError",
+			"Some",
+			"Writer",
+			"Reader",
+			"OlympusConnection",
+			"fnv1a",
+		]
+	}
+
+	fn generate_file_header(&self, output: &mut String) {
+		output.push_str(RUNTIME);
+	}
+
+	fn generate_enum(
+		&self,
+		resolved: &ResolvedEnum,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let ident = Self::type_ident(naming_convention_config, &resolved.ident.value);
+		let mut variants = resolved
+			.variants
+			.iter()
+			.map(|variant| {
+				format!(
+					"    {} = {}",
+					Self::variant_ident(naming_convention_config, &variant.ident.value),
+					variant.value
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+		if !variants.is_empty() {
+			variants.push('\n');
+		}
+
+		write!(
+			output,
+			"
+
+class {ident}(IntEnum):
+{variants}
+    def encode(self, w: Writer) -> None:
+        w.u16(self)
+
+    @classmethod
+    def decode(cls, r: Reader) -> {ident}:
+        tag = r.u16()
+        try:
+            return cls(tag)
+        except ValueError:
+            raise OlympusError(f\"Invalid {ident} tag: {{tag}}\") from None
+"
+		)
+		.unwrap();
+	}
+
+	fn generate_struct(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedStruct,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		Self::generate_record(
+			schema,
+			&Self::type_ident(naming_convention_config, &resolved.ident.value),
+			resolved.fields.iter().map(|field| (&field.ident, &field.kind.value)),
+			output,
+			naming_convention_config,
+		);
+	}
+
+	fn generate_procedure_params(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedProcedure,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		if resolved.params.is_empty() {
+			return;
+		}
+
+		Self::generate_record(
+			schema,
+			&self.procedure_params_ident(resolved, naming_convention_config),
+			resolved.params.iter().map(|param| (&param.ident, &param.kind.value)),
+			output,
+			naming_convention_config,
+		);
+	}
+
+	fn generate_client_impl(
+		&self,
+		schema: &ResolvedSchema,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let methods = schema
+			.procedures
+			.iter()
+			.map(|proc| {
+				let name = &proc.ident.value;
+				let return_ty = Self::return_type_to_py(schema, proc.return_kind.as_ref(), naming_convention_config);
+
+				let (params, write) = if proc.params.is_empty() {
+					(String::new(), "lambda w: None".to_string())
+				} else {
+					let params_ident = self.procedure_params_ident(proc, naming_convention_config);
+					(format!(", params: {params_ident}"), "params.encode".to_string())
+				};
+
+				let method = Self::proc_ident(naming_convention_config, name);
+				// the server doesn't respond to procedures without a return value, so there is nothing to wait for
+				match &proc.return_kind {
+					Some(kind) => format!(
+						"
+    async def {method}(self{params}) -> {return_ty}:
+        \"\"\"Calls `{name}` and waits for its response.\"\"\"
+        return await self.connection.call(\"{name}\", {write}, lambda r: {})",
+						Self::decode_expr(schema, &kind.value, naming_convention_config)
+					),
+					None => format!(
+						"
+    async def {method}(self{params}) -> None:
+        \"\"\"Sends `{name}` without waiting for the server, it doesn't return anything.\"\"\"
+        self.connection.send(\"{name}\", {write})
+        await self.connection.drain()"
+					),
+				}
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		write!(
+			output,
+			"
+
+class {}:
+    def __init__(self, connection: OlympusConnection) -> None:
+        self.connection = connection
+{methods}
+",
+			naming_convention_config.apply_types("ClientRpc")
+		)
+		.unwrap();
+	}
+}
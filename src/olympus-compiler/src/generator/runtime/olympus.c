@@ -0,0 +1,428 @@
+#ifndef OLYMPUS_RUNTIME_IMPLEMENTATION
+#define OLYMPUS_RUNTIME_IMPLEMENTATION
+
+#include <string.h>
+
+olympus_writer olympus_writer_init(uint8_t *data, size_t capacity) {
+	olympus_writer w;
+	w.data = data;
+	w.capacity = capacity;
+	w.len = 0;
+	return w;
+}
+
+olympus_reader olympus_reader_init(const uint8_t *data, size_t len, uint8_t *arena, size_t arena_capacity) {
+	olympus_reader r;
+	r.data = data;
+	r.len = len;
+	r.pos = 0;
+	r.arena = arena;
+	r.arena_capacity = arena_capacity;
+	r.arena_len = 0;
+	return r;
+}
+
+olympus_string olympus_string_from(const char *value) {
+	olympus_string string;
+	string.data = value;
+	string.len = (uint32_t)strlen(value);
+	return string;
+}
+
+/* Integers are big-endian. */
+static olympus_result olympus_write_be(olympus_writer *w, uint64_t value, size_t size) {
+	if (w->capacity - w->len < size) {
+		return OLYMPUS_ERR_BUFFER_FULL;
+	}
+
+	for (size_t idx = 0; idx < size; idx++) {
+		w->data[w->len + idx] = (uint8_t)(value >> (8 * (size - idx - 1)));
+	}
+	w->len += size;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_write_u8(olympus_writer *w, uint8_t value) {
+	return olympus_write_be(w, value, 1);
+}
+
+olympus_result olympus_write_u16(olympus_writer *w, uint16_t value) {
+	return olympus_write_be(w, value, 2);
+}
+
+olympus_result olympus_write_u32(olympus_writer *w, uint32_t value) {
+	return olympus_write_be(w, value, 4);
+}
+
+olympus_result olympus_write_u64(olympus_writer *w, uint64_t value) {
+	return olympus_write_be(w, value, 8);
+}
+
+olympus_result olympus_write_i8(olympus_writer *w, int8_t value) {
+	return olympus_write_be(w, (uint8_t)value, 1);
+}
+
+olympus_result olympus_write_i16(olympus_writer *w, int16_t value) {
+	return olympus_write_be(w, (uint16_t)value, 2);
+}
+
+olympus_result olympus_write_i32(olympus_writer *w, int32_t value) {
+	return olympus_write_be(w, (uint32_t)value, 4);
+}
+
+olympus_result olympus_write_i64(olympus_writer *w, int64_t value) {
+	return olympus_write_be(w, (uint64_t)value, 8);
+}
+
+/* LEB128, 7 bits per byte starting with the least significant ones. */
+olympus_result olympus_write_vu64(olympus_writer *w, uint64_t value) {
+	while (value >= 0x80) {
+		OLYMPUS_TRY(olympus_write_u8(w, (uint8_t)((value & 0x7f) | 0x80)));
+		value >>= 7;
+	}
+	return olympus_write_u8(w, (uint8_t)value);
+}
+
+olympus_result olympus_write_vu8(olympus_writer *w, uint8_t value) {
+	return olympus_write_vu64(w, value);
+}
+
+olympus_result olympus_write_vu16(olympus_writer *w, uint16_t value) {
+	return olympus_write_vu64(w, value);
+}
+
+olympus_result olympus_write_vu32(olympus_writer *w, uint32_t value) {
+	return olympus_write_vu64(w, value);
+}
+
+/* Zigzag encoded so small negative numbers stay short, then written like the unsigned varints. */
+static olympus_result olympus_write_zigzag(olympus_writer *w, int64_t value, unsigned bits) {
+	uint64_t zigzag = ((uint64_t)value << 1) ^ (value < 0 ? UINT64_MAX : 0);
+	if (bits < 64) {
+		zigzag &= ((uint64_t)1 << bits) - 1;
+	}
+	return olympus_write_vu64(w, zigzag);
+}
+
+olympus_result olympus_write_vi8(olympus_writer *w, int8_t value) {
+	return olympus_write_zigzag(w, value, 8);
+}
+
+olympus_result olympus_write_vi16(olympus_writer *w, int16_t value) {
+	return olympus_write_zigzag(w, value, 16);
+}
+
+olympus_result olympus_write_vi32(olympus_writer *w, int32_t value) {
+	return olympus_write_zigzag(w, value, 32);
+}
+
+olympus_result olympus_write_vi64(olympus_writer *w, int64_t value) {
+	return olympus_write_zigzag(w, value, 64);
+}
+
+olympus_result olympus_write_string(olympus_writer *w, olympus_string value) {
+	OLYMPUS_TRY(olympus_write_u32(w, value.len));
+	if (w->capacity - w->len < value.len) {
+		return OLYMPUS_ERR_BUFFER_FULL;
+	}
+
+	if (value.len > 0) {
+		memcpy(w->data + w->len, value.data, value.len);
+	}
+	w->len += value.len;
+	return OLYMPUS_OK;
+}
+
+static olympus_result olympus_read_be(olympus_reader *r, size_t size, uint64_t *out) {
+	if (r->len - r->pos < size) {
+		return OLYMPUS_ERR_UNEXPECTED_END;
+	}
+
+	uint64_t value = 0;
+	for (size_t idx = 0; idx < size; idx++) {
+		value = (value << 8) | r->data[r->pos + idx];
+	}
+	r->pos += size;
+	*out = value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_u8(olympus_reader *r, uint8_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 1, &value));
+	*out = (uint8_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_u16(olympus_reader *r, uint16_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 2, &value));
+	*out = (uint16_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_u32(olympus_reader *r, uint32_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 4, &value));
+	*out = (uint32_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_u64(olympus_reader *r, uint64_t *out) {
+	return olympus_read_be(r, 8, out);
+}
+
+olympus_result olympus_read_i8(olympus_reader *r, int8_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 1, &value));
+	*out = (int8_t)(uint8_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_i16(olympus_reader *r, int16_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 2, &value));
+	*out = (int16_t)(uint16_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_i32(olympus_reader *r, int32_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 4, &value));
+	*out = (int32_t)(uint32_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_i64(olympus_reader *r, int64_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 8, &value));
+	*out = (int64_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vu64(olympus_reader *r, uint64_t *out) {
+	uint64_t value = 0;
+	for (unsigned shift = 0; shift < 64; shift += 7) {
+		uint8_t byte;
+		OLYMPUS_TRY(olympus_read_u8(r, &byte));
+
+		value |= (uint64_t)(byte & 0x7f) << shift;
+		if ((byte & 0x80) == 0) {
+			*out = value;
+			return OLYMPUS_OK;
+		}
+	}
+	return OLYMPUS_ERR_INVALID_DATA;
+}
+
+olympus_result olympus_read_vu8(olympus_reader *r, uint8_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_vu64(r, &value));
+	*out = (uint8_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vu16(olympus_reader *r, uint16_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_vu64(r, &value));
+	*out = (uint16_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vu32(olympus_reader *r, uint32_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_vu64(r, &value));
+	*out = (uint32_t)value;
+	return OLYMPUS_OK;
+}
+
+static olympus_result olympus_read_zigzag(olympus_reader *r, unsigned bits, int64_t *out) {
+	uint64_t zigzag;
+	OLYMPUS_TRY(olympus_read_vu64(r, &zigzag));
+	if (bits < 64) {
+		zigzag &= ((uint64_t)1 << bits) - 1;
+	}
+
+	*out = (int64_t)(zigzag >> 1) ^ -(int64_t)(zigzag & 1);
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vi8(olympus_reader *r, int8_t *out) {
+	int64_t value;
+	OLYMPUS_TRY(olympus_read_zigzag(r, 8, &value));
+	*out = (int8_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vi16(olympus_reader *r, int16_t *out) {
+	int64_t value;
+	OLYMPUS_TRY(olympus_read_zigzag(r, 16, &value));
+	*out = (int16_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vi32(olympus_reader *r, int32_t *out) {
+	int64_t value;
+	OLYMPUS_TRY(olympus_read_zigzag(r, 32, &value));
+	*out = (int32_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vi64(olympus_reader *r, int64_t *out) {
+	return olympus_read_zigzag(r, 64, out);
+}
+
+olympus_result olympus_read_string(olympus_reader *r, olympus_string *out) {
+	uint32_t len;
+	OLYMPUS_TRY(olympus_read_u32(r, &len));
+	if (r->len - r->pos < len) {
+		return OLYMPUS_ERR_UNEXPECTED_END;
+	}
+
+	out->data = (const char *)(r->data + r->pos);
+	out->len = len;
+	r->pos += len;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_arena_alloc(olympus_reader *r, size_t item_size, uint32_t len, void **out) {
+	size_t start = (r->arena_len + OLYMPUS_ARENA_ALIGN - 1) / OLYMPUS_ARENA_ALIGN * OLYMPUS_ARENA_ALIGN;
+	if (start > r->arena_capacity || (item_size > 0 && len > (r->arena_capacity - start) / item_size)) {
+		return OLYMPUS_ERR_ARENA_FULL;
+	}
+
+	*out = r->arena + start;
+	r->arena_len = start + item_size * len;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_begin_request(olympus_writer *w, uint64_t hash, size_t *start) {
+	*start = w->len;
+	/* requests are never compressed, the length is filled in by olympus_end_request */
+	OLYMPUS_TRY(olympus_write_u8(w, 0));
+	OLYMPUS_TRY(olympus_write_u32(w, 0));
+	return olympus_write_u64(w, hash);
+}
+
+olympus_result olympus_end_request(olympus_writer *w, size_t start) {
+	size_t payload_len = w->len - start - 5;
+	if (payload_len > OLYMPUS_MAX_PACKET_SIZE) {
+		return OLYMPUS_ERR_INVALID_DATA;
+	}
+
+	olympus_writer header = olympus_writer_init(w->data + start + 1, 4);
+	return olympus_write_u32(&header, (uint32_t)payload_len);
+}
+
+/* Decompresses a raw LZ4 block, which is how large frames are compressed. */
+static olympus_result olympus_lz4_decompress(
+	const uint8_t *input,
+	size_t input_len,
+	uint8_t *output,
+	size_t output_len
+) {
+	size_t input_idx = 0;
+	size_t output_idx = 0;
+
+	while (input_idx < input_len) {
+		uint8_t token = input[input_idx++];
+
+		size_t literal_len = token >> 4;
+		if (literal_len == 15) {
+			uint8_t byte;
+			do {
+				if (input_idx >= input_len) {
+					return OLYMPUS_ERR_INVALID_DATA;
+				}
+				byte = input[input_idx++];
+				literal_len += byte;
+			} while (byte == 255);
+		}
+		if (literal_len > input_len - input_idx || literal_len > output_len - output_idx) {
+			return OLYMPUS_ERR_INVALID_DATA;
+		}
+		memcpy(output + output_idx, input + input_idx, literal_len);
+		input_idx += literal_len;
+		output_idx += literal_len;
+
+		/* the last sequence only has literals */
+		if (input_idx >= input_len) {
+			break;
+		}
+		if (input_len - input_idx < 2) {
+			return OLYMPUS_ERR_INVALID_DATA;
+		}
+
+		size_t offset = (size_t)input[input_idx] | ((size_t)input[input_idx + 1] << 8);
+		input_idx += 2;
+		size_t match_len = token & 0x0f;
+		if (match_len == 15) {
+			uint8_t byte;
+			do {
+				if (input_idx >= input_len) {
+					return OLYMPUS_ERR_INVALID_DATA;
+				}
+				byte = input[input_idx++];
+				match_len += byte;
+			} while (byte == 255);
+		}
+		match_len += 4;
+		if (offset == 0 || offset > output_idx || match_len > output_len - output_idx) {
+			return OLYMPUS_ERR_INVALID_DATA;
+		}
+
+		/* matches may overlap with the bytes they produce, so they have to be copied one at a time */
+		for (size_t idx = 0; idx < match_len; idx++) {
+			output[output_idx] = output[output_idx - offset];
+			output_idx++;
+		}
+	}
+
+	return output_idx == output_len ? OLYMPUS_OK : OLYMPUS_ERR_INVALID_DATA;
+}
+
+olympus_result olympus_read_frame(
+	const uint8_t *data,
+	size_t len,
+	uint8_t *scratch,
+	size_t scratch_capacity,
+	olympus_frame *out
+) {
+	olympus_reader header = olympus_reader_init(data, len, NULL, 0);
+	uint8_t compressed;
+	uint32_t data_len;
+	uint32_t decompressed_len = 0;
+	OLYMPUS_TRY(olympus_read_u8(&header, &compressed));
+	OLYMPUS_TRY(olympus_read_u32(&header, &data_len));
+	if (compressed) {
+		OLYMPUS_TRY(olympus_read_u32(&header, &decompressed_len));
+	}
+	if (data_len > OLYMPUS_MAX_PACKET_SIZE || decompressed_len > OLYMPUS_MAX_PACKET_SIZE) {
+		return OLYMPUS_ERR_INVALID_DATA;
+	}
+	if (len - header.pos < data_len) {
+		return OLYMPUS_ERR_UNEXPECTED_END;
+	}
+
+	const uint8_t *payload = data + header.pos;
+	size_t payload_len = data_len;
+	if (compressed) {
+		if (decompressed_len > scratch_capacity) {
+			return OLYMPUS_ERR_BUFFER_FULL;
+		}
+		OLYMPUS_TRY(olympus_lz4_decompress(payload, payload_len, scratch, decompressed_len));
+		payload = scratch;
+		payload_len = decompressed_len;
+	}
+
+	olympus_reader reader = olympus_reader_init(payload, payload_len, NULL, 0);
+	if (olympus_read_u64(&reader, &out->hash) != OLYMPUS_OK) {
+		return OLYMPUS_ERR_INVALID_DATA;
+	}
+	out->payload = payload + 8;
+	out->payload_len = payload_len - 8;
+	out->frame_len = header.pos + data_len;
+	return OLYMPUS_OK;
+}
+
+#endif /* OLYMPUS_RUNTIME_IMPLEMENTATION */
@@ -0,0 +1,154 @@
+/*
+ * Runtime for the olympus wire format, every generated type and request builds on it.
+ *
+ * Nothing allocates: encoding writes into a buffer supplied by the caller and decoding stores arrays in a
+ * caller-supplied arena, every write and read checks the space that is left. Define OLYMPUS_IMPLEMENTATION in exactly
+ * one source file before including this header to compile the implementation.
+ */
+
+#ifndef OLYMPUS_RUNTIME_H
+#define OLYMPUS_RUNTIME_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#if defined(__GNUC__) || defined(__clang__)
+#define OLYMPUS_MAYBE_UNUSED __attribute__((unused))
+#else
+#define OLYMPUS_MAYBE_UNUSED
+#endif
+
+/* Arrays are stored in the arena at multiples of this, it has to satisfy the alignment of every decoded type. */
+#ifndef OLYMPUS_ARENA_ALIGN
+#define OLYMPUS_ARENA_ALIGN 8
+#endif
+
+#define OLYMPUS_MAX_PACKET_SIZE (8u * 1024u * 1024u)
+
+typedef enum olympus_result {
+	OLYMPUS_OK = 0,
+	/* the writer's buffer can't fit the encoded value */
+	OLYMPUS_ERR_BUFFER_FULL,
+	/* the data ended in the middle of a value or frame, more data might complete it */
+	OLYMPUS_ERR_UNEXPECTED_END,
+	/* the reader's arena can't fit the decoded arrays */
+	OLYMPUS_ERR_ARENA_FULL,
+	/* the data doesn't match the wire format */
+	OLYMPUS_ERR_INVALID_DATA,
+} olympus_result;
+
+/* Returns the result of `expr` from the calling function if it isn't OLYMPUS_OK. */
+#define OLYMPUS_TRY(expr) \
+	do { \
+		olympus_result olympus_try_result = (expr); \
+		if (olympus_try_result != OLYMPUS_OK) { \
+			return olympus_try_result; \
+		} \
+	} while (0)
+
+typedef struct olympus_writer {
+	uint8_t *data;
+	size_t capacity;
+	size_t len;
+} olympus_writer;
+
+/*
+ * Decoded strings point into `data` and decoded arrays into `arena`, so both have to outlive the decoded values.
+ */
+typedef struct olympus_reader {
+	const uint8_t *data;
+	size_t len;
+	size_t pos;
+	uint8_t *arena;
+	size_t arena_capacity;
+	size_t arena_len;
+} olympus_reader;
+
+/* Not NUL terminated. */
+typedef struct olympus_string {
+	const char *data;
+	uint32_t len;
+} olympus_string;
+
+typedef struct olympus_frame {
+	/* identifies the procedure the frame belongs to, see the OLYMPUS_PROC_ constants */
+	uint64_t hash;
+	const uint8_t *payload;
+	size_t payload_len;
+	/* how many bytes of the input the frame took up */
+	size_t frame_len;
+} olympus_frame;
+
+olympus_writer olympus_writer_init(uint8_t *data, size_t capacity);
+olympus_reader olympus_reader_init(const uint8_t *data, size_t len, uint8_t *arena, size_t arena_capacity);
+olympus_string olympus_string_from(const char *value);
+
+olympus_result olympus_write_u8(olympus_writer *w, uint8_t value);
+olympus_result olympus_write_u16(olympus_writer *w, uint16_t value);
+olympus_result olympus_write_u32(olympus_writer *w, uint32_t value);
+olympus_result olympus_write_u64(olympus_writer *w, uint64_t value);
+olympus_result olympus_write_i8(olympus_writer *w, int8_t value);
+olympus_result olympus_write_i16(olympus_writer *w, int16_t value);
+olympus_result olympus_write_i32(olympus_writer *w, int32_t value);
+olympus_result olympus_write_i64(olympus_writer *w, int64_t value);
+olympus_result olympus_write_vu8(olympus_writer *w, uint8_t value);
+olympus_result olympus_write_vu16(olympus_writer *w, uint16_t value);
+olympus_result olympus_write_vu32(olympus_writer *w, uint32_t value);
+olympus_result olympus_write_vu64(olympus_writer *w, uint64_t value);
+olympus_result olympus_write_vi8(olympus_writer *w, int8_t value);
+olympus_result olympus_write_vi16(olympus_writer *w, int16_t value);
+olympus_result olympus_write_vi32(olympus_writer *w, int32_t value);
+olympus_result olympus_write_vi64(olympus_writer *w, int64_t value);
+olympus_result olympus_write_string(olympus_writer *w, olympus_string value);
+
+olympus_result olympus_read_u8(olympus_reader *r, uint8_t *out);
+olympus_result olympus_read_u16(olympus_reader *r, uint16_t *out);
+olympus_result olympus_read_u32(olympus_reader *r, uint32_t *out);
+olympus_result olympus_read_u64(olympus_reader *r, uint64_t *out);
+olympus_result olympus_read_i8(olympus_reader *r, int8_t *out);
+olympus_result olympus_read_i16(olympus_reader *r, int16_t *out);
+olympus_result olympus_read_i32(olympus_reader *r, int32_t *out);
+olympus_result olympus_read_i64(olympus_reader *r, int64_t *out);
+olympus_result olympus_read_vu8(olympus_reader *r, uint8_t *out);
+olympus_result olympus_read_vu16(olympus_reader *r, uint16_t *out);
+olympus_result olympus_read_vu32(olympus_reader *r, uint32_t *out);
+olympus_result olympus_read_vu64(olympus_reader *r, uint64_t *out);
+olympus_result olympus_read_vi8(olympus_reader *r, int8_t *out);
+olympus_result olympus_read_vi16(olympus_reader *r, int16_t *out);
+olympus_result olympus_read_vi32(olympus_reader *r, int32_t *out);
+olympus_result olympus_read_vi64(olympus_reader *r, int64_t *out);
+olympus_result olympus_read_string(olympus_reader *r, olympus_string *out);
+
+/* Reserves space for `len` items of `item_size` bytes in the arena. */
+olympus_result olympus_arena_alloc(olympus_reader *r, size_t item_size, uint32_t len, void **out);
+
+/*
+ * Starts a request frame for the procedure with the given hash, the params have to be written next.
+ * `start` has to be passed to olympus_end_request once they are.
+ */
+olympus_result olympus_begin_request(olympus_writer *w, uint64_t hash, size_t *start);
+olympus_result olympus_end_request(olympus_writer *w, size_t start);
+
+/*
+ * Parses the frame at the start of `data`, returning OLYMPUS_ERR_UNEXPECTED_END until all of it was received.
+ * Large responses are LZ4 compressed, they are decompressed into `scratch`, which should be able to hold the largest
+ * expected response.
+ */
+olympus_result olympus_read_frame(
+	const uint8_t *data,
+	size_t len,
+	uint8_t *scratch,
+	size_t scratch_capacity,
+	olympus_frame *out
+);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* OLYMPUS_RUNTIME_H */
@@ -0,0 +1,414 @@
+// Runtime for the olympus wire format, every generated type and the client below build on it.
+// The compiler writes the package above this, the runtime needs Kotlin 1.5 and kotlinx.coroutines.
+//
+//     val connection = OlympusConnection.connect("127.0.0.1", 9999)
+//     val client = ClientRpc(connection)
+
+import java.io.DataInputStream
+import java.io.IOException
+import java.net.InetSocketAddress
+import java.net.Socket
+import java.nio.ByteBuffer
+import java.nio.charset.CharacterCodingException
+import java.nio.charset.CodingErrorAction
+import kotlinx.coroutines.CompletableDeferred
+import kotlinx.coroutines.CoroutineScope
+import kotlinx.coroutines.Dispatchers
+import kotlinx.coroutines.SupervisorJob
+import kotlinx.coroutines.cancel
+import kotlinx.coroutines.launch
+import kotlinx.coroutines.sync.Mutex
+import kotlinx.coroutines.sync.withLock
+import kotlinx.coroutines.withContext
+
+/** Thrown for data that doesn't match the wire format and for calls on a closed connection. */
+class OlympusException(message: String, cause: Throwable? = null) : Exception(message, cause)
+
+/** Holds the value of an option nested in another option, `T??` is the same type as `T?`. */
+data class Some<out T>(val value: T)
+
+/** Serializes values in the olympus wire format. Integers are big-endian, like every [ByteBuffer]. */
+class Writer {
+    private var buffer: ByteBuffer = ByteBuffer.allocate(256)
+
+    fun toByteArray(): ByteArray = buffer.array().copyOf(buffer.position())
+
+    private fun reserve(size: Int): ByteBuffer {
+        if (buffer.remaining() < size) {
+            val grown = ByteBuffer.allocate(maxOf(buffer.capacity() * 2, buffer.position() + size))
+            buffer.flip()
+            grown.put(buffer)
+            buffer = grown
+        }
+        return buffer
+    }
+
+    fun u8(value: UByte) {
+        reserve(1).put(value.toByte())
+    }
+
+    fun i8(value: Byte) {
+        reserve(1).put(value)
+    }
+
+    fun u16(value: UShort) {
+        reserve(2).putShort(value.toShort())
+    }
+
+    fun i16(value: Short) {
+        reserve(2).putShort(value)
+    }
+
+    fun u32(value: UInt) {
+        reserve(4).putInt(value.toInt())
+    }
+
+    fun i32(value: Int) {
+        reserve(4).putInt(value)
+    }
+
+    fun u64(value: ULong) {
+        reserve(8).putLong(value.toLong())
+    }
+
+    fun i64(value: Long) {
+        reserve(8).putLong(value)
+    }
+
+    /** LEB128, 7 bits per byte starting with the least significant ones. */
+    fun varUint(value: ULong) {
+        var remaining = value
+        while (remaining >= 0x80uL) {
+            u8(((remaining and 0x7fuL) or 0x80uL).toUByte())
+            remaining = remaining shr 7
+        }
+        u8(remaining.toUByte())
+    }
+
+    /** Zigzag encodes a value of the given width so small negative numbers stay short, then writes it like [varUint]. */
+    fun varInt(value: Long, bits: Int) {
+        var zigzag = ((value shl 1) xor (value shr 63)).toULong()
+        if (bits < 64) {
+            zigzag = zigzag and ((1uL shl bits) - 1uL)
+        }
+        varUint(zigzag)
+    }
+
+    fun string(value: String) {
+        val bytes = value.encodeToByteArray()
+        u32(bytes.size.toUInt())
+        reserve(bytes.size).put(bytes)
+    }
+
+    fun <T> array(value: List<T>, write: (T) -> Unit) {
+        u32(value.size.toUInt())
+        value.forEach(write)
+    }
+
+    fun <T : Any> option(value: T?, write: (T) -> Unit) {
+        if (value == null) {
+            u8(0u)
+        } else {
+            u8(1u)
+            write(value)
+        }
+    }
+}
+
+/** Deserializes values in the olympus wire format, the counterpart to [Writer]. */
+class Reader(data: ByteArray) {
+    private val buffer: ByteBuffer = ByteBuffer.wrap(data)
+
+    val remaining: Int
+        get() = buffer.remaining()
+
+    private fun take(size: Int): ByteBuffer {
+        if (size > buffer.remaining()) {
+            throw OlympusException("unexpected end of data")
+        }
+        return buffer
+    }
+
+    fun u8(): UByte = take(1).get().toUByte()
+
+    fun i8(): Byte = take(1).get()
+
+    fun u16(): UShort = take(2).getShort().toUShort()
+
+    fun i16(): Short = take(2).getShort()
+
+    fun u32(): UInt = take(4).getInt().toUInt()
+
+    fun i32(): Int = take(4).getInt()
+
+    fun u64(): ULong = take(8).getLong().toULong()
+
+    fun i64(): Long = take(8).getLong()
+
+    fun varUint(): ULong {
+        var value = 0uL
+        for (shift in 0 until 64 step 7) {
+            val b = u8().toULong()
+            value = value or ((b and 0x7fuL) shl shift)
+            if ((b and 0x80uL) == 0uL) {
+                return value
+            }
+        }
+        throw OlympusException("varint is too long")
+    }
+
+    fun varInt(bits: Int): Long {
+        var zigzag = varUint()
+        if (bits < 64) {
+            zigzag = zigzag and ((1uL shl bits) - 1uL)
+        }
+        return (zigzag shr 1).toLong() xor -(zigzag and 1uL).toLong()
+    }
+
+    fun string(): String {
+        val size = u32()
+        if (size > remaining.toUInt()) {
+            throw OlympusException("unexpected end of data")
+        }
+
+        val bytes = ByteArray(size.toInt())
+        buffer.get(bytes)
+        val decoder = Charsets.UTF_8.newDecoder()
+            .onMalformedInput(CodingErrorAction.REPORT)
+            .onUnmappableCharacter(CodingErrorAction.REPORT)
+        try {
+            return decoder.decode(ByteBuffer.wrap(bytes)).toString()
+        } catch (e: CharacterCodingException) {
+            throw OlympusException("string isn't valid UTF-8", e)
+        }
+    }
+
+    fun <T> array(read: () -> T): List<T> {
+        val count = u32()
+        // a bogus length shouldn't allocate more up front than the data that is left
+        val items = ArrayList<T>(minOf(count, remaining.toUInt()).toInt())
+        for (idx in 0u until count) {
+            items.add(read())
+        }
+        return items
+    }
+
+    fun <T : Any> option(read: () -> T): T? = if (u8() == 0.toUByte()) null else read()
+}
+
+/**
+ * Frames requests and matches responses to calls.
+ *
+ * Every frame starts with a compressed flag and the data length, compressed frames also carry the decompressed
+ * length. Requests are always sent uncompressed.
+ */
+class OlympusConnection private constructor(private val socket: Socket) : AutoCloseable {
+    private val input = DataInputStream(socket.getInputStream().buffered())
+    private val output = socket.getOutputStream()
+
+    // also queues calls to the same procedure in the order they were sent
+    private val writeLock = Mutex()
+
+    // guarded by synchronizing on it, together with error
+    private val pending = HashMap<ULong, ArrayDeque<CompletableDeferred<ByteArray>>>()
+    private var error: Throwable? = null
+    private val scope = CoroutineScope(SupervisorJob() + Dispatchers.IO)
+
+    init {
+        scope.launch { readFrames() }
+    }
+
+    companion object {
+        private const val MAX_PACKET_SIZE = 8 * 1024 * 1024
+
+        suspend fun connect(host: String, port: Int): OlympusConnection = withContext(Dispatchers.IO) {
+            val socket = Socket()
+            try {
+                socket.tcpNoDelay = true
+                socket.connect(InetSocketAddress(host, port))
+            } catch (e: IOException) {
+                socket.close()
+                throw e
+            }
+            OlympusConnection(socket)
+        }
+
+        /** The 64 bit FNV-1a hash, procedures are identified by the hash of their name. */
+        fun fnv1a(value: String): ULong {
+            var hash = 0xcbf29ce484222325uL
+            for (b in value.encodeToByteArray()) {
+                hash = (hash xor b.toUByte().toULong()) * 0x100000001b3uL
+            }
+            return hash
+        }
+    }
+
+    /** Sends a request without waiting for a response. */
+    suspend fun send(procedure: String, write: (Writer) -> Unit) {
+        send(procedure, write, null)
+    }
+
+    /** Sends a request and reads the response to it with [read]. */
+    suspend fun <T> call(procedure: String, write: (Writer) -> Unit, read: (Reader) -> T): T {
+        val response = CompletableDeferred<ByteArray>()
+        send(procedure, write, response)
+        return read(Reader(response.await()))
+    }
+
+    private suspend fun send(procedure: String, write: (Writer) -> Unit, response: CompletableDeferred<ByteArray>?) {
+        val hash = fnv1a(procedure)
+        val w = Writer()
+        // the length is filled in once the params are written
+        w.u8(0u)
+        w.u32(0u)
+        w.u64(hash)
+        write(w)
+
+        val frame = w.toByteArray()
+        val payloadLength = frame.size - 5
+        if (payloadLength > MAX_PACKET_SIZE) {
+            throw OlympusException("request is $payloadLength bytes, the limit is $MAX_PACKET_SIZE")
+        }
+        ByteBuffer.wrap(frame).putInt(1, payloadLength)
+
+        writeLock.withLock {
+            synchronized(pending) {
+                error?.let { throw OlympusException("connection closed", it) }
+                if (response != null) {
+                    pending.getOrPut(hash) { ArrayDeque() }.addLast(response)
+                }
+            }
+
+            withContext(Dispatchers.IO) {
+                output.write(frame)
+                output.flush()
+            }
+        }
+    }
+
+    private fun readFrames() {
+        try {
+            while (true) {
+                val compressed = input.read()
+                if (compressed == -1) {
+                    break
+                }
+
+                val dataLength = input.readInt()
+                val decompressedLength = if (compressed != 0) input.readInt() else 0
+                if (dataLength !in 0..MAX_PACKET_SIZE || decompressedLength !in 0..MAX_PACKET_SIZE) {
+                    throw OlympusException("frame exceeds the maximum packet size")
+                }
+
+                var data = ByteArray(dataLength)
+                input.readFully(data)
+                if (compressed != 0) {
+                    data = lz4Decompress(data, decompressedLength)
+                }
+                if (data.size < 8) {
+                    throw OlympusException("frame is too short to hold a procedure hash")
+                }
+
+                val hash = ByteBuffer.wrap(data).getLong().toULong()
+                val response = synchronized(pending) {
+                    pending[hash]?.let { queue ->
+                        queue.removeFirst().also {
+                            if (queue.isEmpty()) {
+                                pending.remove(hash)
+                            }
+                        }
+                    }
+                }
+                response?.complete(data.copyOfRange(8, data.size))
+            }
+
+            fail(OlympusException("connection closed by the server"))
+        } catch (e: Exception) {
+            fail(e)
+        }
+    }
+
+    private fun fail(cause: Throwable) {
+        val responses = synchronized(pending) {
+            if (error == null) {
+                error = cause
+            }
+            pending.values.flatten().also { pending.clear() }
+        }
+
+        for (response in responses) {
+            response.completeExceptionally(OlympusException("connection closed", cause))
+        }
+    }
+
+    override fun close() {
+        socket.close()
+        scope.cancel()
+    }
+}
+
+/** Decompresses a raw LZ4 block, which is how large frames are compressed. */
+private fun lz4Decompress(input: ByteArray, decompressedLength: Int): ByteArray {
+    val output = ByteArray(decompressedLength)
+    var inputIdx = 0
+    var outputIdx = 0
+
+    fun invalid(): Nothing = throw OlympusException("invalid LZ4 block")
+
+    fun readLength(initial: Int): Int {
+        if (initial != 15) {
+            return initial
+        }
+
+        var length = initial
+        while (true) {
+            if (inputIdx >= input.size) {
+                invalid()
+            }
+
+            val b = input[inputIdx++].toInt() and 0xff
+            length += b
+            if (b != 255) {
+                return length
+            }
+        }
+    }
+
+    while (inputIdx < input.size) {
+        val token = input[inputIdx++].toInt() and 0xff
+
+        val literalLength = readLength(token shr 4)
+        if (literalLength > input.size - inputIdx || literalLength > output.size - outputIdx) {
+            invalid()
+        }
+        input.copyInto(output, outputIdx, inputIdx, inputIdx + literalLength)
+        inputIdx += literalLength
+        outputIdx += literalLength
+
+        // the last sequence only has literals
+        if (inputIdx >= input.size) {
+            break
+        }
+        if (input.size - inputIdx < 2) {
+            invalid()
+        }
+
+        val offset = (input[inputIdx].toInt() and 0xff) or ((input[inputIdx + 1].toInt() and 0xff) shl 8)
+        inputIdx += 2
+        val matchLength = readLength(token and 0x0f) + 4
+        if (offset == 0 || offset > outputIdx || matchLength > output.size - outputIdx) {
+            invalid()
+        }
+
+        // matches may overlap with the bytes they produce, so they have to be copied one at a time
+        repeat(matchLength) {
+            output[outputIdx] = output[outputIdx - offset]
+            outputIdx++
+        }
+    }
+
+    if (outputIdx != output.size) {
+        invalid()
+    }
+    return output
+}
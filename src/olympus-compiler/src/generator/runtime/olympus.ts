@@ -0,0 +1,413 @@
+/* eslint-disable */
+// Runtime for the olympus wire format, every generated type and the client below build on it.
+//
+// The connection doesn't open sockets itself so it works in browsers and Node alike, e.g. with Node's `net`:
+//
+//   const socket = net.connect(9999, "127.0.0.1");
+//   const connection = new OlympusConnection({ write: (data) => socket.write(data) });
+//   socket.on("data", (data) => connection.receive(data));
+//   socket.on("close", () => connection.close());
+//   const client = new ClientRpc(connection);
+
+const textEncoder = new TextEncoder();
+const textDecoder = new TextDecoder("utf-8", { fatal: true });
+
+export class OlympusError extends Error {}
+
+/** Holds the value of an option nested in another option, `T | null | null` is the same type as `T | null`. */
+export interface Some<T> {
+	value: T;
+}
+
+/** Serializes values in the olympus wire format. Integers are big-endian. */
+export class Writer {
+	private buffer = new Uint8Array(64);
+	private view = new DataView(this.buffer.buffer);
+	private length = 0;
+
+	/** Grows the buffer, replacing it and the view, so only access them once this returned. */
+	private reserve(size: number): number {
+		const offset = this.length;
+		if (offset + size > this.buffer.length) {
+			let capacity = this.buffer.length * 2;
+			while (capacity < offset + size) {
+				capacity *= 2;
+			}
+
+			const buffer = new Uint8Array(capacity);
+			buffer.set(this.buffer.subarray(0, offset));
+			this.buffer = buffer;
+			this.view = new DataView(buffer.buffer);
+		}
+
+		this.length += size;
+		return offset;
+	}
+
+	finish(): Uint8Array {
+		return this.buffer.slice(0, this.length);
+	}
+
+	bytes(value: Uint8Array): void {
+		const offset = this.reserve(value.length);
+		this.buffer.set(value, offset);
+	}
+
+	u8(value: number): void {
+		const offset = this.reserve(1);
+		this.view.setUint8(offset, value);
+	}
+
+	i8(value: number): void {
+		const offset = this.reserve(1);
+		this.view.setInt8(offset, value);
+	}
+
+	u16(value: number): void {
+		const offset = this.reserve(2);
+		this.view.setUint16(offset, value);
+	}
+
+	i16(value: number): void {
+		const offset = this.reserve(2);
+		this.view.setInt16(offset, value);
+	}
+
+	u32(value: number): void {
+		const offset = this.reserve(4);
+		this.view.setUint32(offset, value);
+	}
+
+	i32(value: number): void {
+		const offset = this.reserve(4);
+		this.view.setInt32(offset, value);
+	}
+
+	u64(value: bigint): void {
+		const offset = this.reserve(8);
+		this.view.setBigUint64(offset, value);
+	}
+
+	i64(value: bigint): void {
+		const offset = this.reserve(8);
+		this.view.setBigInt64(offset, value);
+	}
+
+	/** LEB128, 7 bits per byte starting with the least significant ones. */
+	varUint(value: number | bigint): void {
+		let remaining = BigInt(value);
+		while (remaining >= 0x80n) {
+			this.u8(Number(remaining & 0x7fn) | 0x80);
+			remaining >>= 7n;
+		}
+		this.u8(Number(remaining));
+	}
+
+	/** Zigzag encoded so small negative numbers stay short, then written like {@link Writer.varUint}. */
+	varInt(value: number | bigint, bits: number): void {
+		const signed = BigInt(value);
+		this.varUint(BigInt.asUintN(bits, (signed << 1n) ^ (signed >> BigInt(bits - 1))));
+	}
+
+	string(value: string): void {
+		const bytes = textEncoder.encode(value);
+		this.u32(bytes.length);
+		this.bytes(bytes);
+	}
+
+	array<T>(value: readonly T[], write: (item: T) => void): void {
+		this.u32(value.length);
+		for (const item of value) {
+			write(item);
+		}
+	}
+
+	option<T>(value: T | null | undefined, write: (item: T) => void): void {
+		if (value === null || value === undefined) {
+			this.u8(0);
+		} else {
+			this.u8(1);
+			write(value);
+		}
+	}
+}
+
+/** Deserializes values in the olympus wire format, the counterpart to {@link Writer}. */
+export class Reader {
+	private readonly data: Uint8Array;
+	private readonly view: DataView;
+	private offset = 0;
+
+	constructor(data: Uint8Array) {
+		this.data = data;
+		this.view = new DataView(data.buffer, data.byteOffset, data.byteLength);
+	}
+
+	private advance(size: number): number {
+		const offset = this.offset;
+		if (offset + size > this.data.length) {
+			throw new OlympusError("Unexpected end of data");
+		}
+
+		this.offset += size;
+		return offset;
+	}
+
+	u8(): number {
+		return this.view.getUint8(this.advance(1));
+	}
+
+	i8(): number {
+		return this.view.getInt8(this.advance(1));
+	}
+
+	u16(): number {
+		return this.view.getUint16(this.advance(2));
+	}
+
+	i16(): number {
+		return this.view.getInt16(this.advance(2));
+	}
+
+	u32(): number {
+		return this.view.getUint32(this.advance(4));
+	}
+
+	i32(): number {
+		return this.view.getInt32(this.advance(4));
+	}
+
+	u64(): bigint {
+		return this.view.getBigUint64(this.advance(8));
+	}
+
+	i64(): bigint {
+		return this.view.getBigInt64(this.advance(8));
+	}
+
+	varUint(): bigint {
+		let value = 0n;
+		let shift = 0n;
+		for (;;) {
+			const byte = this.u8();
+			value |= BigInt(byte & 0x7f) << shift;
+			shift += 7n;
+			if ((byte & 0x80) === 0) {
+				return value;
+			}
+		}
+	}
+
+	varInt(bits: number): bigint {
+		const zigzag = BigInt.asUintN(bits, this.varUint());
+		return (zigzag >> 1n) ^ -(zigzag & 1n);
+	}
+
+	string(): string {
+		const length = this.u32();
+		const offset = this.advance(length);
+		return textDecoder.decode(this.data.subarray(offset, offset + length));
+	}
+
+	array<T>(read: () => T): T[] {
+		const length = this.u32();
+		const items: T[] = [];
+		for (let idx = 0; idx < length; idx++) {
+			items.push(read());
+		}
+		return items;
+	}
+
+	option<T>(read: () => T): T | null {
+		return this.u8() === 0 ? null : read();
+	}
+}
+
+/** 64 bit FNV-1a, procedures are identified by the hash of their name. */
+export function fnv1a(value: string): bigint {
+	let hash = 0xcbf29ce484222325n;
+	for (const byte of textEncoder.encode(value)) {
+		hash ^= BigInt(byte);
+		hash = BigInt.asUintN(64, hash * 0x100000001b3n);
+	}
+	return hash;
+}
+
+/** Decompresses a raw LZ4 block, which is how large frames are compressed. */
+function lz4Decompress(input: Uint8Array, decompressedLength: number): Uint8Array {
+	const output = new Uint8Array(decompressedLength);
+	let inputIdx = 0;
+	let outputIdx = 0;
+
+	const readLength = (length: number): number => {
+		if (length !== 15) {
+			return length;
+		}
+
+		let byte: number;
+		do {
+			byte = input[inputIdx++];
+			length += byte;
+		} while (byte === 255);
+		return length;
+	};
+
+	while (inputIdx < input.length) {
+		const token = input[inputIdx++];
+
+		const literalLength = readLength(token >> 4);
+		if (inputIdx + literalLength > input.length || outputIdx + literalLength > output.length) {
+			throw new OlympusError("Invalid LZ4 block");
+		}
+		output.set(input.subarray(inputIdx, inputIdx + literalLength), outputIdx);
+		inputIdx += literalLength;
+		outputIdx += literalLength;
+
+		// the last sequence only has literals
+		if (inputIdx >= input.length) {
+			break;
+		}
+
+		const offset = input[inputIdx] | (input[inputIdx + 1] << 8);
+		inputIdx += 2;
+		const matchLength = readLength(token & 0x0f) + 4;
+		if (offset === 0 || offset > outputIdx || outputIdx + matchLength > output.length) {
+			throw new OlympusError("Invalid LZ4 block");
+		}
+
+		// matches may overlap with the bytes they produce, so they have to be copied one at a time
+		for (let idx = 0; idx < matchLength; idx++) {
+			output[outputIdx] = output[outputIdx - offset];
+			outputIdx++;
+		}
+	}
+
+	if (outputIdx !== decompressedLength) {
+		throw new OlympusError("Invalid LZ4 block");
+	}
+	return output;
+}
+
+export interface Transport {
+	write(data: Uint8Array): void;
+}
+
+interface PendingCall {
+	resolve(data: Uint8Array): void;
+	reject(error: Error): void;
+}
+
+const MAX_PACKET_SIZE = 8 * 1024 * 1024;
+
+/**
+ * Frames requests and matches responses to calls.
+ *
+ * Every frame starts with a compressed flag and the data length, compressed frames also carry the decompressed length.
+ * Requests are always sent uncompressed.
+ */
+export class OlympusConnection {
+	private readonly transport: Transport;
+	private readonly pending = new Map<bigint, PendingCall[]>();
+	private buffered = new Uint8Array(0);
+
+	constructor(transport: Transport) {
+		this.transport = transport;
+	}
+
+	/** Sends a request without waiting for a response. */
+	send(procedure: string, write: (w: Writer) => void): void {
+		const payload = new Writer();
+		payload.u64(fnv1a(procedure));
+		write(payload);
+		const data = payload.finish();
+
+		const frame = new Writer();
+		frame.u8(0);
+		frame.u32(data.length);
+		frame.bytes(data);
+		this.transport.write(frame.finish());
+	}
+
+	/**
+	 * Sends a request and resolves with its decoded response.
+	 *
+	 * The server answers the requests of a connection in order, so responses are matched to calls in the order they
+	 * were sent.
+	 */
+	call<T>(procedure: string, write: (w: Writer) => void, read: (r: Reader) => T): Promise<T> {
+		return new Promise<T>((resolve, reject) => {
+			const hash = fnv1a(procedure);
+			const queue = this.pending.get(hash) ?? [];
+			queue.push({
+				resolve: (data) => {
+					try {
+						resolve(read(new Reader(data)));
+					} catch (error) {
+						reject(error);
+					}
+				},
+				reject,
+			});
+			this.pending.set(hash, queue);
+
+			try {
+				this.send(procedure, write);
+			} catch (error) {
+				queue.pop();
+				reject(error);
+			}
+		});
+	}
+
+	/** Feeds bytes received from the transport, in the order they arrived. */
+	receive(data: Uint8Array): void {
+		const buffered = new Uint8Array(this.buffered.length + data.length);
+		buffered.set(this.buffered);
+		buffered.set(data, this.buffered.length);
+		this.buffered = buffered;
+
+		for (;;) {
+			const frame = this.nextFrame();
+			if (frame === null) {
+				break;
+			}
+
+			const reader = new Reader(frame);
+			const hash = reader.u64();
+			const call = this.pending.get(hash)?.shift();
+			call?.resolve(frame.subarray(8));
+		}
+	}
+
+	/** Rejects every call that is still waiting for a response, call this once the transport closed. */
+	close(error: Error = new OlympusError("Connection closed")): void {
+		for (const queue of this.pending.values()) {
+			for (const call of queue) {
+				call.reject(error);
+			}
+		}
+		this.pending.clear();
+	}
+
+	private nextFrame(): Uint8Array | null {
+		const header = new DataView(this.buffered.buffer, this.buffered.byteOffset, this.buffered.byteLength);
+		if (header.byteLength < 5) {
+			return null;
+		}
+
+		const compressed = header.getUint8(0) !== 0;
+		const length = header.getUint32(1);
+		if (length > MAX_PACKET_SIZE) {
+			throw new OlympusError(`Packet too big: ${length} bytes`);
+		}
+
+		const headerLength = compressed ? 9 : 5;
+		if (header.byteLength < headerLength + length) {
+			return null;
+		}
+
+		const data = this.buffered.subarray(headerLength, headerLength + length);
+		this.buffered = this.buffered.subarray(headerLength + length);
+		return compressed ? lz4Decompress(data, header.getUint32(5)) : data;
+	}
+}
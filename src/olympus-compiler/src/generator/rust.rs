@@ -1,344 +1,723 @@
-use crate::cli::NamingConventionConfig;
-use olympus_lexer::IntToken;
-use olympus_parser::{ParsedBultin, ParsedEnum, ParsedProcedure, ParsedStruct, ParsedTypeKind};
+use std::collections::BTreeMap;
+
+use crate::naming::NamingConventionConfig;
 use olympus_spanned::Spanned;
+use olympus_verifier::{IntKind, ResolvedEnum, ResolvedProcedure, ResolvedSchema, ResolvedStruct, ResolvedType};
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::quote;
 
 use super::CodeGenerator;
 
-pub struct RustCodeGenerator;
+#[derive(Debug, Clone, Default)]
+pub struct RustCodeGenerator {
+	/// Derived for every generated enum and struct on top of the ones the generated code needs, each has to be a
+	/// path like `PartialEq` or `serde::Serialize`.
+	pub derives: Vec<String>,
+	/// Derived for the enum or struct with the schema name of the key on top of `derives`.
+	pub type_derives: BTreeMap<String, Vec<String>>,
+	/// Derive `Serialize` and `Deserialize` when the `serde` feature is enabled, through the re-export of
+	/// `olympus-net-common`.
+	pub serde: bool,
+}
+
+/// Keywords that can't be used as raw identifiers and get a trailing underscore instead.
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+
+/// The path serde's derives and attributes are referenced through, so generated crates don't need to depend on it.
+const SERDE: &str = "::olympus_net_common::serde";
+
+/// Formats generated items like rustfmt would.
+///
+/// # Panics
+///
+/// If `tokens` aren't a valid file, which would be a bug in the generator.
+#[must_use]
+pub fn pretty(tokens: TokenStream) -> String {
+	let syn::File { shebang, attrs, items } =
+		syn::parse2::<syn::File>(tokens).expect("the generated code is a valid file");
+
+	// prettyplease doesn't put blank lines between items, so every item is printed on its own, the inner attributes
+	// of the file along with the first one
+	let mut items = items.into_iter();
+	let mut parts = vec![prettyplease::unparse(&syn::File {
+		shebang,
+		attrs,
+		items: items.next().into_iter().collect(),
+	})];
+	parts.extend(items.map(|item| {
+		prettyplease::unparse(&syn::File {
+			shebang: None,
+			attrs: Vec::new(),
+			items: vec![item],
+		})
+	}));
+	parts.join("\n")
+}
+
+/// The token for an identifier [`RustCodeGenerator::escape_ident`] returned.
+fn ident(escaped: &str) -> Ident {
+	match escaped.strip_prefix("r#") {
+		Some(raw) => Ident::new_raw(raw, Span::call_site()),
+		None => Ident::new(escaped, Span::call_site()),
+	}
+}
 
 impl RustCodeGenerator {
-	fn generate_enum_decl(parsed: &ParsedEnum, output: &mut String, naming_convention_config: &NamingConventionConfig) {
-		let variants = parsed
-			.variants
+	fn type_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> Ident {
+		self::ident(&RustCodeGenerator::default().escape_ident(&naming_convention_config.apply_types(ident)))
+	}
+
+	fn variant_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> Ident {
+		self::ident(&RustCodeGenerator::default().escape_ident(&naming_convention_config.apply_enum_variants(ident)))
+	}
+
+	fn field_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> Ident {
+		self::ident(&RustCodeGenerator::default().escape_ident(&naming_convention_config.apply_struct_fields(ident)))
+	}
+
+	fn proc_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> Ident {
+		self::ident(&RustCodeGenerator::default().escape_ident(&naming_convention_config.apply_procs(ident)))
+	}
+
+	fn params_ident(&self, proc: &ResolvedProcedure, naming_convention_config: &NamingConventionConfig) -> Ident {
+		ident(&self.procedure_params_ident(proc, naming_convention_config))
+	}
+
+	/// The `models`, `server` and `client` modules a generated crate has as inline modules, for code that is
+	/// included into another crate instead.
+	#[must_use]
+	pub fn generate_modules(
+		&self,
+		schema: &ResolvedSchema,
+		naming_convention_config: &NamingConventionConfig,
+	) -> TokenStream {
+		let models = self.models_module(schema, naming_convention_config);
+		let server = self.server_module(schema, naming_convention_config);
+		let client = self.client_module(schema, naming_convention_config);
+		quote! {
+			pub mod models {
+				#models
+			}
+			pub mod server {
+				#server
+			}
+			pub mod client {
+				#client
+			}
+		}
+	}
+
+	/// Every enum, struct and procedure params struct with their codecs.
+	#[must_use]
+	pub fn models_module(
+		&self,
+		schema: &ResolvedSchema,
+		naming_convention_config: &NamingConventionConfig,
+	) -> TokenStream {
+		let header = Self::file_header();
+		let models = self.models(schema, naming_convention_config);
+		quote! {
+			#header
+			#models
+		}
+	}
+
+	/// The `ServerRpc` trait and `register_procedures`, which need `olympus-server`.
+	#[must_use]
+	pub fn server_module(
+		&self,
+		schema: &ResolvedSchema,
+		naming_convention_config: &NamingConventionConfig,
+	) -> TokenStream {
+		let header = Self::file_header();
+		let server = self.abstract_server_impl(schema, naming_convention_config);
+		let registration = Self::server_registration_fn(schema, naming_convention_config);
+		// the schema types are only named through `models`, so a `struct Box` or `struct Send` doesn't shadow the
+		// prelude names `async_trait` expands to
+		quote! {
+			#header
+			use super::models;
+			#server
+			#registration
+		}
+	}
+
+	/// `ClientRpc`, which needs `olympus-client`.
+	#[must_use]
+	pub fn client_module(
+		&self,
+		schema: &ResolvedSchema,
+		naming_convention_config: &NamingConventionConfig,
+	) -> TokenStream {
+		let header = Self::file_header();
+		let client = self.client_impl(schema, naming_convention_config);
+		quote! {
+			#header
+			use super::models;
+			#client
+		}
+	}
+
+	fn file_header() -> TokenStream {
+		quote! {
+			#![allow(unused_qualifications)]
+			#![allow(non_snake_case)]
+		}
+	}
+
+	fn models(&self, schema: &ResolvedSchema, naming_convention_config: &NamingConventionConfig) -> TokenStream {
+		let enums = schema
+			.enums
 			.iter()
-			.map(|variant| {
-				format!(
-					"\t{} = {},",
-					naming_convention_config.apply_enum_variants(&variant.ident.value),
-					variant.value
-				)
-			})
-			.collect::<Vec<String>>()
-			.join("\n");
-
-		output.push_str(&format!(
-			"
-#[derive(Debug, Clone, Copy)]
-#[repr(u16)]
-pub enum {} {{
-{variants}
-}}\n",
-			naming_convention_config.apply_types(&parsed.ident.value)
-		));
-	}
-
-	fn generate_enum_input_impl(
-		parsed: &ParsedEnum,
-		output: &mut String,
+			.map(|r#enum| self.enum_tokens(r#enum, naming_convention_config));
+		let structs = schema
+			.structs
+			.iter()
+			.map(|r#struct| self.struct_tokens(schema, r#struct, naming_convention_config));
+		let params = schema
+			.procedures
+			.iter()
+			.map(|proc| self.procedure_params_tokens(schema, proc, naming_convention_config));
+		quote! {
+			#(#enums)*
+			#(#structs)*
+			#(#params)*
+		}
+	}
+
+	/// `base` and the derives configured for every type and for the schema type `ident`, without duplicates.
+	fn derives(&self, base: &[&str], ident: Option<&str>) -> Vec<String> {
+		let mut derives = base.iter().map(ToString::to_string).collect::<Vec<_>>();
+		let configured = ident
+			.and_then(|ident| self.type_derives.get(ident))
+			.into_iter()
+			.flatten();
+		for derive in self.derives.iter().chain(configured) {
+			if !derives.contains(derive) {
+				derives.push(derive.clone());
+			}
+		}
+		derives
+	}
+
+	/// The derive attribute for `derives` and the serde derives behind the `serde` feature if enabled.
+	///
+	/// # Panics
+	///
+	/// If a derive isn't a path, [`super::generate`] checks them before generating anything.
+	fn derive_attrs(&self, derives: &[String]) -> TokenStream {
+		let derives = derives
+			.iter()
+			.map(|derive| syn::parse_str::<syn::Path>(derive).expect("derives are paths"));
+		let serde = self.serde.then(|| {
+			let serde = syn::parse_str::<syn::Path>(SERDE).expect("the serde path is valid");
+			quote! {
+				#[cfg_attr(feature = "serde", derive(#serde::Serialize, #serde::Deserialize), serde(crate = #SERDE))]
+			}
+		});
+		quote! {
+			#[derive(#(#derives),*)]
+			#serde
+		}
+	}
+
+	/// Renames an escaped identifier back to the name the naming convention gave it, `r#` is stripped by serde itself.
+	fn serde_rename(&self, name: &str, ident: &Ident) -> Option<TokenStream> {
+		let ident = ident.to_string();
+		if !self.serde || ident.trim_start_matches("r#") == name {
+			return None;
+		}
+		Some(quote!(#[cfg_attr(feature = "serde", serde(rename = #name))]))
+	}
+
+	fn enum_tokens(&self, parsed: &ResolvedEnum, naming_convention_config: &NamingConventionConfig) -> TokenStream {
+		let rust_ident = Self::type_ident(naming_convention_config, &parsed.ident.value);
+		let derives = self.derives(&["Debug", "Clone", "Copy"], Some(&parsed.ident.value));
+		// deriving `Default` for an enum needs a variant to be marked as the default
+		let has_default = derives.iter().any(|derive| derive == "Default");
+		let derive_attrs = self.derive_attrs(&derives);
+
+		let variants = parsed.variants.iter().enumerate().map(|(i, variant)| {
+			let ident = Self::variant_ident(naming_convention_config, &variant.ident.value);
+			let rename = self.serde_rename(
+				&naming_convention_config.apply_enum_variants(&variant.ident.value),
+				&ident,
+			);
+			let default = (has_default && i == 0).then(|| quote!(#[default]));
+			let value = Literal::u16_unsuffixed(variant.value);
+			quote!(#rename #default #ident = #value)
+		});
+		let match_branches = parsed.variants.iter().map(|variant| {
+			let ident = Self::variant_ident(naming_convention_config, &variant.ident.value);
+			let value = Literal::u16_unsuffixed(variant.value);
+			quote!(#value => Ok(Self::#ident))
+		});
+		let convenience_impls = Self::enum_convenience_impls(parsed, &rust_ident, naming_convention_config);
+
+		quote! {
+			#derive_attrs
+			#[repr(u16)]
+			pub enum #rust_ident {
+				#(#variants,)*
+			}
+
+			impl ::olympus_net_common::ProcedureInput for #rust_ident {
+				fn deserialize(input: &mut ::olympus_net_common::bytes::BytesMut) -> ::olympus_net_common::Result<Self> {
+					use ::olympus_net_common::bytes::Buf;
+					let tag = input.get_u16();
+					match tag {
+						#(#match_branches,)*
+						_ => Err(::olympus_net_common::error!("invalid tag: {tag}")),
+					}
+				}
+			}
+
+			impl ::olympus_net_common::ProcedureOutput for #rust_ident {
+				fn serialize(&self) -> ::olympus_net_common::Result<::olympus_net_common::bytes::BytesMut> {
+					use ::olympus_net_common::bytes::BufMut;
+					let mut out = ::olympus_net_common::bytes::BytesMut::with_capacity(::std::mem::size_of::<u16>());
+					out.put_u16(*self as _);
+					Ok(out)
+				}
+			}
+
+			#convenience_impls
+		}
+	}
+
+	/// Conversions from and to tags and names, the names are the ones in the schema rather than the Rust ones.
+	fn enum_convenience_impls(
+		parsed: &ResolvedEnum,
+		rust_ident: &Ident,
 		naming_convention_config: &NamingConventionConfig,
-	) {
-		let match_branches = parsed
+	) -> TokenStream {
+		let enum_name = &parsed.ident.value;
+		let idents = parsed
 			.variants
 			.iter()
-			.map(|variant| {
-				format!(
-					"\t\t\t{} => Ok(Self::{}),",
-					variant.value,
-					naming_convention_config.apply_enum_variants(&variant.ident.value)
-				)
-			})
-			.collect::<Vec<String>>()
-			.join("\n");
-
-		output.push_str(&format!(
-			"
-impl ::olympus_net_common::ProcedureInput for {} {{
-    fn deserialize(input: &mut ::olympus_net_common::bytes::BytesMut) -> ::olympus_net_common::Result<Self> {{
-        use ::olympus_net_common::bytes::Buf;
-        let tag = input.get_u16();
-        match tag {{
-{match_branches}
-            _ => Err(::olympus_net_common::error!(\"invalid tag: {{tag}}\")),
-        }}
-    }}
-}}\n",
-			naming_convention_config.apply_types(&parsed.ident.value)
-		));
-	}
-
-	fn generate_enum_output_impl(
-		parsed: &ParsedEnum,
-		output: &mut String,
+			.map(|variant| Self::variant_ident(naming_convention_config, &variant.ident.value))
+			.collect::<Vec<_>>();
+		let names = parsed
+			.variants
+			.iter()
+			.map(|variant| &variant.ident.value)
+			.collect::<Vec<_>>();
+		let tags = parsed
+			.variants
+			.iter()
+			.map(|variant| Literal::u16_unsuffixed(variant.value));
+		let count = Literal::usize_unsuffixed(parsed.variants.len());
+
+		quote! {
+			impl #rust_ident {
+				/// Every variant in the order they are declared in.
+				pub const ALL: [Self; #count] = [#(Self::#idents),*];
+
+				/// The name of the variant in the schema.
+				#[must_use]
+				pub const fn name(self) -> &'static str {
+					match self {
+						#(Self::#idents => #names,)*
+					}
+				}
+
+				/// The variant with the name `name` in the schema.
+				#[must_use]
+				pub fn from_name(name: &str) -> ::core::option::Option<Self> {
+					match name {
+						#(#names => ::core::option::Option::Some(Self::#idents),)*
+						_ => ::core::option::Option::None,
+					}
+				}
+			}
+
+			impl ::std::convert::TryFrom<u16> for #rust_ident {
+				type Error = ::olympus_net_common::UnknownVariant;
+
+				fn try_from(tag: u16) -> ::core::result::Result<Self, Self::Error> {
+					match tag {
+						#(#tags => ::core::result::Result::Ok(Self::#idents),)*
+						_ => ::core::result::Result::Err(::olympus_net_common::UnknownVariant::Tag {
+							enum_name: #enum_name,
+							tag,
+						}),
+					}
+				}
+			}
+
+			impl ::std::convert::From<#rust_ident> for u16 {
+				fn from(value: #rust_ident) -> Self {
+					value as u16
+				}
+			}
+
+			impl ::std::fmt::Display for #rust_ident {
+				fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+					f.write_str(self.name())
+				}
+			}
+
+			impl ::std::str::FromStr for #rust_ident {
+				type Err = ::olympus_net_common::UnknownVariant;
+
+				fn from_str(name: &str) -> ::core::result::Result<Self, Self::Err> {
+					Self::from_name(name).ok_or_else(|| ::olympus_net_common::UnknownVariant::Name {
+						enum_name: #enum_name,
+						name: ::std::string::ToString::to_string(name),
+					})
+				}
+			}
+		}
+	}
+
+	/// Prelude types are written out in full, since a schema is free to declare a `struct Option`. Schema types are
+	/// prefixed with `models`, which is the path to the models module outside of it.
+	fn type_tokens(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		models: &TokenStream,
 		naming_convention_config: &NamingConventionConfig,
-	) {
-		output.push_str(&format!(
-			"
-impl ::olympus_net_common::ProcedureOutput for {} {{
-    fn serialize(&self) -> ::olympus_net_common::Result<::olympus_net_common::bytes::BytesMut> {{
-        use ::olympus_net_common::bytes::BufMut;
-        let mut out = ::olympus_net_common::bytes::BytesMut::with_capacity(::std::mem::size_of::<u16>());
-        out.put_u16(*self as _);
-        Ok(out)
-    }}
-}}\n",
-			naming_convention_config.apply_types(&parsed.ident.value)
-		));
-	}
-
-	fn parsed_type_kind_to_rust(kind: &ParsedTypeKind, naming_convention_config: &NamingConventionConfig) -> String {
-		fn format_int(token: &IntToken) -> String {
-			match token {
-				IntToken::Int8 => "i8".to_string(),
-				IntToken::Int16 => "i16".to_string(),
-				IntToken::Int32 => "i32".to_string(),
-				IntToken::Int64 => "i64".to_string(),
-				IntToken::UInt8 => "u8".to_string(),
-				IntToken::UInt16 => "u16".to_string(),
-				IntToken::UInt32 => "u32".to_string(),
-				IntToken::UInt64 => "u64".to_string(),
+	) -> TokenStream {
+		fn int(kind: IntKind) -> TokenStream {
+			match kind {
+				IntKind::Int8 => quote!(i8),
+				IntKind::Int16 => quote!(i16),
+				IntKind::Int32 => quote!(i32),
+				IntKind::Int64 => quote!(i64),
+				IntKind::UInt8 => quote!(u8),
+				IntKind::UInt16 => quote!(u16),
+				IntKind::UInt32 => quote!(u32),
+				IntKind::UInt64 => quote!(u64),
 			}
 		}
 
 		match kind {
-			ParsedTypeKind::Builtin(ty) => match ty {
-				ParsedBultin::Nothing => String::new(),
-				ParsedBultin::Int(int) => format_int(int),
-				ParsedBultin::VariableInt(int) => format!("::olympus_net_common::Variable<{}>", format_int(int)),
-				ParsedBultin::String => "String".to_string(),
-				ParsedBultin::Array(ty) => format!(
-					"Vec<{}>",
-					Self::parsed_type_kind_to_rust(&ty.value, naming_convention_config)
-				),
-				ParsedBultin::Option(ty) => format!(
-					"Option<{}>",
-					Self::parsed_type_kind_to_rust(&ty.value, naming_convention_config)
-				),
-			},
-			ParsedTypeKind::External(ident) => naming_convention_config.apply_types(ident),
+			ResolvedType::Int(kind) => int(*kind),
+			ResolvedType::VariableInt(kind) => {
+				let int = int(*kind);
+				quote!(::olympus_net_common::Variable<#int>)
+			}
+			ResolvedType::String => quote!(::std::string::String),
+			ResolvedType::Array(ty) => {
+				let ty = Self::type_tokens(schema, ty, models, naming_convention_config);
+				quote!(::std::vec::Vec<#ty>)
+			}
+			ResolvedType::Option(ty) => {
+				let ty = Self::type_tokens(schema, ty, models, naming_convention_config);
+				quote!(::core::option::Option<#ty>)
+			}
+			ResolvedType::Enum(id) => {
+				let ident = Self::type_ident(naming_convention_config, &schema[*id].ident.value);
+				quote!(#models #ident)
+			}
+			ResolvedType::Struct { id, indirect: true } => {
+				let ident = Self::type_ident(naming_convention_config, &schema[*id].ident.value);
+				quote!(::std::boxed::Box<#models #ident>)
+			}
+			ResolvedType::Struct { id, indirect: false } => {
+				let ident = Self::type_ident(naming_convention_config, &schema[*id].ident.value);
+				quote!(#models #ident)
+			}
 		}
 	}
 
-	fn generate_struct_decl(
-		parsed: &ParsedStruct,
-		output: &mut String,
+	fn return_type_tokens(
+		schema: &ResolvedSchema,
+		kind: Option<&Spanned<ResolvedType>>,
 		naming_convention_config: &NamingConventionConfig,
-	) {
+	) -> TokenStream {
+		kind.map_or_else(
+			|| quote!(()),
+			|kind| Self::type_tokens(schema, &kind.value, &quote!(models::), naming_convention_config),
+		)
+	}
+
+	fn struct_tokens(
+		&self,
+		schema: &ResolvedSchema,
+		parsed: &ResolvedStruct,
+		naming_convention_config: &NamingConventionConfig,
+	) -> TokenStream {
+		let derives = self.derives(&["Debug", "Clone"], Some(&parsed.ident.value));
 		let fields = parsed
 			.fields
 			.iter()
-			.map(|field| {
-				format!(
-					"\tpub {}: {},",
-					naming_convention_config.apply_struct_fields(&field.ident.value),
-					Self::parsed_type_kind_to_rust(&field.kind.value, naming_convention_config)
-				)
-			})
-			.collect::<Vec<String>>()
-			.join("\n");
-
-		output.push_str(&format!(
-			"
-#[derive(Debug, Clone)]
-pub struct {} {{
-{fields}
-}}\n",
-			naming_convention_config.apply_types(&parsed.ident.value)
-		));
-	}
-
-	fn generate_struct_input_impl<F: Iterator<Item = Spanned<String>>>(
-		ident: &str,
-		fields: F,
-		output: &mut String,
+			.map(|field| (&field.ident.value, &field.kind.value));
+		self.fields_struct_tokens(
+			schema,
+			&Self::type_ident(naming_convention_config, &parsed.ident.value),
+			&derives,
+			fields,
+			naming_convention_config,
+		)
+	}
+
+	fn procedure_params_tokens(
+		&self,
+		schema: &ResolvedSchema,
+		parsed: &ResolvedProcedure,
 		naming_convention_config: &NamingConventionConfig,
-	) {
-		let fields = fields
-			.map(|ident| {
-				format!(
-					"\t\t\t{}: ::olympus_net_common::ProcedureInput::deserialize(input)?,",
-					naming_convention_config.apply_struct_fields(&ident.value)
-				)
+	) -> TokenStream {
+		if parsed.params.is_empty() {
+			return TokenStream::new();
+		}
+
+		// params aren't schema types, so only the derives for every type apply
+		let derives = self.derives(&["Debug", "Clone"], None);
+		let params = parsed
+			.params
+			.iter()
+			.map(|param| (&param.ident.value, &param.kind.value));
+		self.fields_struct_tokens(
+			schema,
+			&self.params_ident(parsed, naming_convention_config),
+			&derives,
+			params,
+			naming_convention_config,
+		)
+	}
+
+	/// A struct with its codec, which reads and writes the fields in order.
+	fn fields_struct_tokens<'a>(
+		&self,
+		schema: &ResolvedSchema,
+		rust_ident: &Ident,
+		derives: &[String],
+		fields: impl Iterator<Item = (&'a String, &'a ResolvedType)>,
+		naming_convention_config: &NamingConventionConfig,
+	) -> TokenStream {
+		let derive_attrs = self.derive_attrs(derives);
+		let (idents, fields): (Vec<_>, Vec<_>) = fields
+			.map(|(ident, kind)| {
+				let rust_ident = Self::field_ident(naming_convention_config, ident);
+				let rename = self.serde_rename(&naming_convention_config.apply_struct_fields(ident), &rust_ident);
+				let kind = Self::type_tokens(schema, kind, &TokenStream::new(), naming_convention_config);
+				let field = quote!(#rename pub #rust_ident: #kind);
+				(rust_ident, field)
 			})
-			.collect::<Vec<String>>()
-			.join("\n");
-
-		output.push_str(&format!(
-			"
-impl ::olympus_net_common::ProcedureInput for {} {{
-    fn deserialize(input: &mut ::olympus_net_common::bytes::BytesMut) -> ::olympus_net_common::Result<Self> {{
-        Ok(Self {{
-{fields}
-        }})
-    }}
-}}\n",
-			naming_convention_config.apply_types(ident)
-		));
-	}
-
-	fn generate_struct_output_impl<F: Iterator<Item = Spanned<String>>>(
-		ident: &str,
-		fields: F,
-		output: &mut String,
+			.unzip();
+
+		quote! {
+			#derive_attrs
+			pub struct #rust_ident {
+				#(#fields,)*
+			}
+
+			impl ::olympus_net_common::ProcedureInput for #rust_ident {
+				fn deserialize(input: &mut ::olympus_net_common::bytes::BytesMut) -> ::olympus_net_common::Result<Self> {
+					Ok(Self {
+						#(#idents: ::olympus_net_common::ProcedureInput::deserialize(input)?,)*
+					})
+				}
+			}
+
+			impl ::olympus_net_common::ProcedureOutput for #rust_ident {
+				fn serialize(&self) -> ::olympus_net_common::Result<::olympus_net_common::bytes::BytesMut> {
+					let mut out = ::olympus_net_common::bytes::BytesMut::new();
+					#(out.extend(self.#idents.serialize()?);)*
+					Ok(out)
+				}
+			}
+		}
+	}
+
+	fn abstract_server_impl(
+		&self,
+		schema: &ResolvedSchema,
 		naming_convention_config: &NamingConventionConfig,
-	) {
-		let fields = fields
-			.map(|ident| {
-				format!(
-					"\t\tout.extend(self.{}.serialize()?);",
-					naming_convention_config.apply_struct_fields(&ident.value)
+	) -> TokenStream {
+		let server_ident = ident(&naming_convention_config.apply_types("ServerRpc"));
+		let procedures = schema.procedures.iter().map(|proc| {
+			let proc_ident = Self::proc_ident(naming_convention_config, &proc.ident.value);
+			let return_ty = Self::return_type_tokens(schema, proc.return_kind.as_ref(), naming_convention_config);
+			let params = (!proc.params.is_empty()).then(|| {
+				let params_ident = self.params_ident(proc, naming_convention_config);
+				quote!(, params: models::#params_ident)
+			});
+			quote! {
+				async fn #proc_ident(context: Ctx #params) -> ::olympus_net_common::Result<#return_ty>;
+			}
+		});
+
+		quote! {
+			#[::olympus_net_common::async_trait]
+			pub trait #server_ident<Ctx: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static> {
+				#(#procedures)*
+			}
+		}
+	}
+
+	fn server_registration_fn(
+		schema: &ResolvedSchema,
+		naming_convention_config: &NamingConventionConfig,
+	) -> TokenStream {
+		let fn_ident = ident(&naming_convention_config.apply_procs("register_procedures"));
+		let server_ident = ident(&naming_convention_config.apply_types("ServerRpc"));
+		let registrations = schema.procedures.iter().map(|proc| {
+			let name = &proc.ident.value;
+			let proc_ident = Self::proc_ident(naming_convention_config, name);
+			if proc.params.is_empty() {
+				quote!(server.register_procedure(#name, |ctx, (): ()| I::#proc_ident(ctx)).await;)
+			} else {
+				quote!(server.register_procedure(#name, I::#proc_ident).await;)
+			}
+		});
+
+		quote! {
+			pub async fn #fn_ident<C: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static, I: #server_ident<C> + 'static>(
+				server: &mut ::olympus_server::OlympusServer<C>,
+				_imp: I,
+			) {
+				#(#registrations)*
+			}
+		}
+	}
+
+	fn client_impl(&self, schema: &ResolvedSchema, naming_convention_config: &NamingConventionConfig) -> TokenStream {
+		let client_ident = ident(&naming_convention_config.apply_types("ClientRpc"));
+		let procedures = schema.procedures.iter().map(|proc| {
+			let name = &proc.ident.value;
+			let proc_ident = Self::proc_ident(naming_convention_config, name);
+			let return_ty = Self::return_type_tokens(schema, proc.return_kind.as_ref(), naming_convention_config);
+
+			let (params, params_arg) = if proc.params.is_empty() {
+				(None, quote!(&()))
+			} else {
+				let params_ident = self.params_ident(proc, naming_convention_config);
+				(Some(quote!(, params: models::#params_ident)), quote!(&params))
+			};
+
+			// the server doesn't respond to procedures without a return value, so there is nothing to wait for
+			let (doc, body) = if proc.return_kind.is_some() {
+				(
+					format!(" Calls `{name}` and waits for its response."),
+					quote!(self.inner.call(#name, #params_arg).await),
 				)
-			})
-			.collect::<Vec<String>>()
-			.join("\n");
-
-		output.push_str(&format!(
-			"
-impl ::olympus_net_common::ProcedureOutput for {} {{
-    fn serialize(&self) -> ::olympus_net_common::Result<::olympus_net_common::bytes::BytesMut> {{
-        let mut out = ::olympus_net_common::bytes::BytesMut::new();
-{fields}
-        Ok(out)
-    }}
-}}\n",
-			naming_convention_config.apply_types(ident)
-		));
+			} else {
+				(
+					format!(" Sends `{name}` without waiting, it doesn't return anything."),
+					quote!(self.inner.send(#name, #params_arg)),
+				)
+			};
+
+			quote! {
+				#[doc = #doc]
+				pub async fn #proc_ident(&self #params) -> ::olympus_net_common::Result<#return_ty> {
+					#body
+				}
+			}
+		});
+
+		quote! {
+			#[derive(Clone)]
+			pub struct #client_ident<Ctx> {
+				pub inner: ::olympus_client::OlympusClient<Ctx>,
+			}
+
+			impl<Ctx: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static> #client_ident<Ctx> {
+				pub fn new(inner: ::olympus_client::OlympusClient<Ctx>) -> Self {
+					Self { inner }
+				}
+
+				#(#procedures)*
+			}
+		}
 	}
 }
 
 impl CodeGenerator for RustCodeGenerator {
+	fn reserved_words(&self) -> &'static [&'static str] {
+		&[
+			"as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false",
+			"fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+			"self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+			"abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try", "typeof", "unsized",
+			"virtual", "yield",
+		]
+	}
+
+	fn escape_ident(&self, ident: &str) -> String {
+		if !self.reserved_words().contains(&ident) {
+			ident.to_string()
+		} else if NON_RAW_KEYWORDS.contains(&ident) {
+			format!("{ident}_")
+		} else {
+			format!("r#{ident}")
+		}
+	}
+
+	// the trait's string based methods print the same tokens the modules are built from
+
+	fn generate_models(
+		&self,
+		schema: &ResolvedSchema,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		output.push_str(&pretty(self.models(schema, naming_convention_config)));
+	}
+
 	fn generate_file_header(&self, output: &mut String) {
-		output.push_str("#![allow(unused_qualifications)]\n");
-		output.push_str("#![allow(non_snake_case)]\n");
+		output.push_str(&pretty(Self::file_header()));
 	}
 
 	fn generate_enum(
 		&self,
-		parsed: &ParsedEnum,
+		resolved: &ResolvedEnum,
 		output: &mut String,
 		naming_convention_config: &NamingConventionConfig,
 	) {
-		Self::generate_enum_decl(parsed, output, naming_convention_config);
-		Self::generate_enum_input_impl(parsed, output, naming_convention_config);
-		Self::generate_enum_output_impl(parsed, output, naming_convention_config);
+		output.push_str(&pretty(self.enum_tokens(resolved, naming_convention_config)));
 	}
 
 	fn generate_struct(
 		&self,
-		parsed: &ParsedStruct,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedStruct,
 		output: &mut String,
 		naming_convention_config: &NamingConventionConfig,
 	) {
-		let field_idents = parsed.fields.iter().map(|field| field.ident.clone());
-		Self::generate_struct_decl(parsed, output, naming_convention_config);
-		Self::generate_struct_input_impl(
-			&parsed.ident.value,
-			field_idents.clone(),
-			output,
-			naming_convention_config,
-		);
-		Self::generate_struct_output_impl(&parsed.ident.value, field_idents, output, naming_convention_config);
+		output.push_str(&pretty(self.struct_tokens(schema, resolved, naming_convention_config)));
 	}
 
 	fn generate_abstract_server_impl(
 		&self,
-		parsed: &[ParsedProcedure],
+		schema: &ResolvedSchema,
 		output: &mut String,
 		naming_convention_config: &NamingConventionConfig,
 	) {
-		let procedures = parsed
-			.iter()
-			.map(|proc| {
-				let return_ty = Self::parsed_type_kind_to_rust(&proc.return_kind.value, naming_convention_config);
-				let return_ty = if return_ty.is_empty() {
-					"::olympus_net_common::Result<()>".to_string()
-				} else {
-					format!("::olympus_net_common::Result<{return_ty}>")
-				};
-
-				let proc_params = if proc.params.is_empty() {
-					String::new()
-				} else {
-					format!(", params: {}Params", proc.ident.value)
-				};
-
-				format!(
-					"\tasync fn {}(context: Ctx{proc_params}) -> {return_ty};",
-					naming_convention_config.apply_procs(&proc.ident.value)
-				)
-			})
-			.collect::<Vec<String>>()
-			.join("\n");
-		output.push_str("#[::olympus_net_common::async_trait]\n");
-		output.push_str(&format!(
-			"pub trait {}<Ctx: Clone + Send + Sync + 'static> {{\n",
-			naming_convention_config.apply_types("ServerRpc")
-		));
-		output.push_str(&procedures);
-		output.push_str("\n}\n");
+		output.push_str(&pretty(self.abstract_server_impl(schema, naming_convention_config)));
 	}
 
 	fn generate_server_registration_fn(
 		&self,
-		parsed: &[ParsedProcedure],
+		schema: &ResolvedSchema,
 		output: &mut String,
 		naming_convention_config: &NamingConventionConfig,
 	) {
-		output.push_str(&format!("pub async fn {}<C: Clone + Send + Sync + 'static, I: {}<C> + 'static>(server: &mut ::olympus_server::OlympusServer<C>, _imp: I) {{\n", naming_convention_config.apply_procs("register_procedures"), naming_convention_config.apply_types("ServerRpc")));
-		for proc in parsed {
-			if proc.params.is_empty() {
-				output.push_str(&format!(
-					"\tserver.register_procedure(\"{}\", |ctx, (): ()| I::{}(ctx)).await;\n",
-					proc.ident.value,
-					naming_convention_config.apply_procs(&proc.ident.value)
-				));
-			} else {
-				output.push_str(&format!(
-					"\tserver.register_procedure(\"{}\", I::{}).await;\n",
-					proc.ident.value,
-					naming_convention_config.apply_procs(&proc.ident.value)
-				));
-			}
-		}
-		output.push_str("}\n");
+		output.push_str(&pretty(Self::server_registration_fn(schema, naming_convention_config)));
 	}
 
-	fn generate_procedure_params(
+	fn generate_client_impl(
 		&self,
-		parsed: &ParsedProcedure,
+		schema: &ResolvedSchema,
 		output: &mut String,
 		naming_convention_config: &NamingConventionConfig,
 	) {
-		if parsed.params.is_empty() {
-			return;
-		}
+		output.push_str(&pretty(self.client_impl(schema, naming_convention_config)));
+	}
 
-		let params = parsed
-			.params
-			.iter()
-			.map(|param| {
-				format!(
-					"\tpub {}: {},",
-					param.ident.value,
-					Self::parsed_type_kind_to_rust(&param.kind.value, naming_convention_config)
-				)
-			})
-			.collect::<Vec<String>>()
-			.join("\n");
-
-		output.push_str(&format!(
-			"
-#[derive(Debug, Clone)]
-pub struct {}Params {{
-{params}
-}}
-	",
-			parsed.ident.value
-		));
-
-		let struct_ident = format!("{}Params", parsed.ident.value);
-		let param_idents = parsed.params.iter().map(|field| field.ident.clone());
-		Self::generate_struct_input_impl(&struct_ident, param_idents.clone(), output, naming_convention_config);
-		Self::generate_struct_output_impl(&struct_ident, param_idents, output, naming_convention_config);
+	fn generate_procedure_params(
+		&self,
+		schema: &ResolvedSchema,
+		parsed: &ResolvedProcedure,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		output.push_str(&pretty(self.procedure_params_tokens(
+			schema,
+			parsed,
+			naming_convention_config,
+		)));
 	}
 }
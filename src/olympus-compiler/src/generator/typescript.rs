@@ -0,0 +1,495 @@
+use std::fmt::Write;
+
+use crate::naming::NamingConventionConfig;
+use olympus_spanned::Spanned;
+use olympus_verifier::{IntKind, ResolvedEnum, ResolvedProcedure, ResolvedSchema, ResolvedStruct, ResolvedType};
+
+use super::CodeGenerator;
+
+pub struct TypeScriptCodeGenerator;
+
+const RUNTIME: &str = include_str!("runtime/olympus.ts");
+
+impl TypeScriptCodeGenerator {
+	fn type_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		TypeScriptCodeGenerator.escape_ident(&naming_convention_config.apply_types(ident))
+	}
+
+	fn variant_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		TypeScriptCodeGenerator.escape_member(&naming_convention_config.apply_enum_variants(ident))
+	}
+
+	fn field_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		TypeScriptCodeGenerator.escape_member(&naming_convention_config.apply_struct_fields(ident))
+	}
+
+	fn proc_ident(naming_convention_config: &NamingConventionConfig, ident: &str) -> String {
+		TypeScriptCodeGenerator.escape_member(&naming_convention_config.apply_procs(ident))
+	}
+
+	fn int_method(kind: IntKind) -> &'static str {
+		match kind {
+			IntKind::Int8 => "i8",
+			IntKind::Int16 => "i16",
+			IntKind::Int32 => "i32",
+			IntKind::Int64 => "i64",
+			IntKind::UInt8 => "u8",
+			IntKind::UInt16 => "u16",
+			IntKind::UInt32 => "u32",
+			IntKind::UInt64 => "u64",
+		}
+	}
+
+	/// 64 bit integers don't fit into a `number` without losing precision.
+	fn int_type(kind: IntKind) -> &'static str {
+		if kind.bits() == 64 {
+			"bigint"
+		} else {
+			"number"
+		}
+	}
+
+	fn declared_ident(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Enum(id) => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			ResolvedType::Struct { id, .. } => Self::type_ident(naming_convention_config, &schema[*id].ident.value),
+			_ => unreachable!("only enums and structs are declared"),
+		}
+	}
+
+	fn resolved_type_to_ts(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) | ResolvedType::VariableInt(int) => Self::int_type(*int).to_string(),
+			ResolvedType::String => "string".to_string(),
+			ResolvedType::Array(ty) => format!(
+				"Array<{}>",
+				Self::resolved_type_to_ts(schema, ty, naming_convention_config)
+			),
+			// `T | null | null` would be the same type as `T | null`, so the inner option is wrapped
+			ResolvedType::Option(ty) if matches!(**ty, ResolvedType::Option(_)) => format!(
+				"Some<{}> | null",
+				Self::resolved_type_to_ts(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) => format!(
+				"{} | null",
+				Self::resolved_type_to_ts(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => {
+				Self::declared_ident(schema, kind, naming_convention_config)
+			}
+		}
+	}
+
+	fn return_type_to_ts(
+		schema: &ResolvedSchema,
+		kind: Option<&Spanned<ResolvedType>>,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		kind.map_or_else(
+			|| "void".to_string(),
+			|kind| Self::resolved_type_to_ts(schema, &kind.value, naming_convention_config),
+		)
+	}
+
+	/// An expression writing `value` to the `Writer` in scope as `w`.
+	///
+	/// `depth` keeps the item names of nested arrays and options apart.
+	fn encode_expr(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		value: &str,
+		depth: usize,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) => format!("w.{}({value})", Self::int_method(*int)),
+			ResolvedType::VariableInt(int) if int.is_signed() => format!("w.varInt({value}, {})", int.bits()),
+			ResolvedType::VariableInt(_) => format!("w.varUint({value})"),
+			ResolvedType::String => format!("w.string({value})"),
+			ResolvedType::Array(ty) => {
+				let item = format!("item{depth}");
+				format!(
+					"w.array({value}, ({item}) => {})",
+					Self::encode_expr(schema, ty, &item, depth + 1, naming_convention_config)
+				)
+			}
+			ResolvedType::Option(ty) => {
+				let item = format!("item{depth}");
+				let inner = if matches!(**ty, ResolvedType::Option(_)) {
+					format!("{item}.value")
+				} else {
+					item.clone()
+				};
+				format!(
+					"w.option({value}, ({item}) => {})",
+					Self::encode_expr(schema, ty, &inner, depth + 1, naming_convention_config)
+				)
+			}
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => format!(
+				"encode{}(w, {value})",
+				Self::declared_ident(schema, kind, naming_convention_config)
+			),
+		}
+	}
+
+	/// An expression reading a value from the `Reader` in scope as `r`.
+	fn decode_expr(
+		schema: &ResolvedSchema,
+		kind: &ResolvedType,
+		naming_convention_config: &NamingConventionConfig,
+	) -> String {
+		match kind {
+			ResolvedType::Int(int) => format!("r.{}()", Self::int_method(*int)),
+			ResolvedType::VariableInt(int) => {
+				let read = if int.is_signed() {
+					format!("r.varInt({})", int.bits())
+				} else {
+					"r.varUint()".to_string()
+				};
+
+				if int.bits() == 64 {
+					read
+				} else {
+					format!("Number({read})")
+				}
+			}
+			ResolvedType::String => "r.string()".to_string(),
+			ResolvedType::Array(ty) => format!(
+				"r.array(() => {})",
+				Self::decode_expr(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) if matches!(**ty, ResolvedType::Option(_)) => format!(
+				"r.option(() => ({{ value: {} }}))",
+				Self::decode_expr(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Option(ty) => format!(
+				"r.option(() => {})",
+				Self::decode_expr(schema, ty, naming_convention_config)
+			),
+			ResolvedType::Enum(_) | ResolvedType::Struct { .. } => format!(
+				"decode{}(r)",
+				Self::declared_ident(schema, kind, naming_convention_config)
+			),
+		}
+	}
+
+	/// An interface with an encode and decode function, used for structs and procedure params.
+	fn generate_record<'a>(
+		schema: &ResolvedSchema,
+		ident: &str,
+		fields: impl Iterator<Item = (&'a Spanned<String>, &'a ResolvedType)> + Clone,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let fields = fields
+			.map(|(field, kind)| (Self::field_ident(naming_convention_config, &field.value), kind))
+			.collect::<Vec<_>>();
+
+		let decl_fields = fields
+			.iter()
+			.map(|(field, kind)| {
+				format!(
+					"\t{field}: {};",
+					Self::resolved_type_to_ts(schema, kind, naming_convention_config)
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		let encode_fields = fields
+			.iter()
+			.map(|(field, kind)| {
+				format!(
+					"\t{};",
+					Self::encode_expr(schema, kind, &format!("value.{field}"), 0, naming_convention_config)
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		let decode_fields = fields
+			.iter()
+			.map(|(field, kind)| {
+				format!(
+					"\t\t{field}: {},",
+					Self::decode_expr(schema, kind, naming_convention_config)
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		write!(
+			output,
+			"
+export interface {ident} {{
+{decl_fields}
+}}
+
+export function encode{ident}(w: Writer, value: {ident}): void {{
+{encode_fields}
+}}
+
+export function decode{ident}(r: Reader): {ident} {{
+	return {{
+{decode_fields}
+	}};
+}}
+"
+		)
+		.unwrap();
+	}
+}
+
+impl CodeGenerator for TypeScriptCodeGenerator {
+	fn reserved_words(&self) -> &'static [&'static str] {
+		&[
+			"break",
+			"case",
+			"catch",
+			"class",
+			"const",
+			"continue",
+			"debugger",
+			"default",
+			"delete",
+			"do",
+			"else",
+			"enum",
+			"export",
+			"extends",
+			"false",
+			"finally",
+			"for",
+			"function",
+			"if",
+			"import",
+			"in",
+			"instanceof",
+			"new",
+			"null",
+			"return",
+			"super",
+			"switch",
+			"this",
+			"throw",
+			"true",
+			"try",
+			"typeof",
+			"var",
+			"void",
+			"while",
+			"with",
+			"implements",
+			"interface",
+			"let",
+			"package",
+			"private",
+			"protected",
+			"public",
+			"static",
+			"yield",
+			"await",
+			"any",
+			"boolean",
+			"number",
+			"string",
+			"symbol",
+			"bigint",
+			"never",
+			"unknown",
+			"object",
+			"undefined",
+			"type",
+			"Array",
+			"Promise",
+			"Error",
+			// declared by the runtime
+			"Writer",
+			"Reader",
+			"OlympusConnection",
+			"OlympusError",
+			"Transport",
+			"PendingCall",
+			"Some",
+			"fnv1a",
+			"lz4Decompress",
+			"textEncoder",
+			"textDecoder",
+			"MAX_PACKET_SIZE",
+		]
+	}
+
+	/// Reserved words are valid property names, only the ones with a special meaning as a property are escaped.
+	fn escape_member(&self, ident: &str) -> String {
+		// `__proto__` sets the prototype in an object literal, `constructor` and `connection` are members of the client
+		if ["__proto__", "constructor", "connection"].contains(&ident) {
+			format!("{ident}_")
+		} else {
+			ident.to_string()
+		}
+	}
+
+	fn generate_file_header(&self, output: &mut String) {
+		output.push_str(RUNTIME);
+	}
+
+	fn generate_enum(
+		&self,
+		resolved: &ResolvedEnum,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let ident = Self::type_ident(naming_convention_config, &resolved.ident.value);
+		let variants = resolved
+			.variants
+			.iter()
+			.map(|variant| {
+				format!(
+					"\t{}: {},",
+					Self::variant_ident(naming_convention_config, &variant.ident.value),
+					variant.value
+				)
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+		let mut cases = resolved
+			.variants
+			.iter()
+			.map(|variant| format!("\t\tcase {}:", variant.value))
+			.collect::<Vec<String>>()
+			.join("\n");
+		if !cases.is_empty() {
+			write!(cases, "\n\t\t\treturn tag as {ident};\n").unwrap();
+		}
+
+		write!(
+			output,
+			"
+export const {ident} = {{
+{variants}
+}} as const;
+export type {ident} = (typeof {ident})[keyof typeof {ident}];
+
+export function encode{ident}(w: Writer, value: {ident}): void {{
+	w.u16(value);
+}}
+
+export function decode{ident}(r: Reader): {ident} {{
+	const tag = r.u16();
+	switch (tag) {{
+{cases}		default:
+			throw new OlympusError(`Invalid {ident} tag: ${{tag}}`);
+	}}
+}}
+"
+		)
+		.unwrap();
+	}
+
+	fn generate_struct(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedStruct,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		Self::generate_record(
+			schema,
+			&Self::type_ident(naming_convention_config, &resolved.ident.value),
+			resolved.fields.iter().map(|field| (&field.ident, &field.kind.value)),
+			output,
+			naming_convention_config,
+		);
+	}
+
+	fn generate_procedure_params(
+		&self,
+		schema: &ResolvedSchema,
+		resolved: &ResolvedProcedure,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		if resolved.params.is_empty() {
+			return;
+		}
+
+		Self::generate_record(
+			schema,
+			&self.procedure_params_ident(resolved, naming_convention_config),
+			resolved.params.iter().map(|param| (&param.ident, &param.kind.value)),
+			output,
+			naming_convention_config,
+		);
+	}
+
+	fn generate_client_impl(
+		&self,
+		schema: &ResolvedSchema,
+		output: &mut String,
+		naming_convention_config: &NamingConventionConfig,
+	) {
+		let methods = schema
+			.procedures
+			.iter()
+			.map(|proc| {
+				let name = &proc.ident.value;
+				let return_ty = Self::return_type_to_ts(schema, proc.return_kind.as_ref(), naming_convention_config);
+
+				let (params, write) = if proc.params.is_empty() {
+					(String::new(), "() => {}".to_string())
+				} else {
+					let params_ident = self.procedure_params_ident(proc, naming_convention_config);
+					(
+						format!("params: {params_ident}"),
+						format!("(w) => encode{params_ident}(w, params)"),
+					)
+				};
+
+				let method = Self::proc_ident(naming_convention_config, name);
+				// the server doesn't respond to procedures without a return value, so there is nothing to wait for
+				match &proc.return_kind {
+					Some(kind) => format!(
+						"
+	/** Calls `{name}` and waits for its response. */
+	{method}({params}): Promise<{return_ty}> {{
+		return this.connection.call(\"{name}\", {write}, (r) => {});
+	}}",
+						Self::decode_expr(schema, &kind.value, naming_convention_config)
+					),
+					None => format!(
+						"
+	/** Sends `{name}` without waiting, it doesn't return anything. */
+	async {method}({params}): Promise<void> {{
+		this.connection.send(\"{name}\", {write});
+	}}"
+					),
+				}
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		write!(
+			output,
+			"
+export class {} {{
+	readonly connection: OlympusConnection;
+
+	constructor(connection: OlympusConnection) {{
+		this.connection = connection;
+	}}
+{methods}
+}}
+",
+			naming_convention_config.apply_types("ClientRpc")
+		)
+		.unwrap();
+	}
+}
@@ -0,0 +1,152 @@
+//! The olympus compiler as a library: load schemas from disk or memory, verify them, get the diagnostics as values
+//! and generate code into memory. `olympusc` is a thin wrapper around this that prints diagnostics and writes files.
+//!
+//! ```no_run
+//! use olympus_compiler::{generate, verify_schema, CompileLanguage, GenerateOptions, LintConfig};
+//!
+//! let verification = verify_schema("schemas/files.ol".as_ref(), &LintConfig::default());
+//! for diagnostic in &verification.diagnostics {
+//!     eprintln!("{}", diagnostic.subject);
+//! }
+//!
+//! if let Some(schema) = verification.schema {
+//!     for file in generate(&schema, &GenerateOptions::new(CompileLanguage::Python)).unwrap() {
+//!         println!("{}: {} bytes", file.path.display(), file.contents.len());
+//!     }
+//! }
+//! ```
+
+pub mod diagnostics;
+pub mod generator;
+pub mod loader;
+pub mod naming;
+pub mod plugin;
+pub mod project;
+
+use loader::{FileSystem, RealFileSystem};
+use std::path::{Path, PathBuf};
+
+pub use generator::{
+	generate, generate_rust_modules, CompileLanguage, GenerateOptions, GeneratedFile, RustCrateSide, RustDependencies,
+};
+pub use loader::MemoryFileSystem;
+pub use naming::{NamingConvention, NamingConventionConfig, NamingConventionOverrides};
+pub use olympus_spanned::{OlympusError, Severity};
+pub use olympus_verifier::{Lint, LintConfig, LintLevel, ResolvedSchema};
+
+/// The outcome of verifying a schema file and its imports.
+#[derive(Debug)]
+pub struct Verification {
+	/// `None` if there was an error or a lint that is set to deny fired.
+	pub schema: Option<ResolvedSchema>,
+	/// Every error and lint, in the order they were found.
+	pub diagnostics: Vec<OlympusError>,
+	/// Every file that was read, so tools can regenerate code once one of them changes.
+	///
+	/// Paths are relative to wherever the schema path is relative to. If loading failed only the files that are part
+	/// of the error and the schema itself are known.
+	pub files: Vec<PathBuf>,
+	/// The files every verified path loads, itself first and in the order loading it on its own reads them. Empty if
+	/// loading failed.
+	pub root_files: Vec<Vec<PathBuf>>,
+}
+
+impl Verification {
+	/// The schema the path at `idx` of the verified paths results in on its own, cut out of the schema every path was
+	/// verified into instead of verifying it again. `None` if that schema didn't verify.
+	#[must_use]
+	pub fn root_schema(&self, idx: usize) -> Option<ResolvedSchema> {
+		let file_names = self.root_files[idx]
+			.iter()
+			.map(|path| path.to_string_lossy().into_owned())
+			.collect::<Vec<_>>();
+		Some(self.schema.as_ref()?.subset(&file_names))
+	}
+}
+
+/// Loads, parses, verifies and lints a schema file and its imports without reporting anything.
+#[must_use]
+pub fn verify_schema(path: &Path, lint_config: &LintConfig) -> Verification {
+	verify_schema_from(&RealFileSystem, path, &[], lint_config)
+}
+
+/// [`verify_schema`] reading from `fs` and looking for imports in `include_paths` as well.
+#[must_use]
+pub fn verify_schema_from(
+	fs: &dyn FileSystem,
+	path: &Path,
+	include_paths: &[PathBuf],
+	lint_config: &LintConfig,
+) -> Verification {
+	verify_schemas_from(fs, &[path.to_path_buf()], include_paths, lint_config)
+}
+
+/// [`verify_schema_from`] for several schema files that are verified into a single schema, like a directory of
+/// schemas that import each other.
+///
+/// Every file is a root, so a type declared in one of them is only unused if no file uses it.
+#[must_use]
+pub fn verify_schemas_from(
+	fs: &dyn FileSystem,
+	paths: &[PathBuf],
+	include_paths: &[PathBuf],
+	lint_config: &LintConfig,
+) -> Verification {
+	let (files, roots) = match loader::load_schema_roots_from(fs, paths, include_paths) {
+		Ok(loaded) => loaded,
+		Err(err) => {
+			let mut read = err
+				.labels
+				.iter()
+				.map(|label| PathBuf::from(&label.source.file_name))
+				.collect::<Vec<_>>();
+			for path in paths {
+				if fs.canonicalize(path).is_ok() && !read.contains(path) {
+					read.push(path.clone());
+				}
+			}
+			read.dedup();
+
+			return Verification {
+				schema: None,
+				diagnostics: vec![err],
+				files: read,
+				root_files: Vec::new(),
+			};
+		}
+	};
+	let read = files
+		.iter()
+		.map(|file| PathBuf::from(&file.parser.source.file_name))
+		.collect::<Vec<_>>();
+	let root_files = roots
+		.iter()
+		.map(|root| {
+			loader::imported_files(&files, *root)
+				.into_iter()
+				.map(|idx| read[idx].clone())
+				.collect()
+		})
+		.collect();
+
+	let schema = match olympus_verifier::verify(&files) {
+		Ok(schema) => schema,
+		Err(err) => {
+			return Verification {
+				schema: None,
+				diagnostics: vec![err],
+				files: read,
+				root_files,
+			}
+		}
+	};
+
+	let diagnostics = olympus_verifier::lint_roots(&files, &schema, lint_config, &roots);
+	let denied = diagnostics.iter().any(OlympusError::is_error);
+	Verification {
+		schema: (!denied).then_some(schema),
+		diagnostics,
+		files: read,
+		root_files,
+	}
+}
@@ -0,0 +1,327 @@
+use std::{
+	collections::HashMap,
+	io,
+	path::{Component, Path, PathBuf},
+	sync::Arc,
+};
+
+use olympus_lexer::Lexer;
+use olympus_parser::Parser;
+use olympus_spanned::{CodeSource, ErrorCode, OlympusError};
+use olympus_verifier::SchemaFile;
+
+/// Where schema files are read from, shared between threads that verify schemas in parallel.
+pub trait FileSystem: Send + Sync {
+	fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+	/// An absolute path without `.` and `..` that is the same for every path pointing to the file.
+	fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+}
+
+/// The file system of the machine.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+	fn read_to_string(&self, path: &Path) -> io::Result<String> {
+		std::fs::read_to_string(path)
+	}
+
+	fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+		path.canonicalize()
+	}
+}
+
+/// Files that only exist in memory, like unsaved editor buffers or schemas embedded into a binary.
+///
+/// Paths are compared after resolving `.` and `..`, relative paths are relative to `/`.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFileSystem {
+	files: HashMap<PathBuf, String>,
+}
+
+impl MemoryFileSystem {
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn insert(&mut self, path: impl AsRef<Path>, src: impl Into<String>) {
+		self.files.insert(Self::normalize(path.as_ref()), src.into());
+	}
+
+	fn normalize(path: &Path) -> PathBuf {
+		let mut normalized = PathBuf::from("/");
+		for component in path.components() {
+			match component {
+				Component::Normal(part) => normalized.push(part),
+				Component::ParentDir => {
+					normalized.pop();
+				}
+				Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+			}
+		}
+		normalized
+	}
+}
+
+impl FileSystem for MemoryFileSystem {
+	fn read_to_string(&self, path: &Path) -> io::Result<String> {
+		self.files
+			.get(&Self::normalize(path))
+			.cloned()
+			.ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+	}
+
+	fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+		let path = Self::normalize(path);
+		if self.files.contains_key(&path) {
+			Ok(path)
+		} else {
+			Err(io::Error::from(io::ErrorKind::NotFound))
+		}
+	}
+}
+
+/// Loads a schema file and everything it imports.
+///
+/// `import foo;` refers to `foo.ol` next to the importing file. Every file is only loaded once, so import cycles are
+/// fine. The root file is always the first one returned.
+///
+/// Use [`load_schema_files_from`] to also look for imports in include directories.
+///
+/// File names in diagnostics are relative to wherever `root` is relative to, so editors can find them.
+pub fn load_schema_files(root: &Path) -> Result<Vec<SchemaFile>, OlympusError> {
+	load_schema_files_from(&RealFileSystem, root, &[])
+}
+
+/// [`load_schema_files`] reading from `fs`.
+///
+/// Imports that aren't next to the importing file are looked up in `include_paths`, in order.
+pub fn load_schema_files_from(
+	fs: &dyn FileSystem,
+	root: &Path,
+	include_paths: &[PathBuf],
+) -> Result<Vec<SchemaFile>, OlympusError> {
+	let (files, _) = load_schema_roots_from(fs, &[root.to_path_buf()], include_paths)?;
+	Ok(files)
+}
+
+/// [`load_schema_files_from`] for several root files at once, every file is still only loaded once.
+///
+/// Returns the files and the index of every root in them, in the order of `roots`.
+pub fn load_schema_roots_from(
+	fs: &dyn FileSystem,
+	roots: &[PathBuf],
+	include_paths: &[PathBuf],
+) -> Result<(Vec<SchemaFile>, Vec<usize>), OlympusError> {
+	let mut loader = Loader {
+		fs,
+		include_paths,
+		files: Vec::new(),
+		indices: HashMap::new(),
+	};
+
+	let mut indices = Vec::with_capacity(roots.len());
+	for root in roots {
+		let display_path = root.clone();
+		let root = fs.canonicalize(root).unwrap_or_else(|_| root.clone());
+		// a root can be imported by one that came before it
+		if let Some(existing) = loader.indices.get(&root) {
+			indices.push(*existing);
+			continue;
+		}
+
+		let src = fs.read_to_string(&display_path).map_err(|err| {
+			OlympusError::new(&format!("Couldn't read '{}': {err}", display_path.display()))
+				.code(ErrorCode::UnreadableFile)
+		})?;
+		indices.push(loader.load(&root, &display_path, src)?);
+	}
+
+	Ok((loader.files.into_iter().flatten().collect(), indices))
+}
+
+/// The index of `root` and every file it imports, directly or not, in the order loading `root` on its own reads them.
+#[must_use]
+pub fn imported_files(files: &[SchemaFile], root: usize) -> Vec<usize> {
+	fn visit(files: &[SchemaFile], idx: usize, visited: &mut Vec<usize>) {
+		if visited.contains(&idx) {
+			return;
+		}
+
+		visited.push(idx);
+		for import in &files[idx].imports {
+			visit(files, *import, visited);
+		}
+	}
+
+	let mut visited = Vec::new();
+	visit(files, root, &mut visited);
+	visited
+}
+
+struct Loader<'a> {
+	fs: &'a dyn FileSystem,
+	include_paths: &'a [PathBuf],
+	files: Vec<Option<SchemaFile>>,
+	indices: HashMap<PathBuf, usize>,
+}
+
+impl Loader<'_> {
+	fn load(&mut self, path: &Path, display_path: &Path, src: String) -> Result<usize, OlympusError> {
+		let source = Arc::new(CodeSource {
+			file_name: display_path.display().to_string(),
+			src,
+		});
+
+		let mut lexer = Lexer::new(source);
+		lexer.lex()?;
+
+		let mut parser = Parser::new(lexer);
+		parser.parse()?;
+
+		let idx = self.files.len();
+		self.files.push(None);
+		self.indices.insert(path.to_path_buf(), idx);
+
+		let dir = path.parent().unwrap_or(Path::new("."));
+		let display_dir = display_path.parent().unwrap_or(Path::new(""));
+		let mut imports = Vec::with_capacity(parser.imports.len());
+		for import in &parser.imports {
+			let file_name = format!("{}.ol", import.value);
+			let next_to = dir.join(&file_name);
+			let mut candidates = std::iter::once((next_to.clone(), display_dir.join(&file_name))).chain(
+				self.include_paths
+					.iter()
+					.map(|include| (include.join(&file_name), include.join(&file_name))),
+			);
+			let Some((import_path, import_display_path)) =
+				candidates.find_map(|(path, display_path)| Some((self.fs.canonicalize(&path).ok()?, display_path)))
+			else {
+				let searched = if self.include_paths.is_empty() {
+					String::new()
+				} else {
+					" or in the include paths".to_string()
+				};
+				return Err(OlympusError::error(
+					parser.source.clone(),
+					&format!("Couldn't find imported file '{}'{searched}", next_to.display()),
+					import.span.clone(),
+				)
+				.code(ErrorCode::ImportNotFound));
+			};
+
+			if let Some(existing) = self.indices.get(&import_path) {
+				imports.push(*existing);
+				continue;
+			}
+
+			let src = self.fs.read_to_string(&import_path).map_err(|err| {
+				OlympusError::error(
+					parser.source.clone(),
+					&format!("Couldn't read imported file '{}': {err}", import_path.display()),
+					import.span.clone(),
+				)
+				.code(ErrorCode::UnreadableFile)
+			})?;
+			imports.push(self.load(&import_path, &import_display_path, src)?);
+		}
+
+		self.files[idx] = Some(SchemaFile { parser, imports });
+		Ok(idx)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use olympus_verifier::LintConfig;
+
+	use super::*;
+
+	#[test]
+	fn loads_imports_from_memory() {
+		let mut fs = MemoryFileSystem::new();
+		fs.insert(
+			"schemas/files.ol",
+			"import user;\nimport action;\nstruct File { owner->User; }",
+		);
+		fs.insert("/schemas/./user.ol", "import files;\nstruct User { name->@string; }");
+		fs.insert("schemas/action.ol", "enum Action { Delete->1; }");
+
+		let files = load_schema_files_from(&fs, Path::new("schemas/files.ol"), &[]).unwrap();
+		let names = files
+			.iter()
+			.map(|file| file.parser.source.file_name.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(names, ["schemas/files.ol", "schemas/user.ol", "schemas/action.ol"]);
+		// the cycle back to files.ol points at the already loaded file
+		assert_eq!(files[1].imports, [0]);
+	}
+
+	#[test]
+	fn loads_several_roots_once() {
+		let mut fs = MemoryFileSystem::new();
+		fs.insert("files.ol", "import user;\nstruct File { owner->User; }");
+		fs.insert("user.ol", "struct User { name->@string; }");
+		fs.insert("action.ol", "enum Action { Delete->1; }");
+
+		let roots = ["files.ol", "user.ol", "action.ol"].map(PathBuf::from);
+		let (files, indices) = load_schema_roots_from(&fs, &roots, &[]).unwrap();
+		let names = files
+			.iter()
+			.map(|file| file.parser.source.file_name.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(names, ["files.ol", "user.ol", "action.ol"]);
+		// user.ol was already loaded as an import of files.ol
+		assert_eq!(indices, [0, 1, 2]);
+	}
+
+	#[test]
+	fn schemas_verify_in_parallel() {
+		let mut fs = MemoryFileSystem::new();
+		fs.insert("user.ol", "struct User { name->@string; }");
+		fs.insert("files.ol", "import user;\nstruct File { owner->User; }");
+
+		let verifications = std::thread::scope(|scope| {
+			let handles = ["files.ol", "user.ol"].map(|path| {
+				let fs = &fs;
+				scope.spawn(move || crate::verify_schema_from(fs, Path::new(path), &[], &LintConfig::default()))
+			});
+			handles.map(|handle| handle.join().unwrap())
+		});
+
+		for verification in verifications {
+			assert!(verification.schema.is_some(), "{:?}", verification.diagnostics);
+		}
+	}
+
+	#[test]
+	fn imports_fall_back_to_include_paths() {
+		let mut fs = MemoryFileSystem::new();
+		fs.insert("schemas/files.ol", "import user;\nimport action;");
+		fs.insert("schemas/action.ol", "enum Action { Delete->1; }");
+		fs.insert("shared/user.ol", "struct User { name->@string; }");
+		fs.insert("vendor/action.ol", "enum Action { Encrypt->1; }");
+
+		let include_paths = [PathBuf::from("vendor"), PathBuf::from("shared")];
+		let files = load_schema_files_from(&fs, Path::new("schemas/files.ol"), &include_paths).unwrap();
+		let names = files
+			.iter()
+			.map(|file| file.parser.source.file_name.as_str())
+			.collect::<Vec<_>>();
+		// files next to the importing one win over the include paths
+		assert_eq!(names, ["schemas/files.ol", "shared/user.ol", "schemas/action.ol"]);
+	}
+
+	#[test]
+	fn missing_memory_import_is_reported() {
+		let mut fs = MemoryFileSystem::new();
+		fs.insert("files.ol", "import user;");
+
+		let Err(err) = load_schema_files_from(&fs, Path::new("files.ol"), &[]) else {
+			panic!("the import doesn't exist");
+		};
+		assert_eq!(err.code, Some(ErrorCode::ImportNotFound));
+	}
+}
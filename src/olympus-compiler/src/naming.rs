@@ -0,0 +1,132 @@
+use clap::ValueEnum;
+use heck::{AsKebabCase, AsLowerCamelCase, AsPascalCase, AsShoutyKebabCase, AsShoutySnakeCase, AsSnakeCase};
+use serde::Deserialize;
+
+use crate::generator::CompileLanguage;
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[clap(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NamingConvention {
+	Pascal,
+	LowerCamel,
+	Snake,
+	ShoutySnake,
+	Kebab,
+	ShoutyKebab,
+}
+
+#[derive(Debug, Clone)]
+pub struct NamingConventionConfig {
+	pub types: NamingConvention,
+	pub struct_fields: NamingConvention,
+	pub enum_variants: NamingConvention,
+	pub procs: NamingConvention,
+}
+
+impl NamingConventionConfig {
+	/// The conventions code in `language` usually follows.
+	#[must_use]
+	pub fn defaults_for(language: CompileLanguage) -> Self {
+		let (struct_fields, enum_variants, procs) = match language {
+			CompileLanguage::Rust => (
+				NamingConvention::Snake,
+				NamingConvention::Pascal,
+				NamingConvention::Snake,
+			),
+			CompileLanguage::TypeScript => (
+				NamingConvention::LowerCamel,
+				NamingConvention::Pascal,
+				NamingConvention::LowerCamel,
+			),
+			CompileLanguage::Python | CompileLanguage::C => (
+				NamingConvention::Snake,
+				NamingConvention::ShoutySnake,
+				NamingConvention::Snake,
+			),
+			// Go only exports capitalized names and .NET capitalizes every public member
+			CompileLanguage::Go | CompileLanguage::CSharp => (
+				NamingConvention::Pascal,
+				NamingConvention::Pascal,
+				NamingConvention::Pascal,
+			),
+			CompileLanguage::Kotlin => (
+				NamingConvention::LowerCamel,
+				NamingConvention::ShoutySnake,
+				NamingConvention::LowerCamel,
+			),
+		};
+
+		Self {
+			types: NamingConvention::Pascal,
+			struct_fields,
+			enum_variants,
+			procs,
+		}
+	}
+
+	fn apply(conv: NamingConvention, input: &str) -> String {
+		match conv {
+			NamingConvention::Pascal => AsPascalCase(input).to_string(),
+			NamingConvention::LowerCamel => AsLowerCamelCase(input).to_string(),
+			NamingConvention::Snake => AsSnakeCase(input).to_string(),
+			NamingConvention::ShoutySnake => AsShoutySnakeCase(input).to_string(),
+			NamingConvention::Kebab => AsKebabCase(input).to_string(),
+			NamingConvention::ShoutyKebab => AsShoutyKebabCase(input).to_string(),
+		}
+	}
+
+	#[must_use]
+	pub fn apply_types(&self, input: &str) -> String {
+		Self::apply(self.types, input)
+	}
+
+	#[must_use]
+	pub fn apply_enum_variants(&self, input: &str) -> String {
+		Self::apply(self.enum_variants, input)
+	}
+
+	#[must_use]
+	pub fn apply_struct_fields(&self, input: &str) -> String {
+		Self::apply(self.struct_fields, input)
+	}
+
+	#[must_use]
+	pub fn apply_procs(&self, input: &str) -> String {
+		Self::apply(self.procs, input)
+	}
+}
+
+/// Naming conventions that were configured explicitly, the language or plugin picks the rest.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NamingConventionOverrides {
+	pub types: Option<NamingConvention>,
+	pub enum_variants: Option<NamingConvention>,
+	pub struct_fields: Option<NamingConvention>,
+	pub procs: Option<NamingConvention>,
+}
+
+impl NamingConventionOverrides {
+	/// The conventions set in `self`, falling back to the ones set in `fallback`.
+	#[must_use]
+	pub fn or(&self, fallback: &Self) -> Self {
+		Self {
+			types: self.types.or(fallback.types),
+			enum_variants: self.enum_variants.or(fallback.enum_variants),
+			struct_fields: self.struct_fields.or(fallback.struct_fields),
+			procs: self.procs.or(fallback.procs),
+		}
+	}
+
+	/// Fills in the conventions that weren't set from `defaults`.
+	#[must_use]
+	pub fn resolve(&self, defaults: &NamingConventionConfig) -> NamingConventionConfig {
+		NamingConventionConfig {
+			types: self.types.unwrap_or(defaults.types),
+			enum_variants: self.enum_variants.unwrap_or(defaults.enum_variants),
+			struct_fields: self.struct_fields.unwrap_or(defaults.struct_fields),
+			procs: self.procs.unwrap_or(defaults.procs),
+		}
+	}
+}
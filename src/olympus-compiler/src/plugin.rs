@@ -0,0 +1,290 @@
+//! Out-of-process code generators.
+//!
+//! `olympusc compile <input> <output-dir> --plugin <name>` runs the `olympus-gen-<name>` executable from `PATH`,
+//! writes a request to its stdin and reads a response from its stdout. Both are a single JSON object. Anything the
+//! plugin writes to stderr is passed through, and a non-zero exit status fails the compilation.
+//!
+//! # Request
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "compiler_version": "0.0.0",
+//!   "input": "schemas/files.ol",
+//!   "parameters": { "package": "files" },
+//!   "naming_conventions": { "types": null, "enum_variants": null, "struct_fields": "snake", "procs": null },
+//!   "schema": {
+//!     "enums": [{ "name": "Action", "variants": [{ "name": "Delete", "tag": 1 }] }],
+//!     "structs": [{ "name": "File", "fields": [{ "name": "name", "type": { "kind": "string" } }] }],
+//!     "procedures": [{
+//!       "name": "GetFile",
+//!       "hash": "0xd99937932879aae9",
+//!       "params": [{ "name": "path", "type": { "kind": "string" } }],
+//!       "returns": { "kind": "struct", "index": 0, "name": "File", "indirect": false }
+//!     }]
+//!   }
+//! }
+//! ```
+//!
+//! - `version` is the version of this protocol and only changes if a plugin written against an older one would
+//!   misread the request.
+//! - `parameters` holds every `--plugin-opt KEY=VALUE`, a later value replaces an earlier one for the same key.
+//! - `naming_conventions` holds the naming convention flags that were passed, `null` leaves the choice to the plugin.
+//!   The values are `pascal`, `lower_camel`, `snake`, `shouty_snake`, `kebab` and `shouty_kebab`.
+//! - Names are written exactly as they are declared in the schema, declarations keep their order.
+//! - `hash` is the 64 bit FNV-1a hash of the procedure name that identifies it on the wire, as a hex string since
+//!   JSON numbers can't hold every `u64`.
+//! - `returns` is `null` for procedures that don't return anything.
+//!
+//! A type is one of
+//!
+//! - `{ "kind": "int", "int": "int8" }` for a fixed size integer. `int` is one of `int8`, `int16`, `int32`, `int64`,
+//!   `uint8`, `uint16`, `uint32` and `uint64`.
+//! - `{ "kind": "varint", "int": "uint16" }` for a variable length integer, with the same `int` values.
+//! - `{ "kind": "string" }`
+//! - `{ "kind": "array", "item": <type> }` and `{ "kind": "option", "item": <type> }`
+//! - `{ "kind": "enum", "index": 0, "name": "Action" }`, `index` points into `schema.enums`.
+//! - `{ "kind": "struct", "index": 0, "name": "File", "indirect": false }`, `index` points into `schema.structs`.
+//!   `indirect` is `true` for the reference that closes a recursive cycle, it has to be stored behind a pointer in
+//!   languages that store structs inline.
+//!
+//! # Response
+//!
+//! ```json
+//! { "files": [{ "path": "files/models.py", "contents": "..." }] }
+//! ```
+//!
+//! Paths are relative to the output directory and can't leave it. Directories are created as needed. No file is
+//! written if any of them already exists and `--overwrite` wasn't passed.
+
+use std::{
+	collections::BTreeMap,
+	io::Write,
+	path::{Component, Path, PathBuf},
+	process::{Command, Stdio},
+	thread,
+};
+
+use clap::ValueEnum;
+use eyre::{eyre, WrapErr};
+use olympus_verifier::{IntKind, ResolvedSchema, ResolvedType};
+use serde_json::{json, Value};
+
+use crate::{
+	generator::GeneratedFile,
+	naming::{NamingConvention, NamingConventionOverrides},
+};
+
+/// The version of the plugin protocol, see the module documentation.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The name of the executable that implements the plugin `name`.
+#[must_use]
+pub fn executable_name(name: &str) -> String {
+	format!("olympus-gen-{name}")
+}
+
+/// Runs the plugin `name` and returns the files it generated.
+pub fn run_plugin(
+	name: &str,
+	input: &Path,
+	schema: &ResolvedSchema,
+	parameters: &BTreeMap<String, String>,
+	naming_conventions: &NamingConventionOverrides,
+) -> eyre::Result<Vec<GeneratedFile>> {
+	let executable = executable_name(name);
+	let request = json!({
+		"version": PROTOCOL_VERSION,
+		"compiler_version": env!("CARGO_PKG_VERSION"),
+		"input": input.to_string_lossy(),
+		"parameters": parameters,
+		"naming_conventions": naming_conventions_to_json(naming_conventions),
+		"schema": schema_to_json(schema),
+	});
+
+	let mut child = Command::new(&executable)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::inherit())
+		.spawn()
+		.wrap_err_with(|| format!("Couldn't run the plugin '{executable}', is it on your PATH?"))?;
+
+	// written while the response is read, a plugin may start answering before it read the whole request and would
+	// wait for its stdout to be read once the pipe is full
+	let mut stdin = child.stdin.take().expect("stdin is piped");
+	let request = request.to_string();
+	let writer = thread::spawn(move || stdin.write_all(request.as_bytes()));
+
+	let output = child.wait_with_output()?;
+	// a plugin that exits without reading its input closes the pipe, its exit status explains why
+	let write_result = writer.join().expect("writing the request doesn't panic");
+	if !output.status.success() {
+		return Err(eyre!("The plugin '{executable}' failed ({})", output.status));
+	}
+	write_result.wrap_err_with(|| format!("Couldn't send the schema to the plugin '{executable}'"))?;
+
+	let response: Value = serde_json::from_slice(&output.stdout)
+		.wrap_err_with(|| format!("The plugin '{executable}' didn't respond with JSON"))?;
+	parse_response(&response).map_err(|err| eyre!("The plugin '{executable}' sent an invalid response: {err}"))
+}
+
+fn naming_conventions_to_json(naming_conventions: &NamingConventionOverrides) -> Value {
+	let name = |convention: Option<NamingConvention>| {
+		convention.and_then(|convention| convention.to_possible_value().map(|value| value.get_name().to_string()))
+	};
+
+	json!({
+		"types": name(naming_conventions.types),
+		"enum_variants": name(naming_conventions.enum_variants),
+		"struct_fields": name(naming_conventions.struct_fields),
+		"procs": name(naming_conventions.procs),
+	})
+}
+
+/// Serializes a schema in the format described in the module documentation.
+#[must_use]
+pub fn schema_to_json(schema: &ResolvedSchema) -> Value {
+	let enums = schema
+		.enums
+		.iter()
+		.map(|resolved| {
+			let variants = resolved
+				.variants
+				.iter()
+				.map(|variant| json!({ "name": variant.ident.value, "tag": variant.value }))
+				.collect::<Vec<_>>();
+			json!({ "name": resolved.ident.value, "variants": variants })
+		})
+		.collect::<Vec<_>>();
+
+	let structs = schema
+		.structs
+		.iter()
+		.map(|resolved| {
+			let fields = resolved
+				.fields
+				.iter()
+				.map(|field| json!({ "name": field.ident.value, "type": type_to_json(schema, &field.kind.value) }))
+				.collect::<Vec<_>>();
+			json!({ "name": resolved.ident.value, "fields": fields })
+		})
+		.collect::<Vec<_>>();
+
+	let procedures = schema
+		.procedures
+		.iter()
+		.map(|proc| {
+			let params = proc
+				.params
+				.iter()
+				.map(|param| json!({ "name": param.ident.value, "type": type_to_json(schema, &param.kind.value) }))
+				.collect::<Vec<_>>();
+			json!({
+				"name": proc.ident.value,
+				"hash": format!("{:#018x}", olympus_net_common::fnv(&proc.ident.value)),
+				"params": params,
+				"returns": proc.return_kind.as_ref().map(|kind| type_to_json(schema, &kind.value)),
+			})
+		})
+		.collect::<Vec<_>>();
+
+	json!({
+		"enums": enums,
+		"structs": structs,
+		"procedures": procedures,
+	})
+}
+
+fn type_to_json(schema: &ResolvedSchema, kind: &ResolvedType) -> Value {
+	match kind {
+		ResolvedType::Int(int) => json!({ "kind": "int", "int": int_name(*int) }),
+		ResolvedType::VariableInt(int) => json!({ "kind": "varint", "int": int_name(*int) }),
+		ResolvedType::String => json!({ "kind": "string" }),
+		ResolvedType::Array(ty) => json!({ "kind": "array", "item": type_to_json(schema, ty) }),
+		ResolvedType::Option(ty) => json!({ "kind": "option", "item": type_to_json(schema, ty) }),
+		ResolvedType::Enum(id) => json!({ "kind": "enum", "index": id.0, "name": schema[*id].ident.value }),
+		ResolvedType::Struct { id, indirect } => json!({
+			"kind": "struct",
+			"index": id.0,
+			"name": schema[*id].ident.value,
+			"indirect": indirect,
+		}),
+	}
+}
+
+fn int_name(kind: IntKind) -> &'static str {
+	match kind {
+		IntKind::Int8 => "int8",
+		IntKind::Int16 => "int16",
+		IntKind::Int32 => "int32",
+		IntKind::Int64 => "int64",
+		IntKind::UInt8 => "uint8",
+		IntKind::UInt16 => "uint16",
+		IntKind::UInt32 => "uint32",
+		IntKind::UInt64 => "uint64",
+	}
+}
+
+fn parse_response(response: &Value) -> Result<Vec<GeneratedFile>, String> {
+	let files = response
+		.get("files")
+		.and_then(Value::as_array)
+		.ok_or("expected a 'files' array")?;
+
+	files
+		.iter()
+		.enumerate()
+		.map(|(idx, file)| {
+			let path = file
+				.get("path")
+				.and_then(Value::as_str)
+				.ok_or_else(|| format!("file {idx} has no 'path' string"))?;
+			let contents = file
+				.get("contents")
+				.and_then(Value::as_str)
+				.ok_or_else(|| format!("file {idx} has no 'contents' string"))?;
+
+			let path = PathBuf::from(path);
+			if !is_contained(&path) {
+				return Err(format!("the path '{}' leaves the output directory", path.display()));
+			}
+
+			Ok(GeneratedFile {
+				path,
+				contents: contents.to_string(),
+			})
+		})
+		.collect()
+}
+
+/// Whether `path` is relative and stays below the directory it is joined to.
+fn is_contained(path: &Path) -> bool {
+	let mut has_file = false;
+	for component in path.components() {
+		match component {
+			Component::Normal(_) => has_file = true,
+			Component::CurDir => {}
+			Component::ParentDir | Component::RootDir | Component::Prefix(_) => return false,
+		}
+	}
+
+	has_file
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn response_paths_stay_in_the_output_directory() {
+		let response = |path: &str| json!({ "files": [{ "path": path, "contents": "" }] });
+
+		assert!(parse_response(&response("models.py")).is_ok());
+		assert!(parse_response(&response("./nested/models.py")).is_ok());
+		assert!(parse_response(&response("../models.py")).is_err());
+		assert!(parse_response(&response("nested/../../models.py")).is_err());
+		assert!(parse_response(&response("/etc/models.py")).is_err());
+		assert!(parse_response(&response("")).is_err());
+		assert!(parse_response(&json!({ "files": [{ "path": "models.py" }] })).is_err());
+	}
+}
@@ -0,0 +1,412 @@
+//! `olympus.toml`, the project file `olympusc` picks up from the current directory or one of its parents so the
+//! flags don't have to be repeated for every invocation.
+//!
+//! ```toml
+//! schemas = ["schemas/files.ol", "schemas/services/**/*.ol"]
+//! include = ["schemas/shared"]
+//!
+//! [naming]
+//! struct_fields = "lower_camel"
+//!
+//! [lints]
+//! all = "deny"
+//! unused_import = "warn"
+//!
+//! [rust]
+//! derives = ["PartialEq", "Eq"]
+//! serde = true
+//! type_derives = { FileId = ["Hash", "PartialOrd", "Ord"] }
+//!
+//! [[output]]
+//! language = "rust"
+//! path = "crates"
+//! crate_name = "common"
+//! crate_version = "1.2.0"
+//! crate_side = "client"
+//! olympus = { git = "https://github.com/example/olympus", rev = "v0.1.0" }
+//!
+//! [[output]]
+//! language = "typescript"
+//! path = "web/src/generated"
+//! naming = { procs = "snake" }
+//!
+//! [[output]]
+//! plugin = "docs"
+//! path = "docs/api"
+//! options = { format = "markdown" }
+//! ```
+//!
+//! Paths are relative to the directory of the project file. Schemas can be files, directories or globs, the files
+//! generated for a schema found in a directory or by a glob mirror where it was found. Every output is a directory:
+//! Rust, TypeScript and Python write one file per schema named after it, e.g. `files.ts` or `services/users.ts`,
+//! plugins write whatever files they return. The files of a Go or Kotlin package, a C# namespace or a C program share
+//! their declarations, so those languages write every schema into a single file like `olympus.go`, named after the
+//! schema if there is only one. Schemas are verified together, so a type is only unused if no schema uses it.
+
+use std::{
+	collections::BTreeMap,
+	path::{Path, PathBuf},
+};
+
+use eyre::{eyre, WrapErr};
+use olympus_spanned::ErrorCode;
+use olympus_verifier::{Lint, LintConfig, LintLevel};
+use serde::Deserialize;
+
+use crate::{
+	generator::{CompileLanguage, RustCrateSide, RustDependencies},
+	naming::NamingConventionOverrides,
+};
+
+pub const PROJECT_FILE_NAME: &str = "olympus.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Project {
+	/// The directory the project file is in, every other path is relative to it.
+	#[serde(skip)]
+	pub root: PathBuf,
+	/// The schema files, directories or globs to compile, imports are found on their own.
+	#[serde(default)]
+	pub schemas: Vec<PathBuf>,
+	/// Directories imports are looked up in if they aren't next to the importing file.
+	#[serde(default)]
+	pub include: Vec<PathBuf>,
+	/// The naming conventions of every output, outputs can override them.
+	#[serde(default)]
+	pub naming: NamingConventionOverrides,
+	/// Lint names or codes, or `all`, to `allow`, `warn` or `deny`.
+	#[serde(default)]
+	lints: BTreeMap<String, ProjectLintLevel>,
+	#[serde(default)]
+	pub rust: RustConfig,
+	#[serde(default, rename = "output")]
+	pub outputs: Vec<Output>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ProjectLintLevel {
+	Allow,
+	Warn,
+	Deny,
+}
+
+impl From<ProjectLintLevel> for LintLevel {
+	fn from(level: ProjectLintLevel) -> Self {
+		match level {
+			ProjectLintLevel::Allow => LintLevel::Allow,
+			ProjectLintLevel::Warn => LintLevel::Warn,
+			ProjectLintLevel::Deny => LintLevel::Deny,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RustConfig {
+	/// Derived for every generated enum and struct on top of the ones the generated code needs.
+	#[serde(default)]
+	pub derives: Vec<String>,
+	/// Derived for the enum or struct with the schema name of the key on top of `derives`.
+	#[serde(default)]
+	pub type_derives: BTreeMap<String, Vec<String>>,
+	/// Derive `Serialize` and `Deserialize` behind a `serde` feature, field and variant names follow the naming
+	/// conventions.
+	#[serde(default)]
+	pub serde: bool,
+}
+
+/// A `[[output]]`, either a built-in language or a plugin.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Output {
+	pub language: Option<CompileLanguage>,
+	/// Generate code with the `olympus-gen-<plugin>` executable instead of a built-in language.
+	pub plugin: Option<String>,
+	/// The directory to write to.
+	pub path: PathBuf,
+	/// The name of the generated file instead of one named after the schema, for a single schema compiled from the
+	/// command line.
+	#[serde(skip)]
+	pub file_name: Option<String>,
+	#[serde(default)]
+	pub naming: NamingConventionOverrides,
+	/// (Rust only) Generate a crate with this name in `path`, only a single schema can be compiled into a crate.
+	pub crate_name: Option<String>,
+	/// (Rust crates only) [default: 0.0.0-OLYMPUS-GENERATED]
+	pub crate_version: Option<String>,
+	/// (Rust crates only) Only generate the client or the server [default: both]
+	pub crate_side: Option<RustCrateSide>,
+	/// (Rust crates only) Where the runtime crates come from [default: the workspace the crate is generated into]
+	pub olympus: Option<OlympusSource>,
+	/// (Go only) [default: the name of `path`]
+	pub go_package: Option<String>,
+	/// (C# only) [default: Olympus]
+	pub cs_namespace: Option<String>,
+	/// (Kotlin only) [default: olympus]
+	pub kt_package: Option<String>,
+	/// Parameters passed to the plugin.
+	#[serde(default)]
+	pub options: BTreeMap<String, String>,
+}
+
+/// Where a generated crate gets the runtime crates from, at most one of a path, a git repository or a version.
+///
+/// A path is the directory the runtime crates are in and, like any path in a `Cargo.toml`, relative to the generated
+/// crate.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OlympusSource {
+	pub path: Option<PathBuf>,
+	pub git: Option<String>,
+	/// The branch, tag or commit of `git` to use.
+	pub rev: Option<String>,
+	pub version: Option<String>,
+}
+
+impl OlympusSource {
+	pub fn dependencies(&self) -> eyre::Result<RustDependencies> {
+		match (&self.path, &self.git, &self.rev, &self.version) {
+			(None, None, None, None) => Ok(RustDependencies::Workspace),
+			(Some(path), None, None, None) => Ok(RustDependencies::Path(path.clone())),
+			(None, Some(url), rev, None) => Ok(RustDependencies::Git {
+				url: url.clone(),
+				rev: rev.clone(),
+			}),
+			(None, None, None, Some(version)) => Ok(RustDependencies::Version(version.clone())),
+			(None, None, Some(_), None) => Err(eyre!("A rev can only be used with a git repository.")),
+			_ => Err(eyre!(
+				"The runtime crates can only come from one of a path, a git repository or a version."
+			)),
+		}
+	}
+}
+
+impl Project {
+	/// Looks for a project file in `dir` and its parents.
+	///
+	/// The root of the project is relative to `dir`, so paths in diagnostics stay short.
+	pub fn discover(dir: &Path) -> eyre::Result<Option<Self>> {
+		let dir = dir.canonicalize()?;
+		let mut root = PathBuf::new();
+		for ancestor in dir.ancestors() {
+			if ancestor.join(PROJECT_FILE_NAME).is_file() {
+				return Self::load(&root.join(PROJECT_FILE_NAME)).map(Some);
+			}
+			root.push("..");
+		}
+
+		Ok(None)
+	}
+
+	/// Reads and validates the project file at `path`.
+	pub fn load(path: &Path) -> eyre::Result<Self> {
+		let src = std::fs::read_to_string(path).wrap_err_with(|| format!("Couldn't read '{}'", path.display()))?;
+		let mut project =
+			toml::from_str::<Self>(&src).wrap_err_with(|| format!("Invalid project file '{}'", path.display()))?;
+		project.root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+		project
+			.validate()
+			.wrap_err_with(|| format!("Invalid project file '{}'", path.display()))?;
+		Ok(project)
+	}
+
+	/// Checks the outputs can be generated, done by [`Project::load`] for project files.
+	pub fn validate(&self) -> eyre::Result<()> {
+		self.lint_config()?;
+
+		for output in &self.outputs {
+			match (&output.language, &output.plugin) {
+				(Some(_), Some(_)) => return Err(eyre!("An output can't have both a language and a plugin.")),
+				(None, None) => return Err(eyre!("Every output needs a language or a plugin.")),
+				(Some(CompileLanguage::Rust), None) => {}
+				(Some(_), None) if output.crate_name.is_some() => {
+					return Err(eyre!("Only Rust outputs can be generated as a crate."));
+				}
+				_ => {}
+			}
+
+			let crate_only = output.crate_version.is_some() || output.crate_side.is_some() || output.olympus.is_some();
+			if crate_only && output.crate_name.is_none() {
+				return Err(eyre!(
+					"crate_version, crate_side and olympus can only be used for outputs with a crate_name."
+				));
+			}
+			if let Some(olympus) = &output.olympus {
+				olympus.dependencies()?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// `path` relative to the working directory instead of the project.
+	#[must_use]
+	pub fn resolve(&self, path: &Path) -> PathBuf {
+		self.root.join(path)
+	}
+
+	/// Every schema the project lists, once even if several entries match it.
+	pub fn schemas(&self) -> eyre::Result<Vec<SchemaInput>> {
+		let mut schemas = Vec::<SchemaInput>::new();
+		for entry in &self.schemas {
+			for schema in find_schemas(&self.resolve(entry))? {
+				if !schemas.iter().any(|found| found.path == schema.path) {
+					schemas.push(schema);
+				}
+			}
+		}
+
+		Ok(schemas)
+	}
+
+	#[must_use]
+	pub fn include_paths(&self) -> Vec<PathBuf> {
+		self.include.iter().map(|include| self.resolve(include)).collect()
+	}
+
+	/// The configured lint levels, `all` is applied before the lints that are named.
+	pub fn lint_config(&self) -> eyre::Result<LintConfig> {
+		let mut config = LintConfig::default();
+		let mut lints = Vec::with_capacity(self.lints.len());
+		for (name, level) in &self.lints {
+			match parse_lint_selector(name).map_err(|err| eyre!(err))? {
+				LintSelector::All => config.set_all((*level).into()),
+				LintSelector::Lint(lint) => lints.push((lint, *level)),
+			}
+		}
+
+		for (lint, level) in lints {
+			config.set(lint, level.into());
+		}
+
+		Ok(config)
+	}
+}
+
+/// A lint name or code, or `all` to target every lint.
+#[derive(Debug, Clone, Copy)]
+pub enum LintSelector {
+	All,
+	Lint(Lint),
+}
+
+pub fn parse_lint_selector(name: &str) -> Result<LintSelector, String> {
+	if name == "all" {
+		return Ok(LintSelector::All);
+	}
+
+	let by_code = ErrorCode::from_code(name).and_then(Lint::from_code);
+	by_code
+		.or_else(|| Lint::from_name(name))
+		.map(LintSelector::Lint)
+		.ok_or_else(|| {
+			let names = Lint::ALL.iter().map(|lint| lint.name()).collect::<Vec<_>>().join(", ");
+			format!("unknown lint '{name}', expected 'all' or one of: {names}")
+		})
+}
+
+/// A schema found by [`find_schemas`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaInput {
+	pub path: PathBuf,
+	/// The directory of the schema relative to the directory that was searched, output trees mirror it.
+	pub relative_dir: PathBuf,
+}
+
+/// The schemas `input` refers to: the file itself, every `.ol` file below a directory or every file matching a glob.
+pub fn find_schemas(input: &Path) -> eyre::Result<Vec<SchemaInput>> {
+	let pattern = input.to_string_lossy();
+	let is_glob = is_glob(input);
+	if !is_glob {
+		if !input.try_exists()? {
+			return Err(eyre!("The provided path doesn't exist."));
+		}
+		if input.is_file() {
+			return Ok(vec![SchemaInput {
+				path: input.to_path_buf(),
+				relative_dir: PathBuf::new(),
+			}]);
+		}
+	}
+
+	let (base, _) = schema_search_dir(input);
+	let pattern = if is_glob {
+		pattern.into_owned()
+	} else {
+		format!("{}/**/*.ol", glob::Pattern::escape(&pattern))
+	};
+
+	let mut schemas = Vec::new();
+	for path in glob::glob(&pattern).map_err(|err| eyre!("Invalid pattern '{pattern}': {err}"))? {
+		let path = path?;
+		if !path.is_file() {
+			continue;
+		}
+
+		let relative_dir = path
+			.strip_prefix(&base)
+			.ok()
+			.and_then(Path::parent)
+			.map(Path::to_path_buf)
+			.unwrap_or_default();
+		schemas.push(SchemaInput { path, relative_dir });
+	}
+
+	if schemas.is_empty() {
+		return Err(eyre!("'{}' doesn't match any schemas.", input.display()));
+	}
+
+	Ok(schemas)
+}
+
+/// The directory [`find_schemas`] looks for schemas in and whether it looks in its subdirectories as well.
+#[must_use]
+pub fn schema_search_dir(input: &Path) -> (PathBuf, bool) {
+	if is_glob(input) {
+		let base = input
+			.components()
+			.take_while(|component| !is_glob(Path::new(component.as_os_str())))
+			.collect::<PathBuf>();
+		(base, input.to_string_lossy().contains("**"))
+	} else if input.is_file() {
+		(input.parent().unwrap_or(Path::new("")).to_path_buf(), false)
+	} else {
+		(input.to_path_buf(), true)
+	}
+}
+
+fn is_glob(input: &Path) -> bool {
+	input.to_string_lossy().contains(['*', '?', '['])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lints_apply_all_first() {
+		let project = toml::from_str::<Project>(
+			r#"
+			[lints]
+			unused_type = "allow"
+			all = "deny"
+			"#,
+		)
+		.unwrap();
+
+		let config = project.lint_config().unwrap();
+		assert_eq!(config.level(Lint::UnusedType), LintLevel::Allow);
+		assert_eq!(config.level(Lint::EmptyEnum), LintLevel::Deny);
+	}
+
+	#[test]
+	fn outputs_need_exactly_one_generator() {
+		for output in ["language = \"go\"\nplugin = \"docs\"", "crate_name = \"common\""] {
+			let project = toml::from_str::<Project>(&format!("[[output]]\npath = \"gen\"\n{output}")).unwrap();
+			assert!(project.validate().is_err(), "{output}");
+		}
+	}
+}
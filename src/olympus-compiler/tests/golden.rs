@@ -0,0 +1,68 @@
+//! The code every built-in language generates for `tests/schemas/golden.ol`, which uses every kind of type, compared to
+//! the files in `tests/golden`. Run with `OLYMPUS_BLESS=1` to update them after an intended change.
+
+use std::path::Path;
+
+use olympus_compiler::{generate, verify_schema, CompileLanguage, GenerateOptions, LintConfig};
+
+fn check(language: CompileLanguage) {
+	let verification = verify_schema(Path::new("tests/schemas/golden.ol"), &LintConfig::default());
+	assert!(verification.diagnostics.is_empty(), "{:?}", verification.diagnostics);
+
+	let files = generate(&verification.schema.unwrap(), &GenerateOptions::new(language)).unwrap();
+	assert_eq!(files.len(), 1);
+	let golden = Path::new("tests/golden").join(language.default_file_name());
+	if std::env::var_os("OLYMPUS_BLESS").is_some() {
+		std::fs::write(&golden, &files[0].contents).unwrap();
+		return;
+	}
+
+	let expected = std::fs::read_to_string(&golden).unwrap();
+	if files[0].contents != expected {
+		// only the first difference, the whole file would be too much to compare by eye
+		let (line, (generated, expected)) = files[0]
+			.contents
+			.lines()
+			.chain(std::iter::once("<end of file>"))
+			.zip(expected.lines().chain(std::iter::once("<end of file>")))
+			.enumerate()
+			.find(|(_, (generated, expected))| generated != expected)
+			.unwrap_or((0, ("", "")));
+		panic!(
+			"the generated code differs from '{}' on line {}, run with OLYMPUS_BLESS=1 to update it\n generated: \
+			 {generated}\n  expected: {expected}",
+			golden.display(),
+			line + 1
+		);
+	}
+}
+
+#[test]
+fn typescript() {
+	check(CompileLanguage::TypeScript);
+}
+
+#[test]
+fn python() {
+	check(CompileLanguage::Python);
+}
+
+#[test]
+fn go() {
+	check(CompileLanguage::Go);
+}
+
+#[test]
+fn c() {
+	check(CompileLanguage::C);
+}
+
+#[test]
+fn csharp() {
+	check(CompileLanguage::CSharp);
+}
+
+#[test]
+fn kotlin() {
+	check(CompileLanguage::Kotlin);
+}
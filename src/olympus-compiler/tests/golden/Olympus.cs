@@ -0,0 +1,729 @@
+// /-- THIS FILE WAS AUTOMATICALLY GENERATED BY OLYMPUS --\
+// <auto-generated />
+
+#nullable enable
+
+namespace Olympus;
+
+// Runtime for the olympus wire format, every generated type and the client below build on it.
+// The compiler writes the namespace above this, the runtime needs .NET 6 or newer.
+//
+//     await using var connection = await OlympusConnection.ConnectAsync("127.0.0.1", 9999);
+//     var client = new ClientRpc(connection);
+
+using System;
+using System.Buffers.Binary;
+using System.Collections.Generic;
+using System.IO;
+using System.Linq;
+using System.Net.Sockets;
+using System.Text;
+using System.Threading;
+using System.Threading.Tasks;
+
+/// <summary>Thrown for data that doesn't match the wire format and for calls on a closed connection.</summary>
+public sealed class OlympusException : Exception
+{
+    public OlympusException(string message) : base(message) { }
+
+    public OlympusException(string message, Exception inner) : base(message, inner) { }
+}
+
+/// <summary>
+/// Holds the value of an option nested in another option, <c>T??</c> isn't a type.
+/// </summary>
+public readonly record struct Some<T>(T Value);
+
+/// <summary>Serializes values in the olympus wire format. Integers are big-endian.</summary>
+public sealed class Writer
+{
+    private byte[] buffer = new byte[256];
+    private int length;
+
+    public int Length => length;
+
+    public byte[] ToArray() => buffer.AsSpan(0, length).ToArray();
+
+    private Span<byte> Reserve(int size)
+    {
+        if (buffer.Length - length < size)
+        {
+            Array.Resize(ref buffer, Math.Max(buffer.Length * 2, length + size));
+        }
+
+        Span<byte> span = buffer.AsSpan(length, size);
+        length += size;
+        return span;
+    }
+
+    public void WriteU8(byte value) => Reserve(1)[0] = value;
+
+    public void WriteI8(sbyte value) => Reserve(1)[0] = (byte)value;
+
+    public void WriteU16(ushort value) => BinaryPrimitives.WriteUInt16BigEndian(Reserve(2), value);
+
+    public void WriteI16(short value) => BinaryPrimitives.WriteInt16BigEndian(Reserve(2), value);
+
+    public void WriteU32(uint value) => BinaryPrimitives.WriteUInt32BigEndian(Reserve(4), value);
+
+    public void WriteI32(int value) => BinaryPrimitives.WriteInt32BigEndian(Reserve(4), value);
+
+    public void WriteU64(ulong value) => BinaryPrimitives.WriteUInt64BigEndian(Reserve(8), value);
+
+    public void WriteI64(long value) => BinaryPrimitives.WriteInt64BigEndian(Reserve(8), value);
+
+    /// <summary>LEB128, 7 bits per byte starting with the least significant ones.</summary>
+    public void WriteVarUint(ulong value)
+    {
+        while (value >= 0x80)
+        {
+            WriteU8((byte)((value & 0x7f) | 0x80));
+            value >>= 7;
+        }
+        WriteU8((byte)value);
+    }
+
+    /// <summary>
+    /// Zigzag encodes a value of the given width so small negative numbers stay short, then writes it like
+    /// <see cref="WriteVarUint"/>.
+    /// </summary>
+    public void WriteVarInt(long value, int bits)
+    {
+        ulong zigzag = (ulong)((value << 1) ^ (value >> 63));
+        if (bits < 64)
+        {
+            zigzag &= (1UL << bits) - 1;
+        }
+        WriteVarUint(zigzag);
+    }
+
+    public void WriteString(string value)
+    {
+        int size = Encoding.UTF8.GetByteCount(value);
+        WriteU32((uint)size);
+        Encoding.UTF8.GetBytes(value, Reserve(size));
+    }
+
+    public void WriteArray<T>(IReadOnlyList<T> value, Action<T> write)
+    {
+        WriteU32((uint)value.Count);
+        foreach (T item in value)
+        {
+            write(item);
+        }
+    }
+
+    public void WriteOption<T>(T? value, Action<T> write) where T : class
+    {
+        if (value is null)
+        {
+            WriteU8(0);
+        }
+        else
+        {
+            WriteU8(1);
+            write(value);
+        }
+    }
+
+    public void WriteNullable<T>(T? value, Action<T> write) where T : struct
+    {
+        if (value is { } item)
+        {
+            WriteU8(1);
+            write(item);
+        }
+        else
+        {
+            WriteU8(0);
+        }
+    }
+}
+
+/// <summary>Deserializes values in the olympus wire format, the counterpart to <see cref="Writer"/>.</summary>
+public sealed class Reader
+{
+    private static readonly UTF8Encoding StrictUtf8 = new(encoderShouldEmitUTF8Identifier: false, throwOnInvalidBytes: true);
+
+    private readonly byte[] data;
+    private int offset;
+
+    public Reader(byte[] data)
+    {
+        this.data = data;
+    }
+
+    public int Remaining => data.Length - offset;
+
+    private ReadOnlySpan<byte> Take(int size)
+    {
+        if (size > Remaining)
+        {
+            throw new OlympusException("unexpected end of data");
+        }
+
+        ReadOnlySpan<byte> span = data.AsSpan(offset, size);
+        offset += size;
+        return span;
+    }
+
+    public byte ReadU8() => Take(1)[0];
+
+    public sbyte ReadI8() => (sbyte)Take(1)[0];
+
+    public ushort ReadU16() => BinaryPrimitives.ReadUInt16BigEndian(Take(2));
+
+    public short ReadI16() => BinaryPrimitives.ReadInt16BigEndian(Take(2));
+
+    public uint ReadU32() => BinaryPrimitives.ReadUInt32BigEndian(Take(4));
+
+    public int ReadI32() => BinaryPrimitives.ReadInt32BigEndian(Take(4));
+
+    public ulong ReadU64() => BinaryPrimitives.ReadUInt64BigEndian(Take(8));
+
+    public long ReadI64() => BinaryPrimitives.ReadInt64BigEndian(Take(8));
+
+    public ulong ReadVarUint()
+    {
+        ulong value = 0;
+        for (int shift = 0; shift < 64; shift += 7)
+        {
+            byte b = ReadU8();
+            value |= (ulong)(b & 0x7f) << shift;
+            if ((b & 0x80) == 0)
+            {
+                return value;
+            }
+        }
+        throw new OlympusException("varint is too long");
+    }
+
+    public long ReadVarInt(int bits)
+    {
+        ulong zigzag = ReadVarUint();
+        if (bits < 64)
+        {
+            zigzag &= (1UL << bits) - 1;
+        }
+        return (long)(zigzag >> 1) ^ -(long)(zigzag & 1);
+    }
+
+    public string ReadString()
+    {
+        uint size = ReadU32();
+        if (size > Remaining)
+        {
+            throw new OlympusException("unexpected end of data");
+        }
+
+        ReadOnlySpan<byte> bytes = Take((int)size);
+        try
+        {
+            return StrictUtf8.GetString(bytes);
+        }
+        catch (DecoderFallbackException e)
+        {
+            throw new OlympusException("string isn't valid UTF-8", e);
+        }
+    }
+
+    public List<T> ReadArray<T>(Func<T> read)
+    {
+        uint count = ReadU32();
+        // a bogus length shouldn't allocate more up front than the data that is left
+        var items = new List<T>((int)Math.Min(count, (uint)Remaining));
+        for (uint idx = 0; idx < count; idx++)
+        {
+            items.Add(read());
+        }
+        return items;
+    }
+
+    public T? ReadOption<T>(Func<T> read) where T : class => ReadU8() == 0 ? null : read();
+
+    public T? ReadNullable<T>(Func<T> read) where T : struct => ReadU8() == 0 ? null : read();
+
+    public T ReadEnum<T>() where T : struct, Enum
+    {
+        ushort tag = ReadU16();
+        if (!Enum.IsDefined(typeof(T), tag))
+        {
+            throw new OlympusException($"invalid {typeof(T).Name} tag: {tag}");
+        }
+        return (T)Enum.ToObject(typeof(T), tag);
+    }
+}
+
+/// <summary>
+/// Frames requests and matches responses to calls.
+///
+/// Every frame starts with a compressed flag and the data length, compressed frames also carry the decompressed
+/// length. Requests are always sent uncompressed.
+/// </summary>
+public sealed class OlympusConnection : IDisposable, IAsyncDisposable
+{
+    private const int MaxPacketSize = 8 * 1024 * 1024;
+
+    private readonly TcpClient client;
+    private readonly NetworkStream stream;
+    // also queues calls to the same procedure in the order they were sent
+    private readonly SemaphoreSlim writeLock = new(1, 1);
+    private readonly object pendingLock = new();
+    private readonly Dictionary<ulong, Queue<TaskCompletionSource<byte[]>>> pending = new();
+    private readonly Task readLoop;
+    private Exception? error;
+
+    private OlympusConnection(TcpClient client)
+    {
+        this.client = client;
+        stream = client.GetStream();
+        readLoop = Task.Run(ReadFramesAsync);
+    }
+
+    public static async Task<OlympusConnection> ConnectAsync(
+        string host,
+        int port,
+        CancellationToken cancellationToken = default)
+    {
+        var client = new TcpClient { NoDelay = true };
+        try
+        {
+            await client.ConnectAsync(host, port, cancellationToken).ConfigureAwait(false);
+        }
+        catch
+        {
+            client.Dispose();
+            throw;
+        }
+        return new OlympusConnection(client);
+    }
+
+    /// <summary>The 64 bit FNV-1a hash, procedures are identified by the hash of their name.</summary>
+    public static ulong Fnv1a(string value)
+    {
+        ulong hash = 0xcbf29ce484222325;
+        foreach (byte b in Encoding.UTF8.GetBytes(value))
+        {
+            hash = unchecked((hash ^ b) * 0x100000001b3);
+        }
+        return hash;
+    }
+
+    /// <summary>Sends a request without waiting for a response.</summary>
+    public Task SendAsync(string procedure, Action<Writer> write, CancellationToken cancellationToken = default) =>
+        SendAsync(procedure, write, null, cancellationToken);
+
+    /// <summary>Sends a request and reads the response to it with <paramref name="read"/>.</summary>
+    public async Task<T> CallAsync<T>(
+        string procedure,
+        Action<Writer> write,
+        Func<Reader, T> read,
+        CancellationToken cancellationToken = default)
+    {
+        var response = new TaskCompletionSource<byte[]>(TaskCreationOptions.RunContinuationsAsynchronously);
+        await SendAsync(procedure, write, response, cancellationToken).ConfigureAwait(false);
+        byte[] payload = await response.Task.WaitAsync(cancellationToken).ConfigureAwait(false);
+        return read(new Reader(payload));
+    }
+
+    private async Task SendAsync(
+        string procedure,
+        Action<Writer> write,
+        TaskCompletionSource<byte[]>? response,
+        CancellationToken cancellationToken)
+    {
+        ulong hash = Fnv1a(procedure);
+        var w = new Writer();
+        // the length is filled in once the params are written
+        w.WriteU8(0);
+        w.WriteU32(0);
+        w.WriteU64(hash);
+        write(w);
+
+        byte[] frame = w.ToArray();
+        int payloadLength = frame.Length - 5;
+        if (payloadLength > MaxPacketSize)
+        {
+            throw new OlympusException($"request is {payloadLength} bytes, the limit is {MaxPacketSize}");
+        }
+        BinaryPrimitives.WriteUInt32BigEndian(frame.AsSpan(1), (uint)payloadLength);
+
+        await writeLock.WaitAsync(cancellationToken).ConfigureAwait(false);
+        try
+        {
+            lock (pendingLock)
+            {
+                if (error is not null)
+                {
+                    throw new OlympusException("connection closed", error);
+                }
+
+                if (response is not null)
+                {
+                    if (!pending.TryGetValue(hash, out Queue<TaskCompletionSource<byte[]>>? queue))
+                    {
+                        queue = new Queue<TaskCompletionSource<byte[]>>();
+                        pending[hash] = queue;
+                    }
+                    queue.Enqueue(response);
+                }
+            }
+
+            await stream.WriteAsync(frame, cancellationToken).ConfigureAwait(false);
+        }
+        finally
+        {
+            writeLock.Release();
+        }
+    }
+
+    private async Task ReadFramesAsync()
+    {
+        try
+        {
+            byte[] header = new byte[5];
+            while (await ReadExactlyAsync(header, allowEnd: true).ConfigureAwait(false))
+            {
+                bool compressed = header[0] != 0;
+                uint dataLength = BinaryPrimitives.ReadUInt32BigEndian(header.AsSpan(1));
+                uint decompressedLength = 0;
+                if (compressed)
+                {
+                    byte[] lengthBuffer = new byte[4];
+                    await ReadExactlyAsync(lengthBuffer, allowEnd: false).ConfigureAwait(false);
+                    decompressedLength = BinaryPrimitives.ReadUInt32BigEndian(lengthBuffer);
+                }
+                if (dataLength > MaxPacketSize || decompressedLength > MaxPacketSize)
+                {
+                    throw new OlympusException("frame exceeds the maximum packet size");
+                }
+
+                byte[] data = new byte[dataLength];
+                await ReadExactlyAsync(data, allowEnd: false).ConfigureAwait(false);
+                if (compressed)
+                {
+                    data = Lz4Decompress(data, (int)decompressedLength);
+                }
+                if (data.Length < 8)
+                {
+                    throw new OlympusException("frame is too short to hold a procedure hash");
+                }
+
+                ulong hash = BinaryPrimitives.ReadUInt64BigEndian(data);
+                TaskCompletionSource<byte[]>? response = null;
+                lock (pendingLock)
+                {
+                    if (pending.TryGetValue(hash, out Queue<TaskCompletionSource<byte[]>>? queue))
+                    {
+                        response = queue.Dequeue();
+                        if (queue.Count == 0)
+                        {
+                            pending.Remove(hash);
+                        }
+                    }
+                }
+                response?.TrySetResult(data[8..]);
+            }
+
+            Fail(new OlympusException("connection closed by the server"));
+        }
+        catch (Exception e)
+        {
+            Fail(e);
+        }
+    }
+
+    /// <summary>Returns false if the stream ended before the first byte and <paramref name="allowEnd"/> is set.</summary>
+    private async Task<bool> ReadExactlyAsync(byte[] buffer, bool allowEnd)
+    {
+        int read = 0;
+        while (read < buffer.Length)
+        {
+            int count = await stream.ReadAsync(buffer.AsMemory(read)).ConfigureAwait(false);
+            if (count == 0)
+            {
+                if (read == 0 && allowEnd)
+                {
+                    return false;
+                }
+                throw new EndOfStreamException("connection closed in the middle of a frame");
+            }
+            read += count;
+        }
+        return true;
+    }
+
+    private void Fail(Exception e)
+    {
+        List<TaskCompletionSource<byte[]>> responses;
+        lock (pendingLock)
+        {
+            error ??= e;
+            responses = pending.Values.SelectMany(queue => queue).ToList();
+            pending.Clear();
+        }
+
+        foreach (TaskCompletionSource<byte[]> response in responses)
+        {
+            response.TrySetException(new OlympusException("connection closed", e));
+        }
+    }
+
+    /// <summary>Decompresses a raw LZ4 block, which is how large frames are compressed.</summary>
+    private static byte[] Lz4Decompress(byte[] input, int decompressedLength)
+    {
+        var invalid = new OlympusException("invalid LZ4 block");
+        byte[] output = new byte[decompressedLength];
+        int inputIdx = 0;
+        int outputIdx = 0;
+
+        int ReadLength(int length)
+        {
+            if (length != 15)
+            {
+                return length;
+            }
+
+            while (true)
+            {
+                if (inputIdx >= input.Length)
+                {
+                    throw invalid;
+                }
+
+                byte b = input[inputIdx++];
+                length += b;
+                if (b != 255)
+                {
+                    return length;
+                }
+            }
+        }
+
+        while (inputIdx < input.Length)
+        {
+            byte token = input[inputIdx++];
+
+            int literalLength = ReadLength(token >> 4);
+            if (literalLength > input.Length - inputIdx || literalLength > output.Length - outputIdx)
+            {
+                throw invalid;
+            }
+            Array.Copy(input, inputIdx, output, outputIdx, literalLength);
+            inputIdx += literalLength;
+            outputIdx += literalLength;
+
+            // the last sequence only has literals
+            if (inputIdx >= input.Length)
+            {
+                break;
+            }
+            if (input.Length - inputIdx < 2)
+            {
+                throw invalid;
+            }
+
+            int offset = input[inputIdx] | (input[inputIdx + 1] << 8);
+            inputIdx += 2;
+            int matchLength = ReadLength(token & 0x0f) + 4;
+            if (offset == 0 || offset > outputIdx || matchLength > output.Length - outputIdx)
+            {
+                throw invalid;
+            }
+
+            // matches may overlap with the bytes they produce, so they have to be copied one at a time
+            for (int idx = 0; idx < matchLength; idx++)
+            {
+                output[outputIdx] = output[outputIdx - offset];
+                outputIdx++;
+            }
+        }
+
+        if (outputIdx != output.Length)
+        {
+            throw invalid;
+        }
+        return output;
+    }
+
+    public void Dispose()
+    {
+        client.Dispose();
+        writeLock.Dispose();
+    }
+
+    public async ValueTask DisposeAsync()
+    {
+        client.Dispose();
+        try
+        {
+            await readLoop.ConfigureAwait(false);
+        }
+        finally
+        {
+            writeLock.Dispose();
+        }
+    }
+}
+
+public enum Action : ushort
+{
+    Delete = 1,
+    SecureDelete = 2,
+    Encrypt = 300,
+}
+
+public sealed record Numbers(byte U8, sbyte I8, ushort U16, short I16, uint U32, int I32, ulong U64, long I64, byte Vu8, sbyte Vi8, ushort Vu16, short Vi16, uint Vu32, int Vi32, long Vi64)
+{
+    public void Encode(Writer w)
+    {
+        w.WriteU8(U8);
+        w.WriteI8(I8);
+        w.WriteU16(U16);
+        w.WriteI16(I16);
+        w.WriteU32(U32);
+        w.WriteI32(I32);
+        w.WriteU64(U64);
+        w.WriteI64(I64);
+        w.WriteVarUint(Vu8);
+        w.WriteVarInt(Vi8, 8);
+        w.WriteVarUint(Vu16);
+        w.WriteVarInt(Vi16, 16);
+        w.WriteVarUint(Vu32);
+        w.WriteVarInt(Vi32, 32);
+        w.WriteVarInt(Vi64, 64);
+    }
+
+    public static Numbers Decode(Reader r) => new(
+        U8: r.ReadU8(),
+        I8: r.ReadI8(),
+        U16: r.ReadU16(),
+        I16: r.ReadI16(),
+        U32: r.ReadU32(),
+        I32: r.ReadI32(),
+        U64: r.ReadU64(),
+        I64: r.ReadI64(),
+        Vu8: (byte)r.ReadVarUint(),
+        Vi8: (sbyte)r.ReadVarInt(8),
+        Vu16: (ushort)r.ReadVarUint(),
+        Vi16: (short)r.ReadVarInt(16),
+        Vu32: (uint)r.ReadVarUint(),
+        Vi32: (int)r.ReadVarInt(32),
+        Vi64: r.ReadVarInt(64));
+}
+
+public sealed record File(string Path, ulong Size, List<byte> Content, User? Owner, List<string?> Tags, Some<string?>? Label, Action Action, Numbers Numbers)
+{
+    public void Encode(Writer w)
+    {
+        w.WriteString(Path);
+        w.WriteVarUint(Size);
+        w.WriteArray(Content, item0 => w.WriteU8(item0));
+        w.WriteOption(Owner, item0 => item0.Encode(w));
+        w.WriteArray(Tags, item0 => w.WriteOption(item0, item1 => w.WriteString(item1)));
+        w.WriteNullable(Label, item0 => w.WriteOption(item0.Value, item1 => w.WriteString(item1)));
+        w.WriteU16((ushort)Action);
+        Numbers.Encode(w);
+    }
+
+    public static File Decode(Reader r) => new(
+        Path: r.ReadString(),
+        Size: r.ReadVarUint(),
+        Content: r.ReadArray(() => r.ReadU8()),
+        Owner: r.ReadOption(() => User.Decode(r)),
+        Tags: r.ReadArray(() => r.ReadOption(() => r.ReadString())),
+        Label: r.ReadNullable(() => new Some<string?>(r.ReadOption(() => r.ReadString()))),
+        Action: r.ReadEnum<Action>(),
+        Numbers: Numbers.Decode(r));
+}
+
+public sealed record Directory(string Name, List<File> Files, Directory? Parent, string Type)
+{
+    public void Encode(Writer w)
+    {
+        w.WriteString(Name);
+        w.WriteArray(Files, item0 => item0.Encode(w));
+        w.WriteOption(Parent, item0 => item0.Encode(w));
+        w.WriteString(Type);
+    }
+
+    public static Directory Decode(Reader r) => new(
+        Name: r.ReadString(),
+        Files: r.ReadArray(() => File.Decode(r)),
+        Parent: r.ReadOption(() => Directory.Decode(r)),
+        Type: r.ReadString());
+}
+
+public sealed record User(string Name, long Id)
+{
+    public void Encode(Writer w)
+    {
+        w.WriteString(Name);
+        w.WriteVarInt(Id, 64);
+    }
+
+    public static User Decode(Reader r) => new(
+        Name: r.ReadString(),
+        Id: r.ReadVarInt(64));
+}
+
+public sealed record GetFileParams(string Path, Action? AfterAction)
+{
+    public void Encode(Writer w)
+    {
+        w.WriteString(Path);
+        w.WriteNullable(AfterAction, item0 => w.WriteU16((ushort)item0));
+    }
+
+    public static GetFileParams Decode(Reader r) => new(
+        Path: r.ReadString(),
+        AfterAction: r.ReadNullable(() => r.ReadEnum<Action>()));
+}
+
+public sealed record ListDirectoryParams(Directory Directory)
+{
+    public void Encode(Writer w)
+    {
+        Directory.Encode(w);
+    }
+
+    public static ListDirectoryParams Decode(Reader r) => new(
+        Directory: Directory.Decode(r));
+}
+
+public sealed record DeleteFileParams(string Path)
+{
+    public void Encode(Writer w)
+    {
+        w.WriteString(Path);
+    }
+
+    public static DeleteFileParams Decode(Reader r) => new(
+        Path: r.ReadString());
+}
+
+public sealed class ClientRpc
+{
+    private readonly OlympusConnection connection;
+
+    public ClientRpc(OlympusConnection connection)
+    {
+        this.connection = connection;
+    }
+
+    /// <summary>Calls <c>GetServerVersion</c> and waits for its response.</summary>
+    public Task<sbyte> GetServerVersionAsync(CancellationToken cancellationToken = default) =>
+        connection.CallAsync("GetServerVersion", _ => { }, r => r.ReadI8(), cancellationToken);
+
+    /// <summary>Calls <c>GetFile</c> and waits for its response.</summary>
+    public Task<File> GetFileAsync(GetFileParams @params, CancellationToken cancellationToken = default) =>
+        connection.CallAsync("GetFile", @params.Encode, r => File.Decode(r), cancellationToken);
+
+    /// <summary>Calls <c>ListDirectory</c> and waits for its response.</summary>
+    public Task<List<File>> ListDirectoryAsync(ListDirectoryParams @params, CancellationToken cancellationToken = default) =>
+        connection.CallAsync("ListDirectory", @params.Encode, r => r.ReadArray(() => File.Decode(r)), cancellationToken);
+
+    /// <summary>Sends <c>DeleteFile</c> without waiting for the server, it doesn't return anything.</summary>
+    public Task DeleteFileAsync(DeleteFileParams @params, CancellationToken cancellationToken = default) =>
+        connection.SendAsync("DeleteFile", @params.Encode, cancellationToken);
+}
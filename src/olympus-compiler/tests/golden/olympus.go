@@ -0,0 +1,812 @@
+// /-- THIS FILE WAS AUTOMATICALLY GENERATED BY OLYMPUS --\
+// Code generated by olympusc. DO NOT EDIT.
+
+package olympus
+
+// Runtime for the olympus wire format, every generated type and the client below build on it.
+// The compiler writes the package clause above this, the runtime needs Go 1.21 or newer.
+//
+//	connection, err := Dial(ctx, "127.0.0.1:9999")
+//	client := NewClientRpc(connection)
+
+import (
+	"context"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"sync"
+	"unicode/utf8"
+)
+
+// ErrInvalidData is wrapped by every error caused by data that doesn't match the wire format.
+var ErrInvalidData = errors.New("olympus: invalid data")
+
+// ErrConnectionClosed is returned by calls on a connection that was closed before they were answered.
+var ErrConnectionClosed = errors.New("olympus: connection closed")
+
+const maxPacketSize = 8 * 1024 * 1024
+
+// Marshaler is implemented by every generated type.
+type Marshaler interface {
+	MarshalOlympus(w *Writer)
+}
+
+// Unmarshaler is implemented by pointers to every generated type.
+type Unmarshaler[T any] interface {
+	*T
+	UnmarshalOlympus(r *Reader) error
+}
+
+// Writer serializes values in the olympus wire format. Integers are big-endian.
+type Writer struct {
+	buf []byte
+}
+
+func (w *Writer) Bytes() []byte {
+	return w.buf
+}
+
+func (w *Writer) WriteU8(value uint8) {
+	w.buf = append(w.buf, value)
+}
+
+func (w *Writer) WriteI8(value int8) {
+	w.buf = append(w.buf, uint8(value))
+}
+
+func (w *Writer) WriteU16(value uint16) {
+	w.buf = binary.BigEndian.AppendUint16(w.buf, value)
+}
+
+func (w *Writer) WriteI16(value int16) {
+	w.buf = binary.BigEndian.AppendUint16(w.buf, uint16(value))
+}
+
+func (w *Writer) WriteU32(value uint32) {
+	w.buf = binary.BigEndian.AppendUint32(w.buf, value)
+}
+
+func (w *Writer) WriteI32(value int32) {
+	w.buf = binary.BigEndian.AppendUint32(w.buf, uint32(value))
+}
+
+func (w *Writer) WriteU64(value uint64) {
+	w.buf = binary.BigEndian.AppendUint64(w.buf, value)
+}
+
+func (w *Writer) WriteI64(value int64) {
+	w.buf = binary.BigEndian.AppendUint64(w.buf, uint64(value))
+}
+
+// WriteVarUint writes LEB128, 7 bits per byte starting with the least significant ones.
+func (w *Writer) WriteVarUint(value uint64) {
+	for value >= 0x80 {
+		w.buf = append(w.buf, uint8(value&0x7f)|0x80)
+		value >>= 7
+	}
+	w.buf = append(w.buf, uint8(value))
+}
+
+// WriteVarInt zigzag encodes a value of the given width so small negative numbers stay short, then writes it like
+// WriteVarUint.
+func (w *Writer) WriteVarInt(value int64, bits uint) {
+	zigzag := uint64((value << 1) ^ (value >> 63))
+	if bits < 64 {
+		zigzag &= (1 << bits) - 1
+	}
+	w.WriteVarUint(zigzag)
+}
+
+func (w *Writer) WriteString(value string) {
+	w.WriteU32(uint32(len(value)))
+	w.buf = append(w.buf, value...)
+}
+
+func WriteArray[T any](w *Writer, value []T, write func(item T)) {
+	w.WriteU32(uint32(len(value)))
+	for _, item := range value {
+		write(item)
+	}
+}
+
+func WriteOption[T any](w *Writer, value *T, write func(item T)) {
+	if value == nil {
+		w.WriteU8(0)
+	} else {
+		w.WriteU8(1)
+		write(*value)
+	}
+}
+
+// Reader deserializes values in the olympus wire format, the counterpart to Writer.
+type Reader struct {
+	data   []byte
+	offset int
+}
+
+func NewReader(data []byte) *Reader {
+	return &Reader{data: data}
+}
+
+func (r *Reader) take(size int) ([]byte, error) {
+	if size > len(r.data)-r.offset {
+		return nil, fmt.Errorf("%w: unexpected end of data", ErrInvalidData)
+	}
+
+	chunk := r.data[r.offset : r.offset+size]
+	r.offset += size
+	return chunk, nil
+}
+
+func (r *Reader) ReadU8() (uint8, error) {
+	chunk, err := r.take(1)
+	if err != nil {
+		return 0, err
+	}
+	return chunk[0], nil
+}
+
+func (r *Reader) ReadI8() (int8, error) {
+	value, err := r.ReadU8()
+	return int8(value), err
+}
+
+func (r *Reader) ReadU16() (uint16, error) {
+	chunk, err := r.take(2)
+	if err != nil {
+		return 0, err
+	}
+	return binary.BigEndian.Uint16(chunk), nil
+}
+
+func (r *Reader) ReadI16() (int16, error) {
+	value, err := r.ReadU16()
+	return int16(value), err
+}
+
+func (r *Reader) ReadU32() (uint32, error) {
+	chunk, err := r.take(4)
+	if err != nil {
+		return 0, err
+	}
+	return binary.BigEndian.Uint32(chunk), nil
+}
+
+func (r *Reader) ReadI32() (int32, error) {
+	value, err := r.ReadU32()
+	return int32(value), err
+}
+
+func (r *Reader) ReadU64() (uint64, error) {
+	chunk, err := r.take(8)
+	if err != nil {
+		return 0, err
+	}
+	return binary.BigEndian.Uint64(chunk), nil
+}
+
+func (r *Reader) ReadI64() (int64, error) {
+	value, err := r.ReadU64()
+	return int64(value), err
+}
+
+func (r *Reader) readVarUint() (uint64, error) {
+	var value uint64
+	for shift := uint(0); shift < 64; shift += 7 {
+		b, err := r.ReadU8()
+		if err != nil {
+			return 0, err
+		}
+
+		value |= uint64(b&0x7f) << shift
+		if b&0x80 == 0 {
+			return value, nil
+		}
+	}
+	return 0, fmt.Errorf("%w: varint is too long", ErrInvalidData)
+}
+
+func (r *Reader) ReadString() (string, error) {
+	length, err := r.ReadU32()
+	if err != nil {
+		return "", err
+	}
+
+	chunk, err := r.take(int(length))
+	if err != nil {
+		return "", err
+	}
+	if !utf8.Valid(chunk) {
+		return "", fmt.Errorf("%w: string isn't valid UTF-8", ErrInvalidData)
+	}
+	return string(chunk), nil
+}
+
+func ReadVarUint[T ~uint8 | ~uint16 | ~uint32 | ~uint64](r *Reader) (T, error) {
+	value, err := r.readVarUint()
+	return T(value), err
+}
+
+func ReadVarInt[T ~int8 | ~int16 | ~int32 | ~int64](r *Reader) (T, error) {
+	zigzag, err := r.readVarUint()
+	return T(zigzag>>1) ^ -T(zigzag&1), err
+}
+
+func ReadArray[T any](r *Reader, read func() (T, error)) ([]T, error) {
+	length, err := r.ReadU32()
+	if err != nil {
+		return nil, err
+	}
+
+	// a bogus length shouldn't allocate more up front than the data that is left
+	items := make([]T, 0, min(int(length), len(r.data)-r.offset))
+	for idx := uint32(0); idx < length; idx++ {
+		item, err := read()
+		if err != nil {
+			return nil, err
+		}
+		items = append(items, item)
+	}
+	return items, nil
+}
+
+func ReadOption[T any](r *Reader, read func() (T, error)) (*T, error) {
+	present, err := r.ReadU8()
+	if err != nil || present == 0 {
+		return nil, err
+	}
+
+	value, err := read()
+	if err != nil {
+		return nil, err
+	}
+	return &value, nil
+}
+
+// ReadValue reads any generated type, e.g. ReadValue[File](r).
+func ReadValue[T any, P Unmarshaler[T]](r *Reader) (T, error) {
+	var value T
+	err := P(&value).UnmarshalOlympus(r)
+	return value, err
+}
+
+// Fnv1a is the 64 bit FNV-1a hash, procedures are identified by the hash of their name.
+func Fnv1a(value string) uint64 {
+	hash := uint64(0xcbf29ce484222325)
+	for idx := 0; idx < len(value); idx++ {
+		hash ^= uint64(value[idx])
+		hash *= 0x100000001b3
+	}
+	return hash
+}
+
+// lz4Decompress decompresses a raw LZ4 block, which is how large frames are compressed.
+func lz4Decompress(input []byte, decompressedLength int) ([]byte, error) {
+	errInvalid := fmt.Errorf("%w: invalid LZ4 block", ErrInvalidData)
+	output := make([]byte, 0, decompressedLength)
+	idx := 0
+
+	readLength := func(length int) (int, error) {
+		if length != 15 {
+			return length, nil
+		}
+
+		for {
+			if idx >= len(input) {
+				return 0, errInvalid
+			}
+
+			b := input[idx]
+			idx++
+			length += int(b)
+			if b != 255 {
+				return length, nil
+			}
+		}
+	}
+
+	for idx < len(input) {
+		token := input[idx]
+		idx++
+
+		literalLength, err := readLength(int(token >> 4))
+		if err != nil {
+			return nil, err
+		}
+		if literalLength > len(input)-idx || len(output)+literalLength > decompressedLength {
+			return nil, errInvalid
+		}
+		output = append(output, input[idx:idx+literalLength]...)
+		idx += literalLength
+
+		// the last sequence only has literals
+		if idx >= len(input) {
+			break
+		}
+		if idx+2 > len(input) {
+			return nil, errInvalid
+		}
+
+		offset := int(input[idx]) | int(input[idx+1])<<8
+		idx += 2
+		matchLength, err := readLength(int(token & 0x0f))
+		if err != nil {
+			return nil, err
+		}
+		matchLength += 4
+		if offset == 0 || offset > len(output) || len(output)+matchLength > decompressedLength {
+			return nil, errInvalid
+		}
+
+		// matches may overlap with the bytes they produce, so they have to be copied one at a time
+		start := len(output) - offset
+		for matchIdx := 0; matchIdx < matchLength; matchIdx++ {
+			output = append(output, output[start+matchIdx])
+		}
+	}
+
+	if len(output) != decompressedLength {
+		return nil, errInvalid
+	}
+	return output, nil
+}
+
+type response struct {
+	data []byte
+	err  error
+}
+
+// Connection frames requests and matches responses to calls.
+//
+// Every frame starts with a compressed flag and the data length, compressed frames also carry the decompressed
+// length. Requests are always sent uncompressed.
+type Connection struct {
+	conn net.Conn
+
+	// guards writes too, so calls to the same procedure are queued in the order they were sent
+	mu      sync.Mutex
+	pending map[uint64][]chan response
+	err     error
+}
+
+func Dial(ctx context.Context, address string) (*Connection, error) {
+	var dialer net.Dialer
+	conn, err := dialer.DialContext(ctx, "tcp", address)
+	if err != nil {
+		return nil, err
+	}
+	return NewConnection(conn), nil
+}
+
+// NewConnection takes ownership of conn and starts reading responses from it.
+func NewConnection(conn net.Conn) *Connection {
+	c := &Connection{
+		conn:    conn,
+		pending: map[uint64][]chan response{},
+	}
+	go c.readFrames()
+	return c
+}
+
+func (c *Connection) Close() error {
+	return c.conn.Close()
+}
+
+// Send sends a request without waiting for a response.
+func (c *Connection) Send(procedure string, write func(w *Writer)) error {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	return c.sendLocked(procedure, write)
+}
+
+func (c *Connection) sendLocked(procedure string, write func(w *Writer)) error {
+	if c.err != nil {
+		return c.err
+	}
+
+	var payload Writer
+	payload.WriteU64(Fnv1a(procedure))
+	write(&payload)
+
+	frame := Writer{buf: make([]byte, 0, 5+len(payload.buf))}
+	frame.WriteU8(0)
+	frame.WriteU32(uint32(len(payload.buf)))
+	frame.buf = append(frame.buf, payload.buf...)
+	_, err := c.conn.Write(frame.buf)
+	return err
+}
+
+// Call sends a request and returns its decoded response.
+//
+// The server answers the requests of a connection in order, so responses are matched to calls in the order they were
+// sent.
+func Call[T any](
+	ctx context.Context,
+	c *Connection,
+	procedure string,
+	write func(w *Writer),
+	read func(r *Reader) (T, error),
+) (T, error) {
+	var zero T
+	// buffered so the response can be delivered even if the caller stopped waiting
+	ch := make(chan response, 1)
+
+	c.mu.Lock()
+	if err := c.sendLocked(procedure, write); err != nil {
+		c.mu.Unlock()
+		return zero, err
+	}
+	hash := Fnv1a(procedure)
+	c.pending[hash] = append(c.pending[hash], ch)
+	c.mu.Unlock()
+
+	select {
+	case res := <-ch:
+		if res.err != nil {
+			return zero, res.err
+		}
+		return read(NewReader(res.data))
+	case <-ctx.Done():
+		return zero, ctx.Err()
+	}
+}
+
+func (c *Connection) readFrames() {
+	err := c.readLoop()
+	if errors.Is(err, io.EOF) || errors.Is(err, io.ErrUnexpectedEOF) || errors.Is(err, net.ErrClosed) {
+		err = ErrConnectionClosed
+	}
+
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.err = err
+	// every call that is still waiting won't get a response anymore
+	for _, queue := range c.pending {
+		for _, ch := range queue {
+			ch <- response{err: err}
+		}
+	}
+	c.pending = nil
+	c.conn.Close()
+}
+
+func (c *Connection) readLoop() error {
+	var header [9]byte
+	for {
+		if _, err := io.ReadFull(c.conn, header[:5]); err != nil {
+			return err
+		}
+
+		compressed := header[0] != 0
+		length := binary.BigEndian.Uint32(header[1:5])
+		if length > maxPacketSize {
+			return fmt.Errorf("%w: packet too big: %d bytes", ErrInvalidData, length)
+		}
+
+		var decompressedLength uint32
+		if compressed {
+			if _, err := io.ReadFull(c.conn, header[5:9]); err != nil {
+				return err
+			}
+			decompressedLength = binary.BigEndian.Uint32(header[5:9])
+			if decompressedLength > maxPacketSize {
+				return fmt.Errorf("%w: packet too big: %d bytes", ErrInvalidData, decompressedLength)
+			}
+		}
+
+		data := make([]byte, length)
+		if _, err := io.ReadFull(c.conn, data); err != nil {
+			return err
+		}
+		if compressed {
+			var err error
+			if data, err = lz4Decompress(data, int(decompressedLength)); err != nil {
+				return err
+			}
+		}
+		if len(data) < 8 {
+			return fmt.Errorf("%w: frame without a procedure hash", ErrInvalidData)
+		}
+
+		hash := binary.BigEndian.Uint64(data)
+		c.mu.Lock()
+		if queue := c.pending[hash]; len(queue) > 0 {
+			queue[0] <- response{data: data[8:]}
+			c.pending[hash] = queue[1:]
+		}
+		c.mu.Unlock()
+	}
+}
+
+type Action uint16
+
+const (
+	ActionDelete       Action = 1
+	ActionSecureDelete Action = 2
+	ActionEncrypt      Action = 300
+)
+
+func (v Action) MarshalOlympus(w *Writer) {
+	w.WriteU16(uint16(v))
+}
+
+func (v *Action) UnmarshalOlympus(r *Reader) error {
+	tag, err := r.ReadU16()
+	if err != nil {
+		return err
+	}
+
+	switch Action(tag) {
+	case ActionDelete, ActionSecureDelete, ActionEncrypt:
+		*v = Action(tag)
+		return nil
+	default:
+		return fmt.Errorf("%w: invalid Action tag: %d", ErrInvalidData, tag)
+	}
+}
+
+type Numbers struct {
+	U8   uint8
+	I8   int8
+	U16  uint16
+	I16  int16
+	U32  uint32
+	I32  int32
+	U64  uint64
+	I64  int64
+	Vu8  uint8
+	Vi8  int8
+	Vu16 uint16
+	Vi16 int16
+	Vu32 uint32
+	Vi32 int32
+	Vi64 int64
+}
+
+func (v Numbers) MarshalOlympus(w *Writer) {
+	w.WriteU8(v.U8)
+	w.WriteI8(v.I8)
+	w.WriteU16(v.U16)
+	w.WriteI16(v.I16)
+	w.WriteU32(v.U32)
+	w.WriteI32(v.I32)
+	w.WriteU64(v.U64)
+	w.WriteI64(v.I64)
+	w.WriteVarUint(uint64(v.Vu8))
+	w.WriteVarInt(int64(v.Vi8), 8)
+	w.WriteVarUint(uint64(v.Vu16))
+	w.WriteVarInt(int64(v.Vi16), 16)
+	w.WriteVarUint(uint64(v.Vu32))
+	w.WriteVarInt(int64(v.Vi32), 32)
+	w.WriteVarInt(int64(v.Vi64), 64)
+}
+
+func (v *Numbers) UnmarshalOlympus(r *Reader) (err error) {
+	if v.U8, err = r.ReadU8(); err != nil {
+		return err
+	}
+	if v.I8, err = r.ReadI8(); err != nil {
+		return err
+	}
+	if v.U16, err = r.ReadU16(); err != nil {
+		return err
+	}
+	if v.I16, err = r.ReadI16(); err != nil {
+		return err
+	}
+	if v.U32, err = r.ReadU32(); err != nil {
+		return err
+	}
+	if v.I32, err = r.ReadI32(); err != nil {
+		return err
+	}
+	if v.U64, err = r.ReadU64(); err != nil {
+		return err
+	}
+	if v.I64, err = r.ReadI64(); err != nil {
+		return err
+	}
+	if v.Vu8, err = ReadVarUint[uint8](r); err != nil {
+		return err
+	}
+	if v.Vi8, err = ReadVarInt[int8](r); err != nil {
+		return err
+	}
+	if v.Vu16, err = ReadVarUint[uint16](r); err != nil {
+		return err
+	}
+	if v.Vi16, err = ReadVarInt[int16](r); err != nil {
+		return err
+	}
+	if v.Vu32, err = ReadVarUint[uint32](r); err != nil {
+		return err
+	}
+	if v.Vi32, err = ReadVarInt[int32](r); err != nil {
+		return err
+	}
+	if v.Vi64, err = ReadVarInt[int64](r); err != nil {
+		return err
+	}
+	return nil
+}
+
+type File struct {
+	Path    string
+	Size    uint64
+	Content []uint8
+	Owner   *User
+	Tags    []*string
+	Label   **string
+	Action  Action
+	Numbers Numbers
+}
+
+func (v File) MarshalOlympus(w *Writer) {
+	w.WriteString(v.Path)
+	w.WriteVarUint(uint64(v.Size))
+	WriteArray(w, v.Content, func(item0 uint8) { w.WriteU8(item0) })
+	WriteOption(w, v.Owner, func(item0 User) { item0.MarshalOlympus(w) })
+	WriteArray(w, v.Tags, func(item0 *string) { WriteOption(w, item0, func(item1 string) { w.WriteString(item1) }) })
+	WriteOption(w, v.Label, func(item0 *string) { WriteOption(w, item0, func(item1 string) { w.WriteString(item1) }) })
+	v.Action.MarshalOlympus(w)
+	v.Numbers.MarshalOlympus(w)
+}
+
+func (v *File) UnmarshalOlympus(r *Reader) (err error) {
+	if v.Path, err = r.ReadString(); err != nil {
+		return err
+	}
+	if v.Size, err = ReadVarUint[uint64](r); err != nil {
+		return err
+	}
+	if v.Content, err = ReadArray(r, func() (uint8, error) { return r.ReadU8() }); err != nil {
+		return err
+	}
+	if v.Owner, err = ReadOption(r, func() (User, error) { return ReadValue[User](r) }); err != nil {
+		return err
+	}
+	if v.Tags, err = ReadArray(r, func() (*string, error) { return ReadOption(r, func() (string, error) { return r.ReadString() }) }); err != nil {
+		return err
+	}
+	if v.Label, err = ReadOption(r, func() (*string, error) { return ReadOption(r, func() (string, error) { return r.ReadString() }) }); err != nil {
+		return err
+	}
+	if v.Action, err = ReadValue[Action](r); err != nil {
+		return err
+	}
+	if v.Numbers, err = ReadValue[Numbers](r); err != nil {
+		return err
+	}
+	return nil
+}
+
+type Directory struct {
+	Name   string
+	Files  []File
+	Parent *Directory
+	Type   string
+}
+
+func (v Directory) MarshalOlympus(w *Writer) {
+	w.WriteString(v.Name)
+	WriteArray(w, v.Files, func(item0 File) { item0.MarshalOlympus(w) })
+	WriteOption(w, v.Parent, func(item0 Directory) { item0.MarshalOlympus(w) })
+	w.WriteString(v.Type)
+}
+
+func (v *Directory) UnmarshalOlympus(r *Reader) (err error) {
+	if v.Name, err = r.ReadString(); err != nil {
+		return err
+	}
+	if v.Files, err = ReadArray(r, func() (File, error) { return ReadValue[File](r) }); err != nil {
+		return err
+	}
+	if v.Parent, err = ReadOption(r, func() (Directory, error) { return ReadValue[Directory](r) }); err != nil {
+		return err
+	}
+	if v.Type, err = r.ReadString(); err != nil {
+		return err
+	}
+	return nil
+}
+
+type User struct {
+	Name string
+	Id   int64
+}
+
+func (v User) MarshalOlympus(w *Writer) {
+	w.WriteString(v.Name)
+	w.WriteVarInt(int64(v.Id), 64)
+}
+
+func (v *User) UnmarshalOlympus(r *Reader) (err error) {
+	if v.Name, err = r.ReadString(); err != nil {
+		return err
+	}
+	if v.Id, err = ReadVarInt[int64](r); err != nil {
+		return err
+	}
+	return nil
+}
+
+type GetFileParams struct {
+	Path        string
+	AfterAction *Action
+}
+
+func (v GetFileParams) MarshalOlympus(w *Writer) {
+	w.WriteString(v.Path)
+	WriteOption(w, v.AfterAction, func(item0 Action) { item0.MarshalOlympus(w) })
+}
+
+func (v *GetFileParams) UnmarshalOlympus(r *Reader) (err error) {
+	if v.Path, err = r.ReadString(); err != nil {
+		return err
+	}
+	if v.AfterAction, err = ReadOption(r, func() (Action, error) { return ReadValue[Action](r) }); err != nil {
+		return err
+	}
+	return nil
+}
+
+type ListDirectoryParams struct {
+	Directory Directory
+}
+
+func (v ListDirectoryParams) MarshalOlympus(w *Writer) {
+	v.Directory.MarshalOlympus(w)
+}
+
+func (v *ListDirectoryParams) UnmarshalOlympus(r *Reader) (err error) {
+	if v.Directory, err = ReadValue[Directory](r); err != nil {
+		return err
+	}
+	return nil
+}
+
+type DeleteFileParams struct {
+	Path string
+}
+
+func (v DeleteFileParams) MarshalOlympus(w *Writer) {
+	w.WriteString(v.Path)
+}
+
+func (v *DeleteFileParams) UnmarshalOlympus(r *Reader) (err error) {
+	if v.Path, err = r.ReadString(); err != nil {
+		return err
+	}
+	return nil
+}
+
+// ClientRpc has a method for every procedure.
+type ClientRpc struct {
+	Connection *Connection
+}
+
+func NewClientRpc(connection *Connection) *ClientRpc {
+	return &ClientRpc{Connection: connection}
+}
+
+// GetServerVersion calls `GetServerVersion` and waits for its response.
+func (c *ClientRpc) GetServerVersion(ctx context.Context) (int8, error) {
+	return Call(ctx, c.Connection, "GetServerVersion", func(w *Writer) {}, func(r *Reader) (int8, error) { return r.ReadI8() })
+}
+
+// GetFile calls `GetFile` and waits for its response.
+func (c *ClientRpc) GetFile(ctx context.Context, params GetFileParams) (File, error) {
+	return Call(ctx, c.Connection, "GetFile", params.MarshalOlympus, func(r *Reader) (File, error) { return ReadValue[File](r) })
+}
+
+// ListDirectory calls `ListDirectory` and waits for its response.
+func (c *ClientRpc) ListDirectory(ctx context.Context, params ListDirectoryParams) ([]File, error) {
+	return Call(ctx, c.Connection, "ListDirectory", params.MarshalOlympus, func(r *Reader) ([]File, error) { return ReadArray(r, func() (File, error) { return ReadValue[File](r) }) })
+}
+
+// DeleteFile sends `DeleteFile` without waiting for the server, it doesn't return anything.
+func (c *ClientRpc) DeleteFile(params DeleteFileParams) error {
+	return c.Connection.Send("DeleteFile", params.MarshalOlympus)
+}
@@ -0,0 +1,1121 @@
+/* /-- THIS FILE WAS AUTOMATICALLY GENERATED BY OLYMPUS --\ */
+#pragma once
+
+/*
+ * Runtime for the olympus wire format, every generated type and request builds on it.
+ *
+ * Nothing allocates: encoding writes into a buffer supplied by the caller and decoding stores arrays in a
+ * caller-supplied arena, every write and read checks the space that is left. Define OLYMPUS_IMPLEMENTATION in exactly
+ * one source file before including this header to compile the implementation.
+ */
+
+#ifndef OLYMPUS_RUNTIME_H
+#define OLYMPUS_RUNTIME_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#if defined(__GNUC__) || defined(__clang__)
+#define OLYMPUS_MAYBE_UNUSED __attribute__((unused))
+#else
+#define OLYMPUS_MAYBE_UNUSED
+#endif
+
+/* Arrays are stored in the arena at multiples of this, it has to satisfy the alignment of every decoded type. */
+#ifndef OLYMPUS_ARENA_ALIGN
+#define OLYMPUS_ARENA_ALIGN 8
+#endif
+
+#define OLYMPUS_MAX_PACKET_SIZE (8u * 1024u * 1024u)
+
+typedef enum olympus_result {
+	OLYMPUS_OK = 0,
+	/* the writer's buffer can't fit the encoded value */
+	OLYMPUS_ERR_BUFFER_FULL,
+	/* the data ended in the middle of a value or frame, more data might complete it */
+	OLYMPUS_ERR_UNEXPECTED_END,
+	/* the reader's arena can't fit the decoded arrays */
+	OLYMPUS_ERR_ARENA_FULL,
+	/* the data doesn't match the wire format */
+	OLYMPUS_ERR_INVALID_DATA,
+} olympus_result;
+
+/* Returns the result of `expr` from the calling function if it isn't OLYMPUS_OK. */
+#define OLYMPUS_TRY(expr) \
+	do { \
+		olympus_result olympus_try_result = (expr); \
+		if (olympus_try_result != OLYMPUS_OK) { \
+			return olympus_try_result; \
+		} \
+	} while (0)
+
+typedef struct olympus_writer {
+	uint8_t *data;
+	size_t capacity;
+	size_t len;
+} olympus_writer;
+
+/*
+ * Decoded strings point into `data` and decoded arrays into `arena`, so both have to outlive the decoded values.
+ */
+typedef struct olympus_reader {
+	const uint8_t *data;
+	size_t len;
+	size_t pos;
+	uint8_t *arena;
+	size_t arena_capacity;
+	size_t arena_len;
+} olympus_reader;
+
+/* Not NUL terminated. */
+typedef struct olympus_string {
+	const char *data;
+	uint32_t len;
+} olympus_string;
+
+typedef struct olympus_frame {
+	/* identifies the procedure the frame belongs to, see the OLYMPUS_PROC_ constants */
+	uint64_t hash;
+	const uint8_t *payload;
+	size_t payload_len;
+	/* how many bytes of the input the frame took up */
+	size_t frame_len;
+} olympus_frame;
+
+olympus_writer olympus_writer_init(uint8_t *data, size_t capacity);
+olympus_reader olympus_reader_init(const uint8_t *data, size_t len, uint8_t *arena, size_t arena_capacity);
+olympus_string olympus_string_from(const char *value);
+
+olympus_result olympus_write_u8(olympus_writer *w, uint8_t value);
+olympus_result olympus_write_u16(olympus_writer *w, uint16_t value);
+olympus_result olympus_write_u32(olympus_writer *w, uint32_t value);
+olympus_result olympus_write_u64(olympus_writer *w, uint64_t value);
+olympus_result olympus_write_i8(olympus_writer *w, int8_t value);
+olympus_result olympus_write_i16(olympus_writer *w, int16_t value);
+olympus_result olympus_write_i32(olympus_writer *w, int32_t value);
+olympus_result olympus_write_i64(olympus_writer *w, int64_t value);
+olympus_result olympus_write_vu8(olympus_writer *w, uint8_t value);
+olympus_result olympus_write_vu16(olympus_writer *w, uint16_t value);
+olympus_result olympus_write_vu32(olympus_writer *w, uint32_t value);
+olympus_result olympus_write_vu64(olympus_writer *w, uint64_t value);
+olympus_result olympus_write_vi8(olympus_writer *w, int8_t value);
+olympus_result olympus_write_vi16(olympus_writer *w, int16_t value);
+olympus_result olympus_write_vi32(olympus_writer *w, int32_t value);
+olympus_result olympus_write_vi64(olympus_writer *w, int64_t value);
+olympus_result olympus_write_string(olympus_writer *w, olympus_string value);
+
+olympus_result olympus_read_u8(olympus_reader *r, uint8_t *out);
+olympus_result olympus_read_u16(olympus_reader *r, uint16_t *out);
+olympus_result olympus_read_u32(olympus_reader *r, uint32_t *out);
+olympus_result olympus_read_u64(olympus_reader *r, uint64_t *out);
+olympus_result olympus_read_i8(olympus_reader *r, int8_t *out);
+olympus_result olympus_read_i16(olympus_reader *r, int16_t *out);
+olympus_result olympus_read_i32(olympus_reader *r, int32_t *out);
+olympus_result olympus_read_i64(olympus_reader *r, int64_t *out);
+olympus_result olympus_read_vu8(olympus_reader *r, uint8_t *out);
+olympus_result olympus_read_vu16(olympus_reader *r, uint16_t *out);
+olympus_result olympus_read_vu32(olympus_reader *r, uint32_t *out);
+olympus_result olympus_read_vu64(olympus_reader *r, uint64_t *out);
+olympus_result olympus_read_vi8(olympus_reader *r, int8_t *out);
+olympus_result olympus_read_vi16(olympus_reader *r, int16_t *out);
+olympus_result olympus_read_vi32(olympus_reader *r, int32_t *out);
+olympus_result olympus_read_vi64(olympus_reader *r, int64_t *out);
+olympus_result olympus_read_string(olympus_reader *r, olympus_string *out);
+
+/* Reserves space for `len` items of `item_size` bytes in the arena. */
+olympus_result olympus_arena_alloc(olympus_reader *r, size_t item_size, uint32_t len, void **out);
+
+/*
+ * Starts a request frame for the procedure with the given hash, the params have to be written next.
+ * `start` has to be passed to olympus_end_request once they are.
+ */
+olympus_result olympus_begin_request(olympus_writer *w, uint64_t hash, size_t *start);
+olympus_result olympus_end_request(olympus_writer *w, size_t start);
+
+/*
+ * Parses the frame at the start of `data`, returning OLYMPUS_ERR_UNEXPECTED_END until all of it was received.
+ * Large responses are LZ4 compressed, they are decompressed into `scratch`, which should be able to hold the largest
+ * expected response.
+ */
+olympus_result olympus_read_frame(
+	const uint8_t *data,
+	size_t len,
+	uint8_t *scratch,
+	size_t scratch_capacity,
+	olympus_frame *out
+);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* OLYMPUS_RUNTIME_H */
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef uint16_t Action;
+enum {
+	ACTION_DELETE = 1,
+	ACTION_SECURE_DELETE = 2,
+	ACTION_ENCRYPT = 300,
+};
+
+typedef struct Numbers Numbers;
+typedef struct File File;
+typedef struct Directory Directory;
+typedef struct User User;
+typedef struct GetFileParams GetFileParams;
+typedef struct ListDirectoryParams ListDirectoryParams;
+typedef struct DeleteFileParams DeleteFileParams;
+typedef struct olympus_array_u8 olympus_array_u8;
+typedef struct olympus_option_User olympus_option_User;
+typedef struct olympus_option_string olympus_option_string;
+typedef struct olympus_array_option_string olympus_array_option_string;
+typedef struct olympus_option_option_string olympus_option_option_string;
+typedef struct olympus_array_File olympus_array_File;
+typedef struct olympus_option_ref_Directory olympus_option_ref_Directory;
+typedef struct olympus_option_Action olympus_option_Action;
+
+struct Numbers {
+	uint8_t u8;
+	int8_t i8;
+	uint16_t u16;
+	int16_t i16;
+	uint32_t u32;
+	int32_t i32;
+	uint64_t u64;
+	int64_t i64;
+	uint8_t vu8;
+	int8_t vi8;
+	uint16_t vu16;
+	int16_t vi16;
+	uint32_t vu32;
+	int32_t vi32;
+	int64_t vi64;
+};
+
+struct olympus_array_u8 {
+	uint8_t *items;
+	uint32_t len;
+};
+
+struct User {
+	olympus_string name;
+	int64_t id;
+};
+
+struct olympus_option_User {
+	bool present;
+	User value;
+};
+
+struct olympus_array_option_string {
+	olympus_option_string *items;
+	uint32_t len;
+};
+
+struct olympus_option_string {
+	bool present;
+	olympus_string value;
+};
+
+struct olympus_option_option_string {
+	bool present;
+	olympus_option_string value;
+};
+
+struct File {
+	olympus_string path;
+	uint64_t size;
+	olympus_array_u8 content;
+	olympus_option_User owner;
+	olympus_array_option_string tags;
+	olympus_option_option_string label;
+	Action action;
+	Numbers numbers;
+};
+
+struct olympus_array_File {
+	File *items;
+	uint32_t len;
+};
+
+struct olympus_option_ref_Directory {
+	bool present;
+	Directory *value;
+};
+
+struct Directory {
+	olympus_string name;
+	olympus_array_File files;
+	olympus_option_ref_Directory parent;
+	olympus_string type;
+};
+
+struct olympus_option_Action {
+	bool present;
+	Action value;
+};
+
+struct GetFileParams {
+	olympus_string path;
+	olympus_option_Action after_action;
+};
+
+struct ListDirectoryParams {
+	Directory directory;
+};
+
+struct DeleteFileParams {
+	olympus_string path;
+};
+
+olympus_result olympus_encode_Action(olympus_writer *w, const Action *value);
+olympus_result olympus_decode_Action(olympus_reader *r, Action *out);
+olympus_result olympus_encode_Numbers(olympus_writer *w, const Numbers *value);
+olympus_result olympus_decode_Numbers(olympus_reader *r, Numbers *out);
+olympus_result olympus_encode_File(olympus_writer *w, const File *value);
+olympus_result olympus_decode_File(olympus_reader *r, File *out);
+olympus_result olympus_encode_Directory(olympus_writer *w, const Directory *value);
+olympus_result olympus_decode_Directory(olympus_reader *r, Directory *out);
+olympus_result olympus_encode_User(olympus_writer *w, const User *value);
+olympus_result olympus_decode_User(olympus_reader *r, User *out);
+olympus_result olympus_encode_GetFileParams(olympus_writer *w, const GetFileParams *value);
+olympus_result olympus_decode_GetFileParams(olympus_reader *r, GetFileParams *out);
+olympus_result olympus_encode_ListDirectoryParams(olympus_writer *w, const ListDirectoryParams *value);
+olympus_result olympus_decode_ListDirectoryParams(olympus_reader *r, ListDirectoryParams *out);
+olympus_result olympus_encode_DeleteFileParams(olympus_writer *w, const DeleteFileParams *value);
+olympus_result olympus_decode_DeleteFileParams(olympus_reader *r, DeleteFileParams *out);
+
+#ifdef OLYMPUS_IMPLEMENTATION
+
+#ifndef OLYMPUS_RUNTIME_IMPLEMENTATION
+#define OLYMPUS_RUNTIME_IMPLEMENTATION
+
+#include <string.h>
+
+olympus_writer olympus_writer_init(uint8_t *data, size_t capacity) {
+	olympus_writer w;
+	w.data = data;
+	w.capacity = capacity;
+	w.len = 0;
+	return w;
+}
+
+olympus_reader olympus_reader_init(const uint8_t *data, size_t len, uint8_t *arena, size_t arena_capacity) {
+	olympus_reader r;
+	r.data = data;
+	r.len = len;
+	r.pos = 0;
+	r.arena = arena;
+	r.arena_capacity = arena_capacity;
+	r.arena_len = 0;
+	return r;
+}
+
+olympus_string olympus_string_from(const char *value) {
+	olympus_string string;
+	string.data = value;
+	string.len = (uint32_t)strlen(value);
+	return string;
+}
+
+/* Integers are big-endian. */
+static olympus_result olympus_write_be(olympus_writer *w, uint64_t value, size_t size) {
+	if (w->capacity - w->len < size) {
+		return OLYMPUS_ERR_BUFFER_FULL;
+	}
+
+	for (size_t idx = 0; idx < size; idx++) {
+		w->data[w->len + idx] = (uint8_t)(value >> (8 * (size - idx - 1)));
+	}
+	w->len += size;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_write_u8(olympus_writer *w, uint8_t value) {
+	return olympus_write_be(w, value, 1);
+}
+
+olympus_result olympus_write_u16(olympus_writer *w, uint16_t value) {
+	return olympus_write_be(w, value, 2);
+}
+
+olympus_result olympus_write_u32(olympus_writer *w, uint32_t value) {
+	return olympus_write_be(w, value, 4);
+}
+
+olympus_result olympus_write_u64(olympus_writer *w, uint64_t value) {
+	return olympus_write_be(w, value, 8);
+}
+
+olympus_result olympus_write_i8(olympus_writer *w, int8_t value) {
+	return olympus_write_be(w, (uint8_t)value, 1);
+}
+
+olympus_result olympus_write_i16(olympus_writer *w, int16_t value) {
+	return olympus_write_be(w, (uint16_t)value, 2);
+}
+
+olympus_result olympus_write_i32(olympus_writer *w, int32_t value) {
+	return olympus_write_be(w, (uint32_t)value, 4);
+}
+
+olympus_result olympus_write_i64(olympus_writer *w, int64_t value) {
+	return olympus_write_be(w, (uint64_t)value, 8);
+}
+
+/* LEB128, 7 bits per byte starting with the least significant ones. */
+olympus_result olympus_write_vu64(olympus_writer *w, uint64_t value) {
+	while (value >= 0x80) {
+		OLYMPUS_TRY(olympus_write_u8(w, (uint8_t)((value & 0x7f) | 0x80)));
+		value >>= 7;
+	}
+	return olympus_write_u8(w, (uint8_t)value);
+}
+
+olympus_result olympus_write_vu8(olympus_writer *w, uint8_t value) {
+	return olympus_write_vu64(w, value);
+}
+
+olympus_result olympus_write_vu16(olympus_writer *w, uint16_t value) {
+	return olympus_write_vu64(w, value);
+}
+
+olympus_result olympus_write_vu32(olympus_writer *w, uint32_t value) {
+	return olympus_write_vu64(w, value);
+}
+
+/* Zigzag encoded so small negative numbers stay short, then written like the unsigned varints. */
+static olympus_result olympus_write_zigzag(olympus_writer *w, int64_t value, unsigned bits) {
+	uint64_t zigzag = ((uint64_t)value << 1) ^ (value < 0 ? UINT64_MAX : 0);
+	if (bits < 64) {
+		zigzag &= ((uint64_t)1 << bits) - 1;
+	}
+	return olympus_write_vu64(w, zigzag);
+}
+
+olympus_result olympus_write_vi8(olympus_writer *w, int8_t value) {
+	return olympus_write_zigzag(w, value, 8);
+}
+
+olympus_result olympus_write_vi16(olympus_writer *w, int16_t value) {
+	return olympus_write_zigzag(w, value, 16);
+}
+
+olympus_result olympus_write_vi32(olympus_writer *w, int32_t value) {
+	return olympus_write_zigzag(w, value, 32);
+}
+
+olympus_result olympus_write_vi64(olympus_writer *w, int64_t value) {
+	return olympus_write_zigzag(w, value, 64);
+}
+
+olympus_result olympus_write_string(olympus_writer *w, olympus_string value) {
+	OLYMPUS_TRY(olympus_write_u32(w, value.len));
+	if (w->capacity - w->len < value.len) {
+		return OLYMPUS_ERR_BUFFER_FULL;
+	}
+
+	if (value.len > 0) {
+		memcpy(w->data + w->len, value.data, value.len);
+	}
+	w->len += value.len;
+	return OLYMPUS_OK;
+}
+
+static olympus_result olympus_read_be(olympus_reader *r, size_t size, uint64_t *out) {
+	if (r->len - r->pos < size) {
+		return OLYMPUS_ERR_UNEXPECTED_END;
+	}
+
+	uint64_t value = 0;
+	for (size_t idx = 0; idx < size; idx++) {
+		value = (value << 8) | r->data[r->pos + idx];
+	}
+	r->pos += size;
+	*out = value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_u8(olympus_reader *r, uint8_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 1, &value));
+	*out = (uint8_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_u16(olympus_reader *r, uint16_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 2, &value));
+	*out = (uint16_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_u32(olympus_reader *r, uint32_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 4, &value));
+	*out = (uint32_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_u64(olympus_reader *r, uint64_t *out) {
+	return olympus_read_be(r, 8, out);
+}
+
+olympus_result olympus_read_i8(olympus_reader *r, int8_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 1, &value));
+	*out = (int8_t)(uint8_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_i16(olympus_reader *r, int16_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 2, &value));
+	*out = (int16_t)(uint16_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_i32(olympus_reader *r, int32_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 4, &value));
+	*out = (int32_t)(uint32_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_i64(olympus_reader *r, int64_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_be(r, 8, &value));
+	*out = (int64_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vu64(olympus_reader *r, uint64_t *out) {
+	uint64_t value = 0;
+	for (unsigned shift = 0; shift < 64; shift += 7) {
+		uint8_t byte;
+		OLYMPUS_TRY(olympus_read_u8(r, &byte));
+
+		value |= (uint64_t)(byte & 0x7f) << shift;
+		if ((byte & 0x80) == 0) {
+			*out = value;
+			return OLYMPUS_OK;
+		}
+	}
+	return OLYMPUS_ERR_INVALID_DATA;
+}
+
+olympus_result olympus_read_vu8(olympus_reader *r, uint8_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_vu64(r, &value));
+	*out = (uint8_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vu16(olympus_reader *r, uint16_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_vu64(r, &value));
+	*out = (uint16_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vu32(olympus_reader *r, uint32_t *out) {
+	uint64_t value;
+	OLYMPUS_TRY(olympus_read_vu64(r, &value));
+	*out = (uint32_t)value;
+	return OLYMPUS_OK;
+}
+
+static olympus_result olympus_read_zigzag(olympus_reader *r, unsigned bits, int64_t *out) {
+	uint64_t zigzag;
+	OLYMPUS_TRY(olympus_read_vu64(r, &zigzag));
+	if (bits < 64) {
+		zigzag &= ((uint64_t)1 << bits) - 1;
+	}
+
+	*out = (int64_t)(zigzag >> 1) ^ -(int64_t)(zigzag & 1);
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vi8(olympus_reader *r, int8_t *out) {
+	int64_t value;
+	OLYMPUS_TRY(olympus_read_zigzag(r, 8, &value));
+	*out = (int8_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vi16(olympus_reader *r, int16_t *out) {
+	int64_t value;
+	OLYMPUS_TRY(olympus_read_zigzag(r, 16, &value));
+	*out = (int16_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vi32(olympus_reader *r, int32_t *out) {
+	int64_t value;
+	OLYMPUS_TRY(olympus_read_zigzag(r, 32, &value));
+	*out = (int32_t)value;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_read_vi64(olympus_reader *r, int64_t *out) {
+	return olympus_read_zigzag(r, 64, out);
+}
+
+olympus_result olympus_read_string(olympus_reader *r, olympus_string *out) {
+	uint32_t len;
+	OLYMPUS_TRY(olympus_read_u32(r, &len));
+	if (r->len - r->pos < len) {
+		return OLYMPUS_ERR_UNEXPECTED_END;
+	}
+
+	out->data = (const char *)(r->data + r->pos);
+	out->len = len;
+	r->pos += len;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_arena_alloc(olympus_reader *r, size_t item_size, uint32_t len, void **out) {
+	size_t start = (r->arena_len + OLYMPUS_ARENA_ALIGN - 1) / OLYMPUS_ARENA_ALIGN * OLYMPUS_ARENA_ALIGN;
+	if (start > r->arena_capacity || (item_size > 0 && len > (r->arena_capacity - start) / item_size)) {
+		return OLYMPUS_ERR_ARENA_FULL;
+	}
+
+	*out = r->arena + start;
+	r->arena_len = start + item_size * len;
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_begin_request(olympus_writer *w, uint64_t hash, size_t *start) {
+	*start = w->len;
+	/* requests are never compressed, the length is filled in by olympus_end_request */
+	OLYMPUS_TRY(olympus_write_u8(w, 0));
+	OLYMPUS_TRY(olympus_write_u32(w, 0));
+	return olympus_write_u64(w, hash);
+}
+
+olympus_result olympus_end_request(olympus_writer *w, size_t start) {
+	size_t payload_len = w->len - start - 5;
+	if (payload_len > OLYMPUS_MAX_PACKET_SIZE) {
+		return OLYMPUS_ERR_INVALID_DATA;
+	}
+
+	olympus_writer header = olympus_writer_init(w->data + start + 1, 4);
+	return olympus_write_u32(&header, (uint32_t)payload_len);
+}
+
+/* Decompresses a raw LZ4 block, which is how large frames are compressed. */
+static olympus_result olympus_lz4_decompress(
+	const uint8_t *input,
+	size_t input_len,
+	uint8_t *output,
+	size_t output_len
+) {
+	size_t input_idx = 0;
+	size_t output_idx = 0;
+
+	while (input_idx < input_len) {
+		uint8_t token = input[input_idx++];
+
+		size_t literal_len = token >> 4;
+		if (literal_len == 15) {
+			uint8_t byte;
+			do {
+				if (input_idx >= input_len) {
+					return OLYMPUS_ERR_INVALID_DATA;
+				}
+				byte = input[input_idx++];
+				literal_len += byte;
+			} while (byte == 255);
+		}
+		if (literal_len > input_len - input_idx || literal_len > output_len - output_idx) {
+			return OLYMPUS_ERR_INVALID_DATA;
+		}
+		memcpy(output + output_idx, input + input_idx, literal_len);
+		input_idx += literal_len;
+		output_idx += literal_len;
+
+		/* the last sequence only has literals */
+		if (input_idx >= input_len) {
+			break;
+		}
+		if (input_len - input_idx < 2) {
+			return OLYMPUS_ERR_INVALID_DATA;
+		}
+
+		size_t offset = (size_t)input[input_idx] | ((size_t)input[input_idx + 1] << 8);
+		input_idx += 2;
+		size_t match_len = token & 0x0f;
+		if (match_len == 15) {
+			uint8_t byte;
+			do {
+				if (input_idx >= input_len) {
+					return OLYMPUS_ERR_INVALID_DATA;
+				}
+				byte = input[input_idx++];
+				match_len += byte;
+			} while (byte == 255);
+		}
+		match_len += 4;
+		if (offset == 0 || offset > output_idx || match_len > output_len - output_idx) {
+			return OLYMPUS_ERR_INVALID_DATA;
+		}
+
+		/* matches may overlap with the bytes they produce, so they have to be copied one at a time */
+		for (size_t idx = 0; idx < match_len; idx++) {
+			output[output_idx] = output[output_idx - offset];
+			output_idx++;
+		}
+	}
+
+	return output_idx == output_len ? OLYMPUS_OK : OLYMPUS_ERR_INVALID_DATA;
+}
+
+olympus_result olympus_read_frame(
+	const uint8_t *data,
+	size_t len,
+	uint8_t *scratch,
+	size_t scratch_capacity,
+	olympus_frame *out
+) {
+	olympus_reader header = olympus_reader_init(data, len, NULL, 0);
+	uint8_t compressed;
+	uint32_t data_len;
+	uint32_t decompressed_len = 0;
+	OLYMPUS_TRY(olympus_read_u8(&header, &compressed));
+	OLYMPUS_TRY(olympus_read_u32(&header, &data_len));
+	if (compressed) {
+		OLYMPUS_TRY(olympus_read_u32(&header, &decompressed_len));
+	}
+	if (data_len > OLYMPUS_MAX_PACKET_SIZE || decompressed_len > OLYMPUS_MAX_PACKET_SIZE) {
+		return OLYMPUS_ERR_INVALID_DATA;
+	}
+	if (len - header.pos < data_len) {
+		return OLYMPUS_ERR_UNEXPECTED_END;
+	}
+
+	const uint8_t *payload = data + header.pos;
+	size_t payload_len = data_len;
+	if (compressed) {
+		if (decompressed_len > scratch_capacity) {
+			return OLYMPUS_ERR_BUFFER_FULL;
+		}
+		OLYMPUS_TRY(olympus_lz4_decompress(payload, payload_len, scratch, decompressed_len));
+		payload = scratch;
+		payload_len = decompressed_len;
+	}
+
+	olympus_reader reader = olympus_reader_init(payload, payload_len, NULL, 0);
+	if (olympus_read_u64(&reader, &out->hash) != OLYMPUS_OK) {
+		return OLYMPUS_ERR_INVALID_DATA;
+	}
+	out->payload = payload + 8;
+	out->payload_len = payload_len - 8;
+	out->frame_len = header.pos + data_len;
+	return OLYMPUS_OK;
+}
+
+#endif /* OLYMPUS_RUNTIME_IMPLEMENTATION */
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_array_u8(olympus_writer *w, const olympus_array_u8 *value);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_array_u8(olympus_reader *r, olympus_array_u8 *out);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_option_User(olympus_writer *w, const olympus_option_User *value);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_option_User(olympus_reader *r, olympus_option_User *out);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_option_string(olympus_writer *w, const olympus_option_string *value);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_option_string(olympus_reader *r, olympus_option_string *out);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_array_option_string(olympus_writer *w, const olympus_array_option_string *value);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_array_option_string(olympus_reader *r, olympus_array_option_string *out);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_option_option_string(olympus_writer *w, const olympus_option_option_string *value);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_option_option_string(olympus_reader *r, olympus_option_option_string *out);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_array_File(olympus_writer *w, const olympus_array_File *value);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_array_File(olympus_reader *r, olympus_array_File *out);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_ref_Directory(olympus_writer *w, Directory *const *value);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_ref_Directory(olympus_reader *r, Directory **out);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_option_ref_Directory(olympus_writer *w, const olympus_option_ref_Directory *value);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_option_ref_Directory(olympus_reader *r, olympus_option_ref_Directory *out);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_option_Action(olympus_writer *w, const olympus_option_Action *value);
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_option_Action(olympus_reader *r, olympus_option_Action *out);
+
+olympus_result olympus_encode_Action(olympus_writer *w, const Action *value) {
+	return olympus_write_u16(w, *value);
+}
+
+olympus_result olympus_decode_Action(olympus_reader *r, Action *out) {
+	uint16_t tag;
+	OLYMPUS_TRY(olympus_read_u16(r, &tag));
+	switch (tag) {
+	case ACTION_DELETE:
+	case ACTION_SECURE_DELETE:
+	case ACTION_ENCRYPT:
+		*out = tag;
+		return OLYMPUS_OK;
+	default:
+		return OLYMPUS_ERR_INVALID_DATA;
+	}
+}
+
+olympus_result olympus_encode_Numbers(olympus_writer *w, const Numbers *value) {
+	OLYMPUS_TRY(olympus_write_u8(w, value->u8));
+	OLYMPUS_TRY(olympus_write_i8(w, value->i8));
+	OLYMPUS_TRY(olympus_write_u16(w, value->u16));
+	OLYMPUS_TRY(olympus_write_i16(w, value->i16));
+	OLYMPUS_TRY(olympus_write_u32(w, value->u32));
+	OLYMPUS_TRY(olympus_write_i32(w, value->i32));
+	OLYMPUS_TRY(olympus_write_u64(w, value->u64));
+	OLYMPUS_TRY(olympus_write_i64(w, value->i64));
+	OLYMPUS_TRY(olympus_write_vu8(w, value->vu8));
+	OLYMPUS_TRY(olympus_write_vi8(w, value->vi8));
+	OLYMPUS_TRY(olympus_write_vu16(w, value->vu16));
+	OLYMPUS_TRY(olympus_write_vi16(w, value->vi16));
+	OLYMPUS_TRY(olympus_write_vu32(w, value->vu32));
+	OLYMPUS_TRY(olympus_write_vi32(w, value->vi32));
+	OLYMPUS_TRY(olympus_write_vi64(w, value->vi64));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_decode_Numbers(olympus_reader *r, Numbers *out) {
+	OLYMPUS_TRY(olympus_read_u8(r, &out->u8));
+	OLYMPUS_TRY(olympus_read_i8(r, &out->i8));
+	OLYMPUS_TRY(olympus_read_u16(r, &out->u16));
+	OLYMPUS_TRY(olympus_read_i16(r, &out->i16));
+	OLYMPUS_TRY(olympus_read_u32(r, &out->u32));
+	OLYMPUS_TRY(olympus_read_i32(r, &out->i32));
+	OLYMPUS_TRY(olympus_read_u64(r, &out->u64));
+	OLYMPUS_TRY(olympus_read_i64(r, &out->i64));
+	OLYMPUS_TRY(olympus_read_vu8(r, &out->vu8));
+	OLYMPUS_TRY(olympus_read_vi8(r, &out->vi8));
+	OLYMPUS_TRY(olympus_read_vu16(r, &out->vu16));
+	OLYMPUS_TRY(olympus_read_vi16(r, &out->vi16));
+	OLYMPUS_TRY(olympus_read_vu32(r, &out->vu32));
+	OLYMPUS_TRY(olympus_read_vi32(r, &out->vi32));
+	OLYMPUS_TRY(olympus_read_vi64(r, &out->vi64));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_encode_File(olympus_writer *w, const File *value) {
+	OLYMPUS_TRY(olympus_write_string(w, value->path));
+	OLYMPUS_TRY(olympus_write_vu64(w, value->size));
+	OLYMPUS_TRY(olympus_encode_array_u8(w, &value->content));
+	OLYMPUS_TRY(olympus_encode_option_User(w, &value->owner));
+	OLYMPUS_TRY(olympus_encode_array_option_string(w, &value->tags));
+	OLYMPUS_TRY(olympus_encode_option_option_string(w, &value->label));
+	OLYMPUS_TRY(olympus_encode_Action(w, &value->action));
+	OLYMPUS_TRY(olympus_encode_Numbers(w, &value->numbers));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_decode_File(olympus_reader *r, File *out) {
+	OLYMPUS_TRY(olympus_read_string(r, &out->path));
+	OLYMPUS_TRY(olympus_read_vu64(r, &out->size));
+	OLYMPUS_TRY(olympus_decode_array_u8(r, &out->content));
+	OLYMPUS_TRY(olympus_decode_option_User(r, &out->owner));
+	OLYMPUS_TRY(olympus_decode_array_option_string(r, &out->tags));
+	OLYMPUS_TRY(olympus_decode_option_option_string(r, &out->label));
+	OLYMPUS_TRY(olympus_decode_Action(r, &out->action));
+	OLYMPUS_TRY(olympus_decode_Numbers(r, &out->numbers));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_encode_Directory(olympus_writer *w, const Directory *value) {
+	OLYMPUS_TRY(olympus_write_string(w, value->name));
+	OLYMPUS_TRY(olympus_encode_array_File(w, &value->files));
+	OLYMPUS_TRY(olympus_encode_option_ref_Directory(w, &value->parent));
+	OLYMPUS_TRY(olympus_write_string(w, value->type));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_decode_Directory(olympus_reader *r, Directory *out) {
+	OLYMPUS_TRY(olympus_read_string(r, &out->name));
+	OLYMPUS_TRY(olympus_decode_array_File(r, &out->files));
+	OLYMPUS_TRY(olympus_decode_option_ref_Directory(r, &out->parent));
+	OLYMPUS_TRY(olympus_read_string(r, &out->type));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_encode_User(olympus_writer *w, const User *value) {
+	OLYMPUS_TRY(olympus_write_string(w, value->name));
+	OLYMPUS_TRY(olympus_write_vi64(w, value->id));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_decode_User(olympus_reader *r, User *out) {
+	OLYMPUS_TRY(olympus_read_string(r, &out->name));
+	OLYMPUS_TRY(olympus_read_vi64(r, &out->id));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_encode_GetFileParams(olympus_writer *w, const GetFileParams *value) {
+	OLYMPUS_TRY(olympus_write_string(w, value->path));
+	OLYMPUS_TRY(olympus_encode_option_Action(w, &value->after_action));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_decode_GetFileParams(olympus_reader *r, GetFileParams *out) {
+	OLYMPUS_TRY(olympus_read_string(r, &out->path));
+	OLYMPUS_TRY(olympus_decode_option_Action(r, &out->after_action));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_encode_ListDirectoryParams(olympus_writer *w, const ListDirectoryParams *value) {
+	OLYMPUS_TRY(olympus_encode_Directory(w, &value->directory));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_decode_ListDirectoryParams(olympus_reader *r, ListDirectoryParams *out) {
+	OLYMPUS_TRY(olympus_decode_Directory(r, &out->directory));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_encode_DeleteFileParams(olympus_writer *w, const DeleteFileParams *value) {
+	OLYMPUS_TRY(olympus_write_string(w, value->path));
+	return OLYMPUS_OK;
+}
+
+olympus_result olympus_decode_DeleteFileParams(olympus_reader *r, DeleteFileParams *out) {
+	OLYMPUS_TRY(olympus_read_string(r, &out->path));
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_array_u8(olympus_writer *w, const olympus_array_u8 *value) {
+	OLYMPUS_TRY(olympus_write_u32(w, value->len));
+	for (uint32_t idx = 0; idx < value->len; idx++) {
+		OLYMPUS_TRY(olympus_write_u8(w, value->items[idx]));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_array_u8(olympus_reader *r, olympus_array_u8 *out) {
+	void *items;
+	OLYMPUS_TRY(olympus_read_u32(r, &out->len));
+	OLYMPUS_TRY(olympus_arena_alloc(r, sizeof(*out->items), out->len, &items));
+	out->items = (uint8_t *)items;
+	for (uint32_t idx = 0; idx < out->len; idx++) {
+		OLYMPUS_TRY(olympus_read_u8(r, &out->items[idx]));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_option_User(olympus_writer *w, const olympus_option_User *value) {
+	OLYMPUS_TRY(olympus_write_u8(w, value->present ? 1 : 0));
+	if (value->present) {
+		OLYMPUS_TRY(olympus_encode_User(w, &value->value));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_option_User(olympus_reader *r, olympus_option_User *out) {
+	uint8_t present;
+	OLYMPUS_TRY(olympus_read_u8(r, &present));
+	memset(out, 0, sizeof(*out));
+	out->present = present != 0;
+	if (out->present) {
+		OLYMPUS_TRY(olympus_decode_User(r, &out->value));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_option_string(olympus_writer *w, const olympus_option_string *value) {
+	OLYMPUS_TRY(olympus_write_u8(w, value->present ? 1 : 0));
+	if (value->present) {
+		OLYMPUS_TRY(olympus_write_string(w, value->value));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_option_string(olympus_reader *r, olympus_option_string *out) {
+	uint8_t present;
+	OLYMPUS_TRY(olympus_read_u8(r, &present));
+	memset(out, 0, sizeof(*out));
+	out->present = present != 0;
+	if (out->present) {
+		OLYMPUS_TRY(olympus_read_string(r, &out->value));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_array_option_string(olympus_writer *w, const olympus_array_option_string *value) {
+	OLYMPUS_TRY(olympus_write_u32(w, value->len));
+	for (uint32_t idx = 0; idx < value->len; idx++) {
+		OLYMPUS_TRY(olympus_encode_option_string(w, &value->items[idx]));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_array_option_string(olympus_reader *r, olympus_array_option_string *out) {
+	void *items;
+	OLYMPUS_TRY(olympus_read_u32(r, &out->len));
+	OLYMPUS_TRY(olympus_arena_alloc(r, sizeof(*out->items), out->len, &items));
+	out->items = (olympus_option_string *)items;
+	for (uint32_t idx = 0; idx < out->len; idx++) {
+		OLYMPUS_TRY(olympus_decode_option_string(r, &out->items[idx]));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_option_option_string(olympus_writer *w, const olympus_option_option_string *value) {
+	OLYMPUS_TRY(olympus_write_u8(w, value->present ? 1 : 0));
+	if (value->present) {
+		OLYMPUS_TRY(olympus_encode_option_string(w, &value->value));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_option_option_string(olympus_reader *r, olympus_option_option_string *out) {
+	uint8_t present;
+	OLYMPUS_TRY(olympus_read_u8(r, &present));
+	memset(out, 0, sizeof(*out));
+	out->present = present != 0;
+	if (out->present) {
+		OLYMPUS_TRY(olympus_decode_option_string(r, &out->value));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_array_File(olympus_writer *w, const olympus_array_File *value) {
+	OLYMPUS_TRY(olympus_write_u32(w, value->len));
+	for (uint32_t idx = 0; idx < value->len; idx++) {
+		OLYMPUS_TRY(olympus_encode_File(w, &value->items[idx]));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_array_File(olympus_reader *r, olympus_array_File *out) {
+	void *items;
+	OLYMPUS_TRY(olympus_read_u32(r, &out->len));
+	OLYMPUS_TRY(olympus_arena_alloc(r, sizeof(*out->items), out->len, &items));
+	out->items = (File *)items;
+	for (uint32_t idx = 0; idx < out->len; idx++) {
+		OLYMPUS_TRY(olympus_decode_File(r, &out->items[idx]));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_ref_Directory(olympus_writer *w, Directory *const *value) {
+	OLYMPUS_TRY(olympus_encode_Directory(w, *value));
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_ref_Directory(olympus_reader *r, Directory **out) {
+	void *item;
+	OLYMPUS_TRY(olympus_arena_alloc(r, sizeof(**out), 1, &item));
+	*out = (Directory *)item;
+	OLYMPUS_TRY(olympus_decode_Directory(r, *out));
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_option_ref_Directory(olympus_writer *w, const olympus_option_ref_Directory *value) {
+	OLYMPUS_TRY(olympus_write_u8(w, value->present ? 1 : 0));
+	if (value->present) {
+		OLYMPUS_TRY(olympus_encode_ref_Directory(w, &value->value));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_option_ref_Directory(olympus_reader *r, olympus_option_ref_Directory *out) {
+	uint8_t present;
+	OLYMPUS_TRY(olympus_read_u8(r, &present));
+	memset(out, 0, sizeof(*out));
+	out->present = present != 0;
+	if (out->present) {
+		OLYMPUS_TRY(olympus_decode_ref_Directory(r, &out->value));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_encode_option_Action(olympus_writer *w, const olympus_option_Action *value) {
+	OLYMPUS_TRY(olympus_write_u8(w, value->present ? 1 : 0));
+	if (value->present) {
+		OLYMPUS_TRY(olympus_encode_Action(w, &value->value));
+	}
+	return OLYMPUS_OK;
+}
+
+static OLYMPUS_MAYBE_UNUSED olympus_result olympus_decode_option_Action(olympus_reader *r, olympus_option_Action *out) {
+	uint8_t present;
+	OLYMPUS_TRY(olympus_read_u8(r, &present));
+	memset(out, 0, sizeof(*out));
+	out->present = present != 0;
+	if (out->present) {
+		OLYMPUS_TRY(olympus_decode_Action(r, &out->value));
+	}
+	return OLYMPUS_OK;
+}
+
+#endif /* OLYMPUS_IMPLEMENTATION */
+
+#define OLYMPUS_PROC_GET_SERVER_VERSION UINT64_C(0x98b640e5f47b3500)
+/* Writes the request frame of `GetServerVersion`. */
+olympus_result olympus_request_get_server_version(olympus_writer *w);
+/* Decodes the payload of a response frame of `GetServerVersion`, see olympus_read_frame. */
+olympus_result olympus_response_get_server_version(olympus_reader *r, int8_t *out);
+
+#define OLYMPUS_PROC_GET_FILE UINT64_C(0xd99937932879aae9)
+/* Writes the request frame of `GetFile`. */
+olympus_result olympus_request_get_file(olympus_writer *w, const GetFileParams *params);
+/* Decodes the payload of a response frame of `GetFile`, see olympus_read_frame. */
+olympus_result olympus_response_get_file(olympus_reader *r, File *out);
+
+#define OLYMPUS_PROC_LIST_DIRECTORY UINT64_C(0xae52e7605751b35c)
+/* Writes the request frame of `ListDirectory`. */
+olympus_result olympus_request_list_directory(olympus_writer *w, const ListDirectoryParams *params);
+/* Decodes the payload of a response frame of `ListDirectory`, see olympus_read_frame. */
+olympus_result olympus_response_list_directory(olympus_reader *r, olympus_array_File *out);
+
+#define OLYMPUS_PROC_DELETE_FILE UINT64_C(0x4e55371a5542694c)
+/* Writes the request frame of `DeleteFile`. */
+olympus_result olympus_request_delete_file(olympus_writer *w, const DeleteFileParams *params);
+
+#ifdef OLYMPUS_IMPLEMENTATION
+
+olympus_result olympus_request_get_server_version(olympus_writer *w) {
+	size_t start;
+	OLYMPUS_TRY(olympus_begin_request(w, OLYMPUS_PROC_GET_SERVER_VERSION, &start));
+	return olympus_end_request(w, start);
+}
+
+olympus_result olympus_response_get_server_version(olympus_reader *r, int8_t *out) {
+	return olympus_read_i8(r, out);
+}
+
+olympus_result olympus_request_get_file(olympus_writer *w, const GetFileParams *params) {
+	size_t start;
+	OLYMPUS_TRY(olympus_begin_request(w, OLYMPUS_PROC_GET_FILE, &start));
+	OLYMPUS_TRY(olympus_encode_GetFileParams(w, params));
+	return olympus_end_request(w, start);
+}
+
+olympus_result olympus_response_get_file(olympus_reader *r, File *out) {
+	return olympus_decode_File(r, out);
+}
+
+olympus_result olympus_request_list_directory(olympus_writer *w, const ListDirectoryParams *params) {
+	size_t start;
+	OLYMPUS_TRY(olympus_begin_request(w, OLYMPUS_PROC_LIST_DIRECTORY, &start));
+	OLYMPUS_TRY(olympus_encode_ListDirectoryParams(w, params));
+	return olympus_end_request(w, start);
+}
+
+olympus_result olympus_response_list_directory(olympus_reader *r, olympus_array_File *out) {
+	return olympus_decode_array_File(r, out);
+}
+
+olympus_result olympus_request_delete_file(olympus_writer *w, const DeleteFileParams *params) {
+	size_t start;
+	OLYMPUS_TRY(olympus_begin_request(w, OLYMPUS_PROC_DELETE_FILE, &start));
+	OLYMPUS_TRY(olympus_encode_DeleteFileParams(w, params));
+	return olympus_end_request(w, start);
+}
+
+#endif /* OLYMPUS_IMPLEMENTATION */
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,521 @@
+# /-- THIS FILE WAS AUTOMATICALLY GENERATED BY OLYMPUS --\
+# Runtime for the olympus wire format, every generated type and the client below build on it.
+#
+#   connection = await OlympusConnection.connect("127.0.0.1", 9999)
+#   client = ClientRpc(connection)
+
+from __future__ import annotations
+
+import asyncio
+import struct
+from collections import deque
+from dataclasses import dataclass
+from enum import IntEnum
+from typing import Callable, Deque, Dict, Generic, List, Optional, TypeVar
+
+T = TypeVar("T")
+
+_U8 = struct.Struct(">B")
+_I8 = struct.Struct(">b")
+_U16 = struct.Struct(">H")
+_I16 = struct.Struct(">h")
+_U32 = struct.Struct(">I")
+_I32 = struct.Struct(">i")
+_U64 = struct.Struct(">Q")
+_I64 = struct.Struct(">q")
+
+MAX_PACKET_SIZE = 8 * 1024 * 1024
+
+
+class OlympusError(Exception):
+    pass
+
+
+@dataclass(frozen=True)
+class Some(Generic[T]):
+    """Holds the value of an option nested in another option, `Optional[Optional[T]]` is the same as `Optional[T]`."""
+
+    value: T
+
+
+class Writer:
+    """Serializes values in the olympus wire format. Integers are big-endian."""
+
+    def __init__(self) -> None:
+        self._buffer = bytearray()
+
+    def finish(self) -> bytes:
+        return bytes(self._buffer)
+
+    def bytes(self, value: bytes) -> None:
+        self._buffer += value
+
+    def u8(self, value: int) -> None:
+        self._buffer += _U8.pack(value)
+
+    def i8(self, value: int) -> None:
+        self._buffer += _I8.pack(value)
+
+    def u16(self, value: int) -> None:
+        self._buffer += _U16.pack(value)
+
+    def i16(self, value: int) -> None:
+        self._buffer += _I16.pack(value)
+
+    def u32(self, value: int) -> None:
+        self._buffer += _U32.pack(value)
+
+    def i32(self, value: int) -> None:
+        self._buffer += _I32.pack(value)
+
+    def u64(self, value: int) -> None:
+        self._buffer += _U64.pack(value)
+
+    def i64(self, value: int) -> None:
+        self._buffer += _I64.pack(value)
+
+    def var_uint(self, value: int) -> None:
+        """LEB128, 7 bits per byte starting with the least significant ones."""
+        while value >= 0x80:
+            self._buffer.append((value & 0x7F) | 0x80)
+            value >>= 7
+        self._buffer.append(value)
+
+    def var_int(self, value: int, bits: int) -> None:
+        """Zigzag encoded so small negative numbers stay short, then written like `var_uint`."""
+        self.var_uint(((value << 1) ^ (value >> (bits - 1))) & ((1 << bits) - 1))
+
+    def string(self, value: str) -> None:
+        data = value.encode("utf-8")
+        self.u32(len(data))
+        self.bytes(data)
+
+    def array(self, value: List[T], write: Callable[[T], None]) -> None:
+        self.u32(len(value))
+        for item in value:
+            write(item)
+
+    def option(self, value: Optional[T], write: Callable[[T], None]) -> None:
+        if value is None:
+            self.u8(0)
+        else:
+            self.u8(1)
+            write(value)
+
+
+class Reader:
+    """Deserializes values in the olympus wire format, the counterpart to `Writer`."""
+
+    def __init__(self, data: bytes) -> None:
+        self._data = memoryview(data)
+        self._offset = 0
+
+    def _take(self, size: int) -> memoryview:
+        if self._offset + size > len(self._data):
+            raise OlympusError("Unexpected end of data")
+        chunk = self._data[self._offset : self._offset + size]
+        self._offset += size
+        return chunk
+
+    def u8(self) -> int:
+        return _U8.unpack(self._take(1))[0]
+
+    def i8(self) -> int:
+        return _I8.unpack(self._take(1))[0]
+
+    def u16(self) -> int:
+        return _U16.unpack(self._take(2))[0]
+
+    def i16(self) -> int:
+        return _I16.unpack(self._take(2))[0]
+
+    def u32(self) -> int:
+        return _U32.unpack(self._take(4))[0]
+
+    def i32(self) -> int:
+        return _I32.unpack(self._take(4))[0]
+
+    def u64(self) -> int:
+        return _U64.unpack(self._take(8))[0]
+
+    def i64(self) -> int:
+        return _I64.unpack(self._take(8))[0]
+
+    def var_uint(self) -> int:
+        value = 0
+        shift = 0
+        while True:
+            byte = self.u8()
+            value |= (byte & 0x7F) << shift
+            shift += 7
+            if byte & 0x80 == 0:
+                return value
+
+    def var_int(self, bits: int) -> int:
+        zigzag = self.var_uint() & ((1 << bits) - 1)
+        return (zigzag >> 1) ^ -(zigzag & 1)
+
+    def string(self) -> str:
+        length = self.u32()
+        return str(self._take(length), "utf-8")
+
+    def array(self, read: Callable[[], T]) -> List[T]:
+        return [read() for _ in range(self.u32())]
+
+    def option(self, read: Callable[[], T]) -> Optional[T]:
+        return None if self.u8() == 0 else read()
+
+
+def fnv1a(value: str) -> int:
+    """64 bit FNV-1a, procedures are identified by the hash of their name."""
+    hash = 0xCBF29CE484222325
+    for byte in value.encode("utf-8"):
+        hash ^= byte
+        hash = (hash * 0x100000001B3) & 0xFFFFFFFFFFFFFFFF
+    return hash
+
+
+def _lz4_decompress(data: bytes, decompressed_length: int) -> bytes:
+    """Decompresses a raw LZ4 block, which is how large frames are compressed."""
+    output = bytearray()
+    idx = 0
+
+    def read_length(length: int) -> int:
+        nonlocal idx
+        if length == 15:
+            while True:
+                byte = data[idx]
+                idx += 1
+                length += byte
+                if byte != 255:
+                    break
+        return length
+
+    try:
+        while idx < len(data):
+            token = data[idx]
+            idx += 1
+
+            literal_length = read_length(token >> 4)
+            output += data[idx : idx + literal_length]
+            idx += literal_length
+
+            # the last sequence only has literals
+            if idx >= len(data):
+                break
+
+            offset = data[idx] | (data[idx + 1] << 8)
+            idx += 2
+            match_length = read_length(token & 0x0F) + 4
+            if offset == 0 or offset > len(output):
+                raise OlympusError("Invalid LZ4 block")
+
+            # matches may overlap with the bytes they produce, so they have to be copied one at a time
+            start = len(output) - offset
+            for match_idx in range(match_length):
+                output.append(output[start + match_idx])
+    except IndexError:
+        raise OlympusError("Invalid LZ4 block") from None
+
+    if len(output) != decompressed_length:
+        raise OlympusError("Invalid LZ4 block")
+    return bytes(output)
+
+
+class OlympusConnection:
+    """Frames requests and matches responses to calls.
+
+    Every frame starts with a compressed flag and the data length, compressed frames also carry the decompressed
+    length. Requests are always sent uncompressed.
+    """
+
+    def __init__(self, reader: asyncio.StreamReader, writer: asyncio.StreamWriter) -> None:
+        self._reader = reader
+        self._writer = writer
+        self._pending: Dict[int, Deque[asyncio.Future[bytes]]] = {}
+        self._read_task = asyncio.get_running_loop().create_task(self._read_frames())
+
+    @classmethod
+    async def connect(cls, host: str, port: int) -> OlympusConnection:
+        reader, writer = await asyncio.open_connection(host, port)
+        return cls(reader, writer)
+
+    def send(self, procedure: str, write: Callable[[Writer], None]) -> None:
+        """Queues a request without waiting for a response, `drain` waits until it was written."""
+        payload = Writer()
+        payload.u64(fnv1a(procedure))
+        write(payload)
+        data = payload.finish()
+
+        frame = Writer()
+        frame.u8(0)
+        frame.u32(len(data))
+        frame.bytes(data)
+        self._writer.write(frame.finish())
+
+    async def drain(self) -> None:
+        await self._writer.drain()
+
+    async def call(self, procedure: str, write: Callable[[Writer], None], read: Callable[[Reader], T]) -> T:
+        """Sends a request and returns its decoded response.
+
+        The server answers the requests of a connection in order, so responses are matched to calls in the order they
+        were sent.
+        """
+        future: asyncio.Future[bytes] = asyncio.get_running_loop().create_future()
+        self._pending.setdefault(fnv1a(procedure), deque()).append(future)
+        self.send(procedure, write)
+        await self.drain()
+        return read(Reader(await future))
+
+    async def close(self) -> None:
+        self._writer.close()
+        await self._writer.wait_closed()
+        await asyncio.gather(self._read_task, return_exceptions=True)
+
+    async def _read_frames(self) -> None:
+        error: Exception = OlympusError("Connection closed")
+        try:
+            while True:
+                header = await self._reader.readexactly(5)
+                compressed = header[0] != 0
+                length = _U32.unpack(header[1:])[0]
+                if length > MAX_PACKET_SIZE:
+                    raise OlympusError(f"Packet too big: {length} bytes")
+
+                decompressed_length = _U32.unpack(await self._reader.readexactly(4))[0] if compressed else 0
+                data = await self._reader.readexactly(length)
+                if compressed:
+                    data = _lz4_decompress(data, decompressed_length)
+
+                queue = self._pending.get(_U64.unpack(data[:8])[0])
+                if queue:
+                    future = queue.popleft()
+                    if not future.done():
+                        future.set_result(data[8:])
+        except (asyncio.IncompleteReadError, ConnectionError):
+            pass
+        except OlympusError as err:
+            error = err
+        finally:
+            # every call that is still waiting won't get a response anymore
+            for queue in self._pending.values():
+                for future in queue:
+                    if not future.done():
+                        future.set_exception(error)
+            self._pending.clear()
+
+
+class Action(IntEnum):
+    DELETE = 1
+    SECURE_DELETE = 2
+    ENCRYPT = 300
+
+    def encode(self, w: Writer) -> None:
+        w.u16(self)
+
+    @classmethod
+    def decode(cls, r: Reader) -> Action:
+        tag = r.u16()
+        try:
+            return cls(tag)
+        except ValueError:
+            raise OlympusError(f"Invalid Action tag: {tag}") from None
+
+
+@dataclass
+class Numbers:
+    u8: int
+    i8: int
+    u16: int
+    i16: int
+    u32: int
+    i32: int
+    u64: int
+    i64: int
+    vu8: int
+    vi8: int
+    vu16: int
+    vi16: int
+    vu32: int
+    vi32: int
+    vi64: int
+
+    def encode(self, w: Writer) -> None:
+        w.u8(self.u8)
+        w.i8(self.i8)
+        w.u16(self.u16)
+        w.i16(self.i16)
+        w.u32(self.u32)
+        w.i32(self.i32)
+        w.u64(self.u64)
+        w.i64(self.i64)
+        w.var_uint(self.vu8)
+        w.var_int(self.vi8, 8)
+        w.var_uint(self.vu16)
+        w.var_int(self.vi16, 16)
+        w.var_uint(self.vu32)
+        w.var_int(self.vi32, 32)
+        w.var_int(self.vi64, 64)
+
+    @classmethod
+    def decode(cls, r: Reader) -> Numbers:
+        return cls(
+            u8=r.u8(),
+            i8=r.i8(),
+            u16=r.u16(),
+            i16=r.i16(),
+            u32=r.u32(),
+            i32=r.i32(),
+            u64=r.u64(),
+            i64=r.i64(),
+            vu8=r.var_uint(),
+            vi8=r.var_int(8),
+            vu16=r.var_uint(),
+            vi16=r.var_int(16),
+            vu32=r.var_uint(),
+            vi32=r.var_int(32),
+            vi64=r.var_int(64),
+        )
+
+
+@dataclass
+class File:
+    path: str
+    size: int
+    content: List[int]
+    owner: Optional[User]
+    tags: List[Optional[str]]
+    label: Optional[Some[Optional[str]]]
+    action: Action
+    numbers: Numbers
+
+    def encode(self, w: Writer) -> None:
+        w.string(self.path)
+        w.var_uint(self.size)
+        w.array(self.content, lambda item0: w.u8(item0))
+        w.option(self.owner, lambda item0: item0.encode(w))
+        w.array(self.tags, lambda item0: w.option(item0, lambda item1: w.string(item1)))
+        w.option(self.label, lambda item0: w.option(item0.value, lambda item1: w.string(item1)))
+        self.action.encode(w)
+        self.numbers.encode(w)
+
+    @classmethod
+    def decode(cls, r: Reader) -> File:
+        return cls(
+            path=r.string(),
+            size=r.var_uint(),
+            content=r.array(lambda: r.u8()),
+            owner=r.option(lambda: User.decode(r)),
+            tags=r.array(lambda: r.option(lambda: r.string())),
+            label=r.option(lambda: Some(r.option(lambda: r.string()))),
+            action=Action.decode(r),
+            numbers=Numbers.decode(r),
+        )
+
+
+@dataclass
+class Directory:
+    name: str
+    files: List[File]
+    parent: Optional[Directory]
+    type_: str
+
+    def encode(self, w: Writer) -> None:
+        w.string(self.name)
+        w.array(self.files, lambda item0: item0.encode(w))
+        w.option(self.parent, lambda item0: item0.encode(w))
+        w.string(self.type_)
+
+    @classmethod
+    def decode(cls, r: Reader) -> Directory:
+        return cls(
+            name=r.string(),
+            files=r.array(lambda: File.decode(r)),
+            parent=r.option(lambda: Directory.decode(r)),
+            type_=r.string(),
+        )
+
+
+@dataclass
+class User:
+    name: str
+    id: int
+
+    def encode(self, w: Writer) -> None:
+        w.string(self.name)
+        w.var_int(self.id, 64)
+
+    @classmethod
+    def decode(cls, r: Reader) -> User:
+        return cls(
+            name=r.string(),
+            id=r.var_int(64),
+        )
+
+
+@dataclass
+class GetFileParams:
+    path: str
+    after_action: Optional[Action]
+
+    def encode(self, w: Writer) -> None:
+        w.string(self.path)
+        w.option(self.after_action, lambda item0: item0.encode(w))
+
+    @classmethod
+    def decode(cls, r: Reader) -> GetFileParams:
+        return cls(
+            path=r.string(),
+            after_action=r.option(lambda: Action.decode(r)),
+        )
+
+
+@dataclass
+class ListDirectoryParams:
+    directory: Directory
+
+    def encode(self, w: Writer) -> None:
+        self.directory.encode(w)
+
+    @classmethod
+    def decode(cls, r: Reader) -> ListDirectoryParams:
+        return cls(
+            directory=Directory.decode(r),
+        )
+
+
+@dataclass
+class DeleteFileParams:
+    path: str
+
+    def encode(self, w: Writer) -> None:
+        w.string(self.path)
+
+    @classmethod
+    def decode(cls, r: Reader) -> DeleteFileParams:
+        return cls(
+            path=r.string(),
+        )
+
+
+class ClientRpc:
+    def __init__(self, connection: OlympusConnection) -> None:
+        self.connection = connection
+
+    async def get_server_version(self) -> int:
+        """Calls `GetServerVersion` and waits for its response."""
+        return await self.connection.call("GetServerVersion", lambda w: None, lambda r: r.i8())
+
+    async def get_file(self, params: GetFileParams) -> File:
+        """Calls `GetFile` and waits for its response."""
+        return await self.connection.call("GetFile", params.encode, lambda r: File.decode(r))
+
+    async def list_directory(self, params: ListDirectoryParams) -> List[File]:
+        """Calls `ListDirectory` and waits for its response."""
+        return await self.connection.call("ListDirectory", params.encode, lambda r: r.array(lambda: File.decode(r)))
+
+    async def delete_file(self, params: DeleteFileParams) -> None:
+        """Sends `DeleteFile` without waiting for the server, it doesn't return anything."""
+        self.connection.send("DeleteFile", params.encode)
+        await self.connection.drain()
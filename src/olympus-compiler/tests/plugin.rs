@@ -0,0 +1,94 @@
+//! `olympusc compile --plugin` against a fake `olympus-gen-fake` executable, which saves the request it was sent and
+//! answers with a file before reading it.
+#![cfg(unix)]
+
+use std::{fmt::Write, os::unix::fs::PermissionsExt, path::Path, process::Command};
+
+use serde_json::Value;
+
+/// More than fits into a pipe, in both directions.
+const SIZE: usize = 200_000;
+
+fn write_plugin(dir: &Path) {
+	let script = format!(
+		r#"#!/bin/sh
+# answers before reading the request, like a plugin that streams its output
+printf '{{"files": [{{"path": "nested/big.txt", "contents": "'
+head -c {SIZE} /dev/zero | tr '\0' 'a'
+printf '"}}]}}'
+cat > "$(dirname "$0")/request.json"
+"#
+	);
+
+	let path = dir.join("olympus-gen-fake");
+	std::fs::write(&path, script).unwrap();
+	std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn sends_the_schema_and_writes_the_response() {
+	let dir = tempfile::tempdir().unwrap();
+	let bin = dir.path().join("bin");
+	std::fs::create_dir(&bin).unwrap();
+	write_plugin(&bin);
+
+	let mut schema = String::from("enum Action { Delete->1; }\nproc Act(action->Action) -> Struct0;\n");
+	for idx in 0..SIZE / 100 {
+		writeln!(
+			schema,
+			"struct Struct{idx} {{ name->@string; next->@option[Struct{}]; }}",
+			idx + 1
+		)
+		.unwrap();
+	}
+	writeln!(schema, "struct Struct{} {{ name->@string; }}", SIZE / 100).unwrap();
+	let input = dir.path().join("files.ol");
+	std::fs::write(&input, schema).unwrap();
+
+	let path = std::env::join_paths(
+		std::iter::once(bin.clone()).chain(std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default())),
+	)
+	.unwrap();
+	let out = dir.path().join("out");
+	let status = Command::new(env!("CARGO_BIN_EXE_olympusc"))
+		.current_dir(dir.path())
+		.env("PATH", path)
+		.args(["compile", "files.ol", "out", "--plugin", "fake"])
+		.args(["--plugin-opt", "package=files", "--plugin-opt", "package=models"])
+		.args(["--struct-field-naming-convention", "snake"])
+		.status()
+		.unwrap();
+	assert!(status.success());
+
+	let request: Value = serde_json::from_str(&std::fs::read_to_string(bin.join("request.json")).unwrap()).unwrap();
+	assert_eq!(request["version"], 1);
+	assert_eq!(request["input"], "files.ol");
+	assert_eq!(request["parameters"], serde_json::json!({ "package": "models" }));
+	assert_eq!(request["naming_conventions"]["struct_fields"], "snake");
+	assert_eq!(request["naming_conventions"]["types"], Value::Null);
+
+	let schema = &request["schema"];
+	assert_eq!(
+		schema["enums"][0]["variants"][0],
+		serde_json::json!({ "name": "Delete", "tag": 1 })
+	);
+	assert_eq!(schema["structs"].as_array().unwrap().len(), SIZE / 100 + 1);
+	assert_eq!(
+		schema["structs"][0]["fields"][1]["type"],
+		serde_json::json!({
+			"kind": "option",
+			"item": { "kind": "struct", "index": 1, "name": "Struct1", "indirect": false },
+		})
+	);
+	let proc = &schema["procedures"][0];
+	assert_eq!(proc["name"], "Act");
+	assert_eq!(
+		proc["params"][0]["type"],
+		serde_json::json!({ "kind": "enum", "index": 0, "name": "Action" })
+	);
+	assert_eq!(proc["returns"]["name"], "Struct0");
+
+	let written = std::fs::read_to_string(out.join("nested/big.txt")).unwrap();
+	assert_eq!(written.len(), SIZE);
+	assert!(written.bytes().all(|byte| byte == b'a'));
+}
@@ -1,6 +1,6 @@
-use std::{ops::Range, rc::Rc};
+use std::{ops::Range, sync::Arc};
 
-use olympus_spanned::{CodeSource, OlympusError, Spanned};
+use olympus_spanned::{CodeSource, ErrorCode, OlympusError, Spanned};
 use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Debug, Clone)]
@@ -93,7 +93,7 @@ pub struct LexPoint {
 }
 
 pub struct Lexer {
-	pub source: Rc<CodeSource>,
+	pub source: Arc<CodeSource>,
 	graphemes: Vec<String>,
 	curr_point: LexPoint,
 	pub tokens: Vec<SpannedToken>,
@@ -101,7 +101,7 @@ pub struct Lexer {
 
 impl Lexer {
 	#[must_use]
-	pub fn new(source: Rc<CodeSource>) -> Self {
+	pub fn new(source: Arc<CodeSource>) -> Self {
 		let graphemes = source.src.clone();
 		let graphemes = graphemes.graphemes(true).map(str::to_string).collect();
 		Self {
@@ -233,11 +233,14 @@ impl Lexer {
 				"," => self.add(AsciiToken::Comma, &start),
 				"-" if self.pop_if(|v| v == ">").is_some() => self.add(Token::Arrow, &start),
 				"@" if matches!(self.peek(), Some(v) if v.chars().all(Self::is_ident_chr_first)) => {
-					let ident = self.pop_ident(None).ok_or(OlympusError::error(
-						self.source.clone(),
-						"Couldn't pop ident after finding it, this shouldn't ever happen.",
-						self.get_span(&start),
-					))?;
+					let ident = self.pop_ident(None).ok_or(
+						OlympusError::error(
+							self.source.clone(),
+							"Couldn't pop ident after finding it, this shouldn't ever happen.",
+							self.get_span(&start),
+						)
+						.code(ErrorCode::Internal),
+					)?;
 
 					match ident.as_str() {
 						"int8" => self.add(IntToken::Int8, &start),
@@ -267,16 +270,20 @@ impl Lexer {
 								self.source.clone(),
 								"Unrecognized builtin",
 								self.get_span(&start),
-							))
+							)
+							.code(ErrorCode::UnknownBuiltin))
 						}
 					}
 				}
 				c if c.chars().all(Self::is_ident_chr_first) => {
-					let ident = self.pop_ident(Some(c)).ok_or(OlympusError::error(
-						self.source.clone(),
-						"Couldn't pop ident after finding it, this shouldn't ever happen.",
-						self.get_span(&start),
-					))?;
+					let ident = self.pop_ident(Some(c)).ok_or(
+						OlympusError::error(
+							self.source.clone(),
+							"Couldn't pop ident after finding it, this shouldn't ever happen.",
+							self.get_span(&start),
+						)
+						.code(ErrorCode::Internal),
+					)?;
 
 					match ident.as_str() {
 						"struct" => self.add(KeywordToken::Struct, &start),
@@ -296,6 +303,7 @@ impl Lexer {
 					let source = self.source.clone().clone();
 					let number = number.parse::<i16>().map_err(|_| {
 						OlympusError::error(source, &format!("Max enum tag is {}", i16::MAX), self.get_span(&start))
+							.code(ErrorCode::EnumTagOutOfRange)
 					})?;
 					self.add(Token::Number(number), &start);
 				}
@@ -304,7 +312,8 @@ impl Lexer {
 						self.source.clone().clone(),
 						&format!("Unexpected character: {c}"),
 						self.get_span(&start),
-					))
+					)
+					.code(ErrorCode::UnexpectedCharacter))
 				}
 			}
 		}
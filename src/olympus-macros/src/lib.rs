@@ -0,0 +1,259 @@
+//! Compiles olympus schemas to Rust while compiling the crate using them, with
+//! `olympus_macros::include_schema!`. Build scripts can use `olympus-build` instead, which writes the same code to
+//! `OUT_DIR`.
+
+use std::{
+	io,
+	path::{Path, PathBuf},
+};
+
+use olympus_compiler::{
+	diagnostics::write_olympus_error,
+	generate_rust_modules,
+	loader::FileSystem,
+	project::{Project, PROJECT_FILE_NAME},
+	verify_schema_from, CompileLanguage, GenerateOptions,
+};
+use olympus_spanned::OlympusError;
+use olympus_verifier::LintConfig;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+
+/// Compiles an olympus schema and expands to the code `olympusc compile --rs-crate` generates for it.
+///
+/// The path is relative to the directory of the crate's `Cargo.toml`, like `include_str!` is relative to the current
+/// file. The schema and its imports are verified and linted while compiling, and errors are reported as compiler
+/// errors on the path.
+///
+/// An `olympus.toml` in the crate directory or one of its parents is used like `olympusc` uses it: its include paths,
+/// lint levels, naming conventions and `[rust]` derives apply, its schemas and outputs are ignored.
+///
+/// Expands to three modules like the generated crate has:
+///
+/// - `models` with every enum, struct and procedure params struct.
+/// - `server` with the `ServerRpc` trait and `register_procedures`, these need `olympus-server`.
+/// - `client` with `ClientRpc`, which needs `olympus-client`.
+///
+/// ```ignore
+/// mod files {
+///     olympus_macros::include_schema!("schemas/files.ol");
+/// }
+///
+/// use files::{models::GetFileParams, server::ServerRpc};
+/// ```
+#[proc_macro]
+pub fn include_schema(input: TokenStream) -> TokenStream {
+	let path = parse_macro_input!(input as LitStr);
+	expand(&path).into()
+}
+
+fn expand(path: &LitStr) -> proc_macro2::TokenStream {
+	let Some(manifest_dir) = std::env::var_os("CARGO_MANIFEST_DIR") else {
+		return syn::Error::new(
+			path.span(),
+			"CARGO_MANIFEST_DIR isn't set, is this being compiled by cargo?",
+		)
+		.to_compile_error();
+	};
+	let crate_fs = CrateFileSystem(PathBuf::from(manifest_dir));
+	let project = match find_project(&crate_fs.0) {
+		Ok(project) => project,
+		Err(err) => return syn::Error::new(path.span(), err).to_compile_error(),
+	};
+
+	let (mut tracked, result) = compile(&crate_fs, Path::new(&path.value()), project.as_ref());
+	if let Some(project) = &project {
+		tracked.push(project.resolve(Path::new(PROJECT_FILE_NAME)));
+	}
+	// proc macros can't tell cargo which files they read, including them makes it rebuild when one of them changes
+	let tracked = tracked
+		.iter()
+		.map(|path| crate_fs.0.join(path).to_string_lossy().into_owned());
+	let tracked = quote! {
+		#(const _: &[u8] = include_bytes!(#tracked);)*
+	};
+
+	match result {
+		Ok(generated) => quote! {
+			#tracked
+			#generated
+		},
+		Err(errors) => {
+			let errors = errors
+				.into_iter()
+				.map(|err| syn::Error::new(path.span(), err).to_compile_error());
+			quote! {
+				#tracked
+				#(#errors)*
+			}
+		}
+	}
+}
+
+/// Reads paths relative to the crate directory, so diagnostics show them the way they are written in the crate.
+struct CrateFileSystem(PathBuf);
+
+impl FileSystem for CrateFileSystem {
+	fn read_to_string(&self, path: &Path) -> io::Result<String> {
+		std::fs::read_to_string(self.0.join(path))
+	}
+
+	fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+		self.0.join(path).canonicalize()
+	}
+}
+
+/// The project file in `dir` or one of its parents.
+fn find_project(dir: &Path) -> Result<Option<Project>, String> {
+	let Some(path) = dir
+		.ancestors()
+		.map(|dir| dir.join(PROJECT_FILE_NAME))
+		.find(|path| path.is_file())
+	else {
+		return Ok(None);
+	};
+
+	Project::load(&path).map(Some).map_err(|err| format!("{err:#}"))
+}
+
+/// Returns the files to track alongside the generated code, or the rendered errors.
+fn compile(
+	fs: &dyn FileSystem,
+	root: &Path,
+	project: Option<&Project>,
+) -> (Vec<PathBuf>, Result<proc_macro2::TokenStream, Vec<String>>) {
+	let render = |err| {
+		let mut out = Vec::new();
+		let _ = write_olympus_error(err, &mut out, false);
+		String::from_utf8_lossy(&out).trim_end().to_string()
+	};
+
+	let mut options = GenerateOptions::new(CompileLanguage::Rust);
+	let mut include_paths = Vec::new();
+	let mut lint_config = LintConfig::default();
+	if let Some(project) = project {
+		options.naming_convention_config = project.naming.resolve(&options.naming_convention_config);
+		options.rs_derives.clone_from(&project.rust.derives);
+		options.rs_type_derives.clone_from(&project.rust.type_derives);
+		options.rs_serde = project.rust.serde;
+		include_paths = project.include_paths();
+		lint_config = project.lint_config().expect("checked by Project::load");
+	}
+
+	let verification = verify_schema_from(fs, root, &include_paths, &lint_config);
+	let Some(schema) = verification.schema else {
+		// warnings can't be emitted from a proc macro on stable, only errors are reported
+		let errors = verification
+			.diagnostics
+			.into_iter()
+			.filter(OlympusError::is_error)
+			.map(render)
+			.collect();
+		return (verification.files, Err(errors));
+	};
+
+	let generated = generate_rust_modules(&schema, &options);
+	(verification.files, generated.map_err(|err| vec![render(err)]))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use olympus_compiler::{
+		generate_rust_modules, verify_schema, verify_schema_from, CompileLanguage, GenerateOptions, NamingConvention,
+		NamingConventionConfig,
+	};
+	use olympus_verifier::LintConfig;
+	use proc_macro2::Span;
+	use quote::quote;
+	use syn::LitStr;
+
+	use super::{compile, expand, find_project, CrateFileSystem};
+
+	#[test]
+	fn expands_to_the_generated_modules() {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/schemas/result.ol");
+		let schema = verify_schema(&path, &LintConfig::default()).schema.unwrap();
+		let generated = generate_rust_modules(&schema, &GenerateOptions::new(CompileLanguage::Rust)).unwrap();
+		let tracked = path.to_string_lossy();
+
+		let expanded = expand(&LitStr::new("tests/schemas/result.ol", Span::call_site()));
+		assert_eq!(
+			expanded.to_string(),
+			quote! {
+				const _: &[u8] = include_bytes!(#tracked);
+				#generated
+			}
+			.to_string()
+		);
+	}
+
+	#[test]
+	fn applies_the_project_file() {
+		let dir = tempfile::tempdir().unwrap();
+		let crate_dir = dir.path().join("crates/files");
+		std::fs::create_dir_all(&crate_dir).unwrap();
+		std::fs::create_dir(dir.path().join("shared")).unwrap();
+		std::fs::write(
+			dir.path().join("olympus.toml"),
+			r#"
+include = ["shared"]
+
+[naming]
+struct_fields = "lower_camel"
+
+[lints]
+unused_type = "deny"
+
+[rust]
+serde = true
+type_derives = { File = ["Hash"] }
+"#,
+		)
+		.unwrap();
+		std::fs::write(
+			crate_dir.join("files.ol"),
+			"import user;\nstruct File { owner_id->User; }",
+		)
+		.unwrap();
+		std::fs::write(dir.path().join("shared/user.ol"), "struct User { name->@string; }").unwrap();
+
+		let project = find_project(&crate_dir).unwrap().unwrap();
+		let crate_fs = CrateFileSystem(crate_dir.clone());
+		let (tracked, result) = compile(&crate_fs, "files.ol".as_ref(), Some(&project));
+		assert_eq!(tracked.len(), 2);
+		// `File` isn't used by a proc and the project denies unused types
+		assert!(result.unwrap_err()[0].contains("Struct 'File' is never used"));
+
+		std::fs::write(
+			crate_dir.join("files.ol"),
+			"import user;\nstruct File { owner_id->User; }\nproc Get() -> File;",
+		)
+		.unwrap();
+		let (_, result) = compile(&crate_fs, "files.ol".as_ref(), Some(&project));
+
+		let schema = verify_schema_from(
+			&crate_fs,
+			"files.ol".as_ref(),
+			&[dir.path().join("shared")],
+			&LintConfig::default(),
+		)
+		.schema
+		.unwrap();
+		let mut options = GenerateOptions::new(CompileLanguage::Rust);
+		options.naming_convention_config = NamingConventionConfig {
+			struct_fields: NamingConvention::LowerCamel,
+			..NamingConventionConfig::defaults_for(CompileLanguage::Rust)
+		};
+		options.rs_serde = true;
+		options
+			.rs_type_derives
+			.insert("File".to_string(), vec!["Hash".to_string()]);
+		assert_eq!(
+			result.unwrap().to_string(),
+			generate_rust_modules(&schema, &options).unwrap().to_string()
+		);
+	}
+}
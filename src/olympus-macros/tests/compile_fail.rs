@@ -0,0 +1,13 @@
+//! Schemas that don't compile are reported as compile errors on the path.
+
+use std::path::Path;
+
+#[test]
+fn broken_schemas() {
+	// trybuild builds the cases as a crate of its own in the target directory, schema paths are relative to it
+	let project_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("../tests/trybuild/olympus-macros");
+	std::fs::create_dir_all(project_dir.join("schemas")).unwrap();
+	std::fs::copy("tests/ui/broken.ol", project_dir.join("schemas/broken.ol")).unwrap();
+
+	trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
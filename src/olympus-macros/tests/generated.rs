@@ -0,0 +1,42 @@
+//! The generated code compiles for schemas whose names clash with the ones the generated code uses itself.
+
+mod result {
+	olympus_macros::include_schema!("tests/schemas/result.ol");
+}
+
+mod prelude {
+	olympus_macros::include_schema!("tests/schemas/prelude.ol");
+}
+
+#[test]
+fn enums_convert_next_to_a_result_struct() {
+	use result::models::{Result, Status};
+
+	let result = Result {
+		status: Status::try_from(2).unwrap(),
+		message: None,
+	};
+	assert_eq!(result.status.name(), "Failed");
+	assert_eq!("Ok".parse::<Status>().map(u16::from), Ok(1));
+}
+
+#[test]
+fn structs_named_after_prelude_types_roundtrip() {
+	use olympus_net_common::{ProcedureInput, ProcedureOutput};
+	use prelude::models::{Box, Option, String, Vec};
+
+	let option = Option {
+		name: Some(String {
+			value: "name".to_string(),
+		}),
+		items: Vec { items: vec![1, 2, 3] },
+		boxed: Box {
+			next: Some(std::boxed::Box::new(Box { next: None })),
+		},
+	};
+	let mut bytes = option.serialize().unwrap();
+	let decoded = Option::deserialize(&mut bytes).unwrap();
+	assert_eq!(decoded.name.map(|name| name.value).as_deref(), Some("name"));
+	assert_eq!(decoded.items.items, [1, 2, 3]);
+	assert!(decoded.boxed.next.is_some_and(|next| next.next.is_none()));
+}
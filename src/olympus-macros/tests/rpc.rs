@@ -0,0 +1,70 @@
+//! Generated servers and clients talking to each other over a real connection.
+
+use std::{net::SocketAddr, time::Duration};
+
+use olympus_client::OlympusClient;
+use olympus_net_common::{async_trait, Result};
+use olympus_server::OlympusServer;
+
+mod empty {
+	olympus_macros::include_schema!("tests/schemas/empty.ol");
+}
+
+use empty::{
+	client::ClientRpc,
+	models::{Empty, NotifyParams},
+	server::{register_procedures, ServerRpc},
+};
+
+struct ServerImpl;
+
+#[async_trait]
+impl ServerRpc<()> for ServerImpl {
+	async fn ping(_context: ()) -> Result<Empty> {
+		Ok(Empty {})
+	}
+
+	async fn notify(_context: (), _params: NotifyParams) -> Result<()> {
+		Ok(())
+	}
+}
+
+async fn connect() -> ClientRpc<()> {
+	let addr: SocketAddr = {
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		listener.local_addr().unwrap()
+	};
+	tokio::spawn(async move {
+		let mut server = OlympusServer::new(());
+		register_procedures(&mut server, ServerImpl).await;
+		server.run(addr).await
+	});
+
+	let mut client = OlympusClient::new(());
+	for _ in 0..50 {
+		if client.connect(addr).await.is_ok() {
+			return ClientRpc::new(client);
+		}
+		tokio::time::sleep(Duration::from_millis(20)).await;
+	}
+	panic!("the server didn't start listening");
+}
+
+#[tokio::test]
+async fn empty_responses_are_answered() {
+	let client = connect().await;
+	let calls = async {
+		client
+			.notify(NotifyParams {
+				message: "before".to_string(),
+			})
+			.await?;
+		client.ping().await?;
+		client.ping().await
+	};
+
+	tokio::time::timeout(Duration::from_secs(5), calls)
+		.await
+		.expect("calls returning an empty struct should be answered")
+		.unwrap();
+}
@@ -0,0 +1,5 @@
+mod broken {
+	olympus_macros::include_schema!("schemas/broken.ol");
+}
+
+fn main() {}
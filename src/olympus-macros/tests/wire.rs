@@ -0,0 +1,391 @@
+//! The wire format pinned to fixed byte vectors.
+//!
+//! The generated Rust code has to produce exactly these bytes, the code generated for other languages has to encode
+//! the same values to them and decode them back. A language is skipped if its toolchain isn't installed, Kotlin also
+//! needs `KOTLINX_COROUTINES_JAR` to point to the kotlinx-coroutines-core-jvm jar.
+
+use std::{
+	fmt::Write,
+	io::ErrorKind,
+	path::Path,
+	process::{Command, Stdio},
+};
+
+use olympus_compiler::{generate, verify_schema, CompileLanguage, GenerateOptions, LintConfig};
+use olympus_net_common::{
+	bytes::{BufMut, BytesMut},
+	fnv, OlympusPacketCodec, ProcedureInput, ProcedureOutput, Variable,
+};
+use tokio_util::codec::Encoder;
+
+mod wire {
+	olympus_macros::include_schema!("tests/schemas/wire.ol");
+}
+
+use wire::models::{Action, File, Numbers, User};
+
+/// Big-endian integers followed by zigzag LEB128 varints: 300 is `ac02`, -3 is `05`, 64 is `8001`, -2 as an i8 is
+/// `03`, -300 as an i16 is `d704` and the biggest u64 takes ten bytes.
+const NUMBERS: &str = "ab fe 1234 fffe deadbeef fffffffe 0102030405060708 fffffffffffffffe ac02 05 8001 c801 03 d704 \
+                       ffffffffffffffffff01";
+/// Strings and arrays start with a u32 length, options with a u8 flag and enums are their u16 tag. The label is an
+/// option holding an empty option.
+const FILE: &str = "00000003 612f62 00000002 0001 0203 01 00000002 c3a9 012c 0100";
+const EMPTY_FILE: &str = "00000000 00000000 00 0001 00";
+/// The FNV-1a hash of `Echo`, requests and responses start with the hash of their procedure.
+const ECHO_HASH: u64 = 0x5a8e_e66c_2774_b544;
+/// Frames at least this big are compressed by the server.
+const MIN_SIZE_TO_COMPRESS: u32 = 8192;
+
+fn numbers() -> Numbers {
+	Numbers {
+		u8: 0xab,
+		i8: -2,
+		u16: 0x1234,
+		i16: -2,
+		u32: 0xdead_beef,
+		i32: -2,
+		u64: 0x0102_0304_0506_0708,
+		i64: -2,
+		vu32: Variable(300),
+		vi32: Variable(-3),
+		vi64: Variable(64),
+		vu8: Variable(200),
+		vi8: Variable(-2),
+		vi16: Variable(-300),
+		vu64: Variable(u64::MAX),
+	}
+}
+
+fn file() -> File {
+	File {
+		path: "a/b".to_string(),
+		content: vec![1, 0x0203],
+		owner: Some(User { name: "é".to_string() }),
+		action: Action::Encrypt,
+		label: Some(None),
+	}
+}
+
+fn empty_file() -> File {
+	File {
+		path: String::new(),
+		content: Vec::new(),
+		owner: None,
+		action: Action::Delete,
+		label: None,
+	}
+}
+
+/// Big enough for the response echoing it to be compressed, drivers build it the same way.
+fn big_file() -> File {
+	File {
+		path: "big".to_string(),
+		content: (0..5000).map(|idx| idx % 7).collect(),
+		owner: None,
+		action: Action::Delete,
+		label: None,
+	}
+}
+
+fn vector(vector: &str) -> String {
+	vector.split_whitespace().collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+	let mut hex = String::with_capacity(bytes.len() * 2);
+	for byte in bytes {
+		write!(hex, "{byte:02x}").unwrap();
+	}
+	hex
+}
+
+fn from_hex(hex: &str) -> BytesMut {
+	(0..hex.len())
+		.step_by(2)
+		.map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16).unwrap())
+		.collect()
+}
+
+fn serialize(value: &impl ProcedureOutput) -> String {
+	to_hex(&value.serialize().unwrap())
+}
+
+/// Decodes the vector and encodes the value again.
+fn roundtrip<T: ProcedureInput + ProcedureOutput>(hex: &str) -> String {
+	let mut input = from_hex(&vector(hex));
+	let value = T::deserialize(&mut input).unwrap();
+	assert!(input.is_empty(), "{} bytes weren't read", input.len());
+	serialize(&value)
+}
+
+/// The frame calling `Echo` with [`file`] is sent in, requests are never compressed.
+fn echo_request() -> String {
+	let params = file().serialize().unwrap();
+	let mut frame = BytesMut::new();
+	frame.put_u8(0);
+	frame.put_u32(u32::try_from(params.len()).unwrap() + 8);
+	frame.put_u64(ECHO_HASH);
+	frame.extend(params);
+	to_hex(&frame)
+}
+
+/// The LZ4 compressed frame the server answers `Echo` with for [`big_file`].
+fn echo_response() -> String {
+	let mut payload = BytesMut::new();
+	payload.put_u64(ECHO_HASH);
+	payload.extend(big_file().serialize().unwrap());
+
+	let mut frame = BytesMut::new();
+	OlympusPacketCodec::compress(MIN_SIZE_TO_COMPRESS)
+		.encode(payload, &mut frame)
+		.unwrap();
+	assert_eq!(frame[0], 1, "the response should be compressed");
+	to_hex(&frame)
+}
+
+/// What every driver prints, line by line: the values it encodes, the vectors it decodes and encodes again, the
+/// request it sends for `Echo` and the file it decodes from [`echo_response`].
+fn expected_output() -> Vec<(&'static str, String)> {
+	vec![
+		("encoded numbers", vector(NUMBERS)),
+		("encoded file", vector(FILE)),
+		("encoded empty file", vector(EMPTY_FILE)),
+		("decoded numbers", vector(NUMBERS)),
+		("decoded file", vector(FILE)),
+		("decoded empty file", vector(EMPTY_FILE)),
+		("echo request", echo_request()),
+		("echo response", serialize(&big_file())),
+	]
+}
+
+/// Compares the output of a driver to [`expected_output`], only the start of a line is shown since the echoed file
+/// is long.
+fn check_output(output: &[String]) {
+	let expected = expected_output();
+	assert_eq!(
+		output.len(),
+		expected.len(),
+		"the driver should print {} lines",
+		expected.len()
+	);
+
+	let start = |line: &str| line.chars().take(120).collect::<String>();
+	for (line, (name, expected)) in output.iter().zip(expected) {
+		assert!(
+			*line == expected,
+			"the {name} differs\n printed: {}\nexpected: {}",
+			start(line),
+			start(&expected)
+		);
+	}
+}
+
+/// Writes the code generated with `options` from the wire schema to `path`.
+fn write_generated(options: &GenerateOptions, path: &Path) {
+	let schema = verify_schema(Path::new("tests/schemas/wire.ol"), &LintConfig::default())
+		.schema
+		.unwrap();
+	let files = generate(&schema, options).unwrap();
+	std::fs::write(path, &files[0].contents).unwrap();
+}
+
+/// The vectors drivers decode, as hex arguments.
+fn vectors() -> Vec<String> {
+	let mut vectors = [NUMBERS, FILE, EMPTY_FILE].map(vector).to_vec();
+	vectors.push(echo_response());
+	vectors
+}
+
+/// Runs `command` and returns the lines it printed, or `None` if the program isn't installed.
+fn run(command: &mut Command) -> Option<Vec<String>> {
+	let program = command.get_program().to_string_lossy().into_owned();
+	let output = match command.stderr(Stdio::inherit()).output() {
+		Ok(output) => output,
+		Err(err) if err.kind() == ErrorKind::NotFound => {
+			eprintln!("skipped, '{program}' isn't installed");
+			return None;
+		}
+		Err(err) => panic!("couldn't run '{program}': {err}"),
+	};
+	assert!(output.status.success(), "'{program}' failed");
+
+	Some(
+		String::from_utf8(output.stdout)
+			.unwrap()
+			.lines()
+			.map(str::to_string)
+			.collect(),
+	)
+}
+
+#[test]
+fn rust_matches_the_vectors() {
+	assert_eq!(serialize(&numbers()), vector(NUMBERS));
+	assert_eq!(serialize(&file()), vector(FILE));
+	assert_eq!(serialize(&empty_file()), vector(EMPTY_FILE));
+
+	assert_eq!(roundtrip::<Numbers>(NUMBERS), vector(NUMBERS));
+	assert_eq!(roundtrip::<File>(FILE), vector(FILE));
+	assert_eq!(roundtrip::<File>(EMPTY_FILE), vector(EMPTY_FILE));
+
+	assert_eq!(fnv("Echo"), ECHO_HASH);
+}
+
+#[test]
+fn python_matches_the_vectors() {
+	let dir = tempfile::tempdir().unwrap();
+	write_generated(
+		&GenerateOptions::new(CompileLanguage::Python),
+		&dir.path().join("wire.py"),
+	);
+	std::fs::write(dir.path().join("driver.py"), include_str!("wire/driver.py")).unwrap();
+
+	let Some(output) = run(Command::new("python3")
+		.arg("driver.py")
+		.args(vectors())
+		.current_dir(dir.path()))
+	else {
+		return;
+	};
+	check_output(&output);
+}
+
+#[test]
+fn c_matches_the_vectors() {
+	let dir = tempfile::tempdir().unwrap();
+	write_generated(&GenerateOptions::new(CompileLanguage::C), &dir.path().join("wire.h"));
+	std::fs::write(dir.path().join("driver.c"), include_str!("wire/driver.c")).unwrap();
+
+	let compiled = run(Command::new("cc")
+		.args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-o", "driver", "driver.c"])
+		.current_dir(dir.path()));
+	if compiled.is_none() {
+		return;
+	}
+
+	let output = run(Command::new(dir.path().join("driver")).args(vectors())).unwrap();
+	check_output(&output);
+}
+
+#[test]
+fn typescript_matches_the_vectors() {
+	let dir = tempfile::tempdir().unwrap();
+	write_generated(
+		&GenerateOptions::new(CompileLanguage::TypeScript),
+		&dir.path().join("wire.ts"),
+	);
+	std::fs::write(dir.path().join("driver.ts"), include_str!("wire/driver.ts")).unwrap();
+
+	// the runtime uses bigint literals, which need ES2020
+	let compiled = run(Command::new("tsc")
+		.args(["--strict", "--target", "es2020", "--module", "commonjs", "driver.ts"])
+		.current_dir(dir.path()));
+	if compiled.is_none() {
+		return;
+	}
+
+	let output = run(Command::new("node")
+		.arg("driver.js")
+		.args(vectors())
+		.current_dir(dir.path()))
+	.unwrap();
+	check_output(&output);
+}
+
+#[test]
+fn go_matches_the_vectors() {
+	let dir = tempfile::tempdir().unwrap();
+	// the driver is a command, which has to be in package main like the code it uses
+	let mut options = GenerateOptions::new(CompileLanguage::Go);
+	options.go_package = Some("main".to_string());
+	write_generated(&options, &dir.path().join("wire.go"));
+	std::fs::write(dir.path().join("driver.go"), include_str!("wire/driver.go")).unwrap();
+	std::fs::write(dir.path().join("go.mod"), "module wire\n\ngo 1.21\n").unwrap();
+
+	let Some(output) = run(Command::new("go")
+		.args(["run", "."])
+		.args(vectors())
+		.current_dir(dir.path()))
+	else {
+		return;
+	};
+	check_output(&output);
+}
+
+/// A `dotnet` command run in `dir`, without the messages it prints the first time it runs.
+fn dotnet(dir: &Path) -> Command {
+	let mut command = Command::new("dotnet");
+	command
+		.current_dir(dir)
+		.env("DOTNET_NOLOGO", "1")
+		.env("DOTNET_CLI_TELEMETRY_OPTOUT", "1");
+	command
+}
+
+#[test]
+fn csharp_matches_the_vectors() {
+	let dir = tempfile::tempdir().unwrap();
+	let Some(version) = run(dotnet(dir.path()).arg("--version")) else {
+		return;
+	};
+	// the framework of the installed SDK, any other would have to be downloaded
+	let major = version.last().unwrap().split('.').next().unwrap().to_string();
+	write_generated(
+		&GenerateOptions::new(CompileLanguage::CSharp),
+		&dir.path().join("Wire.cs"),
+	);
+	std::fs::write(dir.path().join("Driver.cs"), include_str!("wire/Driver.cs")).unwrap();
+	std::fs::write(
+		dir.path().join("driver.csproj"),
+		format!(
+			"<Project Sdk=\"Microsoft.NET.Sdk\">
+  <PropertyGroup>
+    <OutputType>Exe</OutputType>
+    <TargetFramework>net{major}.0</TargetFramework>
+    <Nullable>enable</Nullable>
+    <InvariantGlobalization>true</InvariantGlobalization>
+  </PropertyGroup>
+</Project>
+"
+		),
+	)
+	.unwrap();
+
+	run(dotnet(dir.path()).args(["build", "-o", "out"])).unwrap();
+	let output = run(dotnet(dir.path()).arg("out/driver.dll").args(vectors())).unwrap();
+	check_output(&output);
+}
+
+#[test]
+fn kotlin_matches_the_vectors() {
+	// the runtime needs kotlinx.coroutines, which doesn't come with the compiler
+	let Some(coroutines) = std::env::var_os("KOTLINX_COROUTINES_JAR") else {
+		eprintln!("skipped, KOTLINX_COROUTINES_JAR isn't set to the path of kotlinx-coroutines-core-jvm");
+		return;
+	};
+	let dir = tempfile::tempdir().unwrap();
+	write_generated(
+		&GenerateOptions::new(CompileLanguage::Kotlin),
+		&dir.path().join("Wire.kt"),
+	);
+	std::fs::write(dir.path().join("Driver.kt"), include_str!("wire/Driver.kt")).unwrap();
+
+	let compiled = run(Command::new("kotlinc")
+		.arg("-cp")
+		.arg(&coroutines)
+		.args(["-include-runtime", "-d", "driver.jar", "Wire.kt", "Driver.kt"])
+		.current_dir(dir.path()));
+	if compiled.is_none() {
+		return;
+	}
+
+	let classpath = std::env::join_paths([dir.path().join("driver.jar").into_os_string(), coroutines]).unwrap();
+	let output = run(Command::new("java")
+		.arg("-cp")
+		.arg(classpath)
+		.arg("olympus.DriverKt")
+		.args(vectors()))
+	.unwrap();
+	check_output(&output);
+}
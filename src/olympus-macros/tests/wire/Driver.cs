@@ -0,0 +1,124 @@
+// Encodes the values of `tests/wire.rs` and decodes its vectors with the generated `Wire.cs`.
+//
+// The vectors are passed as hex, every result is printed as hex on its own line.
+
+#nullable enable
+
+using System;
+using System.Buffers.Binary;
+using System.Collections.Generic;
+using System.Net;
+using System.Net.Sockets;
+using System.Threading.Tasks;
+
+// in the generated namespace, so its `File` and `Action` take precedence over the ones of `System`
+namespace Olympus;
+
+public static class Driver
+{
+    private static readonly Numbers TestNumbers = new(
+        U8: 0xab,
+        I8: -2,
+        U16: 0x1234,
+        I16: -2,
+        U32: 0xdeadbeef,
+        I32: -2,
+        U64: 0x0102030405060708,
+        I64: -2,
+        Vu32: 300,
+        Vi32: -3,
+        Vi64: 64,
+        Vu8: 200,
+        Vi8: -2,
+        Vi16: -300,
+        Vu64: ulong.MaxValue);
+    private static readonly File TestFile = new(
+        Path: "a/b",
+        Content: new List<ushort> { 1, 0x0203 },
+        Owner: new User("é"),
+        Action: Action.Encrypt,
+        Label: new Some<string?>(null));
+    private static readonly File EmptyFile =
+        new(Path: "", Content: new List<ushort>(), Owner: null, Action: Action.Delete, Label: null);
+
+    private static string ToHex(byte[] data) => Convert.ToHexString(data).ToLowerInvariant();
+
+    private static string Encode(Action<Writer> encode)
+    {
+        var w = new Writer();
+        encode(w);
+        return ToHex(w.ToArray());
+    }
+
+    private static Reader FromHex(string vector) => new(Convert.FromHexString(vector));
+
+    private static async Task ReadExactlyAsync(NetworkStream stream, byte[] buffer)
+    {
+        int read = 0;
+        while (read < buffer.Length)
+        {
+            int count = await stream.ReadAsync(buffer.AsMemory(read));
+            if (count == 0)
+            {
+                throw new InvalidOperationException("the client closed the connection");
+            }
+            read += count;
+        }
+    }
+
+    /// <summary>Stands in for the server, answering the first request with <paramref name="response"/>.</summary>
+    private static async Task<byte[]> ServeAsync(TcpListener listener, byte[] response)
+    {
+        using TcpClient client = await listener.AcceptTcpClientAsync();
+        NetworkStream stream = client.GetStream();
+        byte[] header = new byte[5];
+        await ReadExactlyAsync(stream, header);
+        byte[] data = new byte[BinaryPrimitives.ReadUInt32BigEndian(header.AsSpan(1))];
+        await ReadExactlyAsync(stream, data);
+
+        await stream.WriteAsync(response);
+        byte[] request = new byte[header.Length + data.Length];
+        header.CopyTo(request, 0);
+        data.CopyTo(request, header.Length);
+        return request;
+    }
+
+    /// <summary>Calls <c>Echo</c> with <see cref="TestFile"/> over a local connection.</summary>
+    private static async Task EchoAsync(string response)
+    {
+        var listener = new TcpListener(IPAddress.Loopback, 0);
+        listener.Start();
+        try
+        {
+            Task<byte[]> request = ServeAsync(listener, Convert.FromHexString(response));
+            int port = ((IPEndPoint)listener.LocalEndpoint).Port;
+            await using OlympusConnection connection = await OlympusConnection.ConnectAsync("127.0.0.1", port);
+
+            File echoed = await new ClientRpc(connection).EchoAsync(new EchoParams(TestFile));
+            Console.WriteLine(ToHex(await request));
+            Console.WriteLine(Encode(echoed.Encode));
+        }
+        finally
+        {
+            listener.Stop();
+        }
+    }
+
+    public static async Task<int> Main(string[] args)
+    {
+        if (args.Length != 4)
+        {
+            Console.Error.WriteLine("expected the numbers, file, empty file and response vectors");
+            return 1;
+        }
+
+        Console.WriteLine(Encode(TestNumbers.Encode));
+        Console.WriteLine(Encode(TestFile.Encode));
+        Console.WriteLine(Encode(EmptyFile.Encode));
+        Console.WriteLine(Encode(Numbers.Decode(FromHex(args[0])).Encode));
+        Console.WriteLine(Encode(File.Decode(FromHex(args[1])).Encode));
+        Console.WriteLine(Encode(File.Decode(FromHex(args[2])).Encode));
+        await EchoAsync(args[3]);
+        return 0;
+    }
+}
@@ -0,0 +1,91 @@
+// Encodes the values of `tests/wire.rs` and decodes its vectors with the generated `Wire.kt`.
+//
+// The vectors are passed as hex, every result is printed as hex on its own line.
+
+package olympus
+
+import java.io.DataInputStream
+import java.net.InetAddress
+import java.net.ServerSocket
+import java.nio.ByteBuffer
+import kotlin.concurrent.thread
+import kotlin.system.exitProcess
+import kotlinx.coroutines.runBlocking
+
+val numbers = Numbers(
+    u8 = 0xabu,
+    i8 = -2,
+    u16 = 0x1234u,
+    i16 = -2,
+    u32 = 0xdeadbeefu,
+    i32 = -2,
+    u64 = 0x0102030405060708uL,
+    i64 = -2,
+    vu32 = 300u,
+    vi32 = -3,
+    vi64 = 64,
+    vu8 = 200u,
+    vi8 = -2,
+    vi16 = -300,
+    vu64 = ULong.MAX_VALUE,
+)
+val file = File(
+    path = "a/b",
+    content = listOf(1u, 0x0203u),
+    owner = User(name = "é"),
+    action = Action.ENCRYPT,
+    label = Some(null),
+)
+val emptyFile = File(path = "", content = listOf(), owner = null, action = Action.DELETE, label = null)
+
+fun ByteArray.toHex(): String = joinToString("") { "%02x".format(it) }
+
+fun String.fromHex(): ByteArray = chunked(2).map { it.toInt(16).toByte() }.toByteArray()
+
+fun encode(write: (Writer) -> Unit): String {
+    val w = Writer()
+    write(w)
+    return w.toByteArray().toHex()
+}
+
+/** Calls `Echo` with [file] over a local connection, the server answers the first request with [response]. */
+fun echo(response: ByteArray) = runBlocking {
+    ServerSocket(0, 1, InetAddress.getLoopbackAddress()).use { server ->
+        var request = ByteArray(0)
+        val serving = thread {
+            server.accept().use { socket ->
+                val input = DataInputStream(socket.getInputStream())
+                val header = ByteArray(5)
+                input.readFully(header)
+                val data = ByteArray(ByteBuffer.wrap(header, 1, 4).getInt())
+                input.readFully(data)
+                request = header + data
+
+                socket.getOutputStream().write(response)
+                socket.getOutputStream().flush()
+            }
+        }
+
+        OlympusConnection.connect("127.0.0.1", server.localPort).use { connection ->
+            val echoed = ClientRpc(connection).echo(EchoParams(file = file))
+            serving.join()
+            println(request.toHex())
+            println(encode(echoed::encode))
+        }
+    }
+}
+
+fun main(args: Array<String>) {
+    if (args.size != 4) {
+        System.err.println("expected the numbers, file, empty file and response vectors")
+        exitProcess(1)
+    }
+
+    println(encode(numbers::encode))
+    println(encode(file::encode))
+    println(encode(emptyFile::encode))
+    println(encode(Numbers.decode(Reader(args[0].fromHex()))::encode))
+    println(encode(File.decode(Reader(args[1].fromHex()))::encode))
+    println(encode(File.decode(Reader(args[2].fromHex()))::encode))
+    echo(args[3].fromHex())
+}
@@ -0,0 +1,141 @@
+/*
+ * Encodes the values of `tests/wire.rs` and decodes its vectors with the generated `wire.h`.
+ *
+ * The vectors are passed as hex, every result is printed as hex on its own line.
+ */
+
+#define OLYMPUS_IMPLEMENTATION
+#include "wire.h"
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static uint8_t buffer[65536];
+static uint8_t arena[65536];
+static uint8_t scratch[65536];
+
+static void check(olympus_result result, const char *what) {
+	if (result != OLYMPUS_OK) {
+		fprintf(stderr, "%s failed with %d\n", what, (int)result);
+		exit(1);
+	}
+}
+
+static void print_hex(const uint8_t *data, size_t len) {
+	for (size_t idx = 0; idx < len; idx++) {
+		printf("%02x", data[idx]);
+	}
+	printf("\n");
+}
+
+/* The caller has to free the returned bytes. */
+static uint8_t *from_hex(const char *hex, size_t *len) {
+	*len = strlen(hex) / 2;
+	uint8_t *data = malloc(*len + 1);
+	for (size_t idx = 0; idx < *len; idx++) {
+		unsigned int byte;
+		sscanf(hex + 2 * idx, "%2x", &byte);
+		data[idx] = (uint8_t)byte;
+	}
+	return data;
+}
+
+static void print_numbers(const Numbers *value) {
+	olympus_writer w = olympus_writer_init(buffer, sizeof(buffer));
+	check(olympus_encode_Numbers(&w, value), "encoding Numbers");
+	print_hex(w.data, w.len);
+}
+
+static void print_file(const File *value) {
+	olympus_writer w = olympus_writer_init(buffer, sizeof(buffer));
+	check(olympus_encode_File(&w, value), "encoding File");
+	print_hex(w.data, w.len);
+}
+
+static void roundtrip_numbers(const char *hex) {
+	size_t len;
+	uint8_t *data = from_hex(hex, &len);
+	olympus_reader r = olympus_reader_init(data, len, arena, sizeof(arena));
+	Numbers value;
+	check(olympus_decode_Numbers(&r, &value), "decoding Numbers");
+	print_numbers(&value);
+	free(data);
+}
+
+static void roundtrip_file(const char *hex) {
+	size_t len;
+	uint8_t *data = from_hex(hex, &len);
+	olympus_reader r = olympus_reader_init(data, len, arena, sizeof(arena));
+	File value;
+	check(olympus_decode_File(&r, &value), "decoding File");
+	/* the strings of the file point into `data` */
+	print_file(&value);
+	free(data);
+}
+
+int main(int argc, char **argv) {
+	if (argc != 5) {
+		fprintf(stderr, "expected the numbers, file, empty file and response vectors\n");
+		return 1;
+	}
+
+	Numbers numbers = {
+		.u8 = 0xab,
+		.i8 = -2,
+		.u16 = 0x1234,
+		.i16 = -2,
+		.u32 = 0xdeadbeef,
+		.i32 = -2,
+		.u64 = UINT64_C(0x0102030405060708),
+		.i64 = -2,
+		.vu32 = 300,
+		.vi32 = -3,
+		.vi64 = 64,
+		.vu8 = 200,
+		.vi8 = -2,
+		.vi16 = -300,
+		.vu64 = UINT64_MAX,
+	};
+	uint16_t content[] = {1, 0x0203};
+	File file = {
+		.path = olympus_string_from("a/b"),
+		.content = {.items = content, .len = 2},
+		.owner = {.present = true, .value = {.name = olympus_string_from("\xc3\xa9")}},
+		.action = ACTION_ENCRYPT,
+		/* present, holding an empty option */
+		.label = {.present = true},
+	};
+	File empty_file = {
+		.path = olympus_string_from(""),
+		.action = ACTION_DELETE,
+	};
+
+	print_numbers(&numbers);
+	print_file(&file);
+	print_file(&empty_file);
+	roundtrip_numbers(argv[1]);
+	roundtrip_file(argv[2]);
+	roundtrip_file(argv[3]);
+
+	olympus_writer w = olympus_writer_init(buffer, sizeof(buffer));
+	EchoParams params = {.file = file};
+	check(olympus_request_echo(&w, &params), "writing the Echo request");
+	print_hex(w.data, w.len);
+
+	size_t len;
+	uint8_t *response = from_hex(argv[4], &len);
+	olympus_frame frame;
+	check(olympus_read_frame(response, len, scratch, sizeof(scratch), &frame), "reading the Echo response");
+	if (frame.hash != OLYMPUS_PROC_ECHO || frame.frame_len != len) {
+		fprintf(stderr, "the response isn't a single Echo frame\n");
+		return 1;
+	}
+
+	olympus_reader r = olympus_reader_init(frame.payload, frame.payload_len, arena, sizeof(arena));
+	File echoed;
+	check(olympus_decode_File(&r, &echoed), "decoding the Echo response");
+	print_file(&echoed);
+	free(response);
+	return 0;
+}
@@ -0,0 +1,113 @@
+// Encodes the values of `tests/wire.rs` and decodes its vectors with the generated `wire.go`.
+//
+// The vectors are passed as hex, every result is printed as hex on its own line.
+package main
+
+import (
+	"context"
+	"encoding/binary"
+	"encoding/hex"
+	"fmt"
+	"io"
+	"math"
+	"net"
+	"os"
+)
+
+var numbers = Numbers{
+	U8:   0xab,
+	I8:   -2,
+	U16:  0x1234,
+	I16:  -2,
+	U32:  0xdeadbeef,
+	I32:  -2,
+	U64:  0x0102030405060708,
+	I64:  -2,
+	Vu32: 300,
+	Vi32: -3,
+	Vi64: 64,
+	Vu8:  200,
+	Vi8:  -2,
+	Vi16: -300,
+	Vu64: math.MaxUint64,
+}
+var file = File{
+	Path:    "a/b",
+	Content: []uint16{1, 0x0203},
+	Owner:   &User{Name: "é"},
+	Action:  ActionEncrypt,
+	Label:   new(*string), // present, holding an empty option
+}
+var emptyFile = File{Path: "", Action: ActionDelete}
+
+// codec is implemented by pointers to every generated type.
+type codec[T any] interface {
+	*T
+	Marshaler
+	UnmarshalOlympus(r *Reader) error
+}
+
+func check(err error) {
+	if err != nil {
+		fmt.Fprintln(os.Stderr, err)
+		os.Exit(1)
+	}
+}
+
+func fromHex(vector string) []byte {
+	data, err := hex.DecodeString(vector)
+	check(err)
+	return data
+}
+
+func encode(value Marshaler) string {
+	var w Writer
+	value.MarshalOlympus(&w)
+	return hex.EncodeToString(w.Bytes())
+}
+
+func roundtrip[T any, P codec[T]](vector string) string {
+	value, err := ReadValue[T, P](NewReader(fromHex(vector)))
+	check(err)
+	return encode(P(&value))
+}
+
+// echo calls Echo with file, the server is replaced by a pipe that answers with response.
+func echo(response []byte) {
+	client, server := net.Pipe()
+	requests := make(chan []byte, 1)
+	go func() {
+		header := make([]byte, 5)
+		_, err := io.ReadFull(server, header)
+		check(err)
+		data := make([]byte, binary.BigEndian.Uint32(header[1:]))
+		_, err = io.ReadFull(server, data)
+		check(err)
+		requests <- append(header, data...)
+
+		_, err = server.Write(response)
+		check(err)
+	}()
+
+	connection := NewConnection(client)
+	defer connection.Close()
+	echoed, err := NewClientRpc(connection).Echo(context.Background(), EchoParams{File: file})
+	check(err)
+	fmt.Println(hex.EncodeToString(<-requests))
+	fmt.Println(encode(echoed))
+}
+
+func main() {
+	if len(os.Args) != 5 {
+		fmt.Fprintln(os.Stderr, "expected the numbers, file, empty file and response vectors")
+		os.Exit(1)
+	}
+
+	fmt.Println(encode(numbers))
+	fmt.Println(encode(file))
+	fmt.Println(encode(emptyFile))
+	fmt.Println(roundtrip[Numbers](os.Args[1]))
+	fmt.Println(roundtrip[File](os.Args[2]))
+	fmt.Println(roundtrip[File](os.Args[3]))
+	echo(fromHex(os.Args[4]))
+}
@@ -0,0 +1,91 @@
+"""Encodes the values of `tests/wire.rs` and decodes its vectors with the generated `wire` module.
+
+The vectors are passed as hex, every result is printed as hex on its own line.
+"""
+
+import asyncio
+import sys
+
+import wire
+
+NUMBERS = wire.Numbers(
+    u8=0xAB,
+    i8=-2,
+    u16=0x1234,
+    i16=-2,
+    u32=0xDEADBEEF,
+    i32=-2,
+    u64=0x0102030405060708,
+    i64=-2,
+    vu32=300,
+    vi32=-3,
+    vi64=64,
+    vu8=200,
+    vi8=-2,
+    vi16=-300,
+    vu64=2**64 - 1,
+)
+FILE = wire.File(
+    path="a/b",
+    content=[1, 0x0203],
+    owner=wire.User(name="é"),
+    action=wire.Action.ENCRYPT,
+    label=wire.Some(None),
+)
+EMPTY_FILE = wire.File(path="", content=[], owner=None, action=wire.Action.DELETE, label=None)
+
+
+def encode(value) -> str:
+    w = wire.Writer()
+    value.encode(w)
+    return w.finish().hex()
+
+
+def roundtrip(cls, vector: str) -> str:
+    return encode(cls.decode(wire.Reader(bytes.fromhex(vector))))
+
+
+class Recorder:
+    """Stands in for the stream writer of a connection, keeping everything that was written."""
+
+    def __init__(self) -> None:
+        self.data = bytearray()
+
+    def write(self, data: bytes) -> None:
+        self.data += data
+
+    async def drain(self) -> None:
+        pass
+
+    def close(self) -> None:
+        pass
+
+    async def wait_closed(self) -> None:
+        pass
+
+
+async def echo(response: bytes) -> None:
+    """Calls `Echo` with `FILE`, the server is replaced by a stream that answers with `response`."""
+    reader = asyncio.StreamReader()
+    reader.feed_data(response)
+    reader.feed_eof()
+    writer = Recorder()
+    connection = wire.OlympusConnection(reader, writer)  # type: ignore[arg-type]
+
+    file = await wire.ClientRpc(connection).echo(wire.EchoParams(file=FILE))
+    await connection.close()
+    print(writer.data.hex())
+    print(encode(file))
+
+
+def main() -> None:
+    numbers, file, empty_file, response = sys.argv[1:]
+    for value in (NUMBERS, FILE, EMPTY_FILE):
+        print(encode(value))
+    print(roundtrip(wire.Numbers, numbers))
+    print(roundtrip(wire.File, file))
+    print(roundtrip(wire.File, empty_file))
+    asyncio.run(echo(bytes.fromhex(response)))
+
+
+main()
@@ -0,0 +1,85 @@
+/**
+ * Encodes the values of `tests/wire.rs` and decodes its vectors with the generated `wire.ts`.
+ *
+ * The vectors are passed as hex, every result is printed as hex on its own line.
+ */
+
+import * as wire from "./wire";
+
+declare const process: { argv: string[]; exitCode?: number };
+
+const NUMBERS: wire.Numbers = {
+	u8: 0xab,
+	i8: -2,
+	u16: 0x1234,
+	i16: -2,
+	u32: 0xdeadbeef,
+	i32: -2,
+	u64: 0x0102030405060708n,
+	i64: -2n,
+	vu32: 300,
+	vi32: -3,
+	vi64: 64n,
+	vu8: 200,
+	vi8: -2,
+	vi16: -300,
+	vu64: 0xffffffffffffffffn,
+};
+const FILE: wire.File = {
+	path: "a/b",
+	content: [1, 0x0203],
+	owner: { name: "é" },
+	action: wire.Action.Encrypt,
+	label: { value: null },
+};
+const EMPTY_FILE: wire.File = { path: "", content: [], owner: null, action: wire.Action.Delete, label: null };
+
+function toHex(data: Uint8Array): string {
+	return Array.from(data, (byte) => byte.toString(16).padStart(2, "0")).join("");
+}
+
+function fromHex(hex: string): Uint8Array {
+	const data = new Uint8Array(hex.length / 2);
+	for (let idx = 0; idx < data.length; idx++) {
+		data[idx] = parseInt(hex.slice(2 * idx, 2 * idx + 2), 16);
+	}
+	return data;
+}
+
+function encode<T>(write: (w: wire.Writer, value: T) => void, value: T): string {
+	const w = new wire.Writer();
+	write(w, value);
+	return toHex(w.finish());
+}
+
+function roundtrip<T>(read: (r: wire.Reader) => T, write: (w: wire.Writer, value: T) => void, vector: string): string {
+	return encode(write, read(new wire.Reader(fromHex(vector))));
+}
+
+/** Calls `Echo` with `FILE`, the server is replaced by feeding `response` to the connection. */
+async function echo(response: Uint8Array): Promise<void> {
+	const written: Uint8Array[] = [];
+	const connection = new wire.OlympusConnection({ write: (data) => written.push(data) });
+
+	const call = new wire.ClientRpc(connection).echo({ file: FILE });
+	connection.receive(response);
+	const file = await call;
+	console.log(written.map(toHex).join(""));
+	console.log(encode(wire.encodeFile, file));
+}
+
+async function main(): Promise<void> {
+	const [numbers, file, emptyFile, response] = process.argv.slice(2);
+	console.log(encode(wire.encodeNumbers, NUMBERS));
+	console.log(encode(wire.encodeFile, FILE));
+	console.log(encode(wire.encodeFile, EMPTY_FILE));
+	console.log(roundtrip(wire.decodeNumbers, wire.encodeNumbers, numbers));
+	console.log(roundtrip(wire.decodeFile, wire.encodeFile, file));
+	console.log(roundtrip(wire.decodeFile, wire.encodeFile, emptyFile));
+	await echo(fromHex(response));
+}
+
+main().catch((error) => {
+	console.error(error);
+	process.exitCode = 1;
+});
@@ -1,6 +1,7 @@
 mod codec;
 mod fnv;
 mod proc;
+mod variant;
 mod varint;
 
 pub mod bytes {
@@ -10,8 +11,11 @@ pub mod bytes {
 pub use async_trait::async_trait;
 pub use eyre::eyre as error;
 pub use eyre::Result;
+#[cfg(feature = "serde")]
+pub use serde;
 
 pub use codec::*;
 pub use fnv::*;
 pub use proc::*;
+pub use variant::*;
 pub use varint::*;
@@ -10,6 +10,10 @@ pub trait ProcedureInput {
 }
 
 pub trait ProcedureOutput {
+	/// Whether a procedure returning this answers its caller. Only `()` doesn't, every other value is sent back even
+	/// when it serializes to nothing, like a struct without fields does.
+	const RESPONDS: bool = true;
+
 	fn serialize(&self) -> Result<BytesMut>;
 }
 
@@ -37,6 +41,8 @@ impl ProcedureInput for () {
 }
 
 impl ProcedureOutput for () {
+	const RESPONDS: bool = false;
+
 	fn serialize(&self) -> Result<BytesMut> {
 		Ok(BytesMut::new())
 	}
@@ -119,3 +125,15 @@ impl<T: ProcedureOutput> ProcedureOutput for Option<T> {
 		Ok(out)
 	}
 }
+
+impl<T: ProcedureInput> ProcedureInput for Box<T> {
+	fn deserialize(input: &mut BytesMut) -> Result<Self> {
+		Ok(Box::new(T::deserialize(input)?))
+	}
+}
+
+impl<T: ProcedureOutput> ProcedureOutput for Box<T> {
+	fn serialize(&self) -> Result<BytesMut> {
+		(**self).serialize()
+	}
+}
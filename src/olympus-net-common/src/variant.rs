@@ -0,0 +1,27 @@
+use std::fmt::{self, Display};
+
+/// Returned by the `TryFrom<u16>` and `FromStr` impls of generated enums for a tag or name without a variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnknownVariant {
+	Tag {
+		/// The name of the enum in the schema.
+		enum_name: &'static str,
+		tag: u16,
+	},
+	Name {
+		/// The name of the enum in the schema.
+		enum_name: &'static str,
+		name: String,
+	},
+}
+
+impl Display for UnknownVariant {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			UnknownVariant::Tag { enum_name, tag } => write!(f, "{enum_name} has no variant with the tag {tag}"),
+			UnknownVariant::Name { enum_name, name } => write!(f, "{enum_name} has no variant named '{name}'"),
+		}
+	}
+}
+
+impl std::error::Error for UnknownVariant {}
@@ -3,7 +3,8 @@ use std::ops::Deref;
 use crate::bytes::{Buf, BufMut, BytesMut};
 use zigzag::{ZigZagDecode, ZigZagEncode};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct Variable<T>(pub T);
 
 impl<T> Deref for Variable<T> {
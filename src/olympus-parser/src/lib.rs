@@ -1,7 +1,7 @@
-use std::{ops::Range, rc::Rc};
+use std::{ops::Range, sync::Arc};
 
 use olympus_lexer::{AsciiToken, IntToken, KeywordToken, Lexer, SpannedToken, Token, TypeToken};
-use olympus_spanned::{CodeSource, OlympusError, Spanned};
+use olympus_spanned::{CodeSource, ErrorCode, OlympusError, Spanned};
 
 #[derive(Debug)]
 pub struct ParsedEnumVariant {
@@ -57,7 +57,7 @@ pub struct ParsedProcedure {
 }
 
 pub struct Parser {
-	pub source: Rc<CodeSource>,
+	pub source: Arc<CodeSource>,
 	tokens: Vec<SpannedToken>,
 	token_idx: usize,
 	pub imports: Vec<Spanned<String>>,
@@ -101,14 +101,15 @@ impl Parser {
 	}
 
 	fn pop_must_match(&mut self, predicate: impl Fn(Token) -> bool, error: &str) -> Result<SpannedToken, OlympusError> {
-		let next = self.peek().ok_or(OlympusError::error(
-			self.source.clone(),
-			"Expected token after",
-			self.get_span(-1),
-		))?;
+		let next = self.peek().ok_or(
+			OlympusError::error(self.source.clone(), "Expected token after", self.get_span(-1))
+				.code(ErrorCode::UnexpectedEndOfInput),
+		)?;
 
 		if !predicate(next.value.clone()) {
-			return Err(OlympusError::error(self.source.clone(), error, self.get_span(0)));
+			return Err(
+				OlympusError::error(self.source.clone(), error, self.get_span(0)).code(ErrorCode::UnexpectedToken)
+			);
 		}
 
 		self.token_idx += 1;
@@ -141,7 +142,8 @@ impl Parser {
 						self.source.clone(),
 						&format!("Unexpected token: {token:?}"),
 						self.get_span(0),
-					))
+					)
+					.code(ErrorCode::UnexpectedToken))
 				}
 			}
 		}
@@ -175,7 +177,8 @@ impl Parser {
 						self.source.clone(),
 						&format!("Expected '}}' or Ident. Got: {token:?}"),
 						self.get_span(0),
-					))
+					)
+					.code(ErrorCode::UnexpectedToken))
 				}
 			}
 		}
@@ -206,11 +209,10 @@ impl Parser {
 			"Expected generic type",
 		)?;
 
-		let array_type = self.pop().ok_or(OlympusError::error(
-			self.source.clone(),
-			"Expected generic type",
-			self.get_span(-1),
-		))?;
+		let array_type = self.pop().ok_or(
+			OlympusError::error(self.source.clone(), "Expected generic type", self.get_span(-1))
+				.code(ErrorCode::UnexpectedEndOfInput),
+		)?;
 
 		let value = match array_type.value {
 			Token::Ident(ident) => Ok(Spanned::new(ParsedTypeKind::External(ident), array_type.span)),
@@ -236,11 +238,10 @@ impl Parser {
 					this_span,
 				)),
 			},
-			_ => Err(OlympusError::error(
-				self.source.clone(),
-				"Expected type",
-				self.get_span(0),
-			)),
+			_ => Err(
+				OlympusError::error(self.source.clone(), "Expected type", self.get_span(0))
+					.code(ErrorCode::ExpectedType),
+			),
 		}?;
 
 		self.pop_must_match(
@@ -283,11 +284,10 @@ impl Parser {
 				),
 			},
 			_ => {
-				return Err(OlympusError::error(
-					self.source.clone(),
-					"Expected type",
-					self.get_span(0),
-				))
+				return Err(
+					OlympusError::error(self.source.clone(), "Expected type", self.get_span(0))
+						.code(ErrorCode::ExpectedType),
+				)
 			}
 		};
 
@@ -302,11 +302,10 @@ impl Parser {
 				Token::Ident(ident) => {
 					self.pop_must_match(|t| matches!(t, Token::Arrow), "Expected '->' after ident")?;
 
-					let kind = self.pop().ok_or(OlympusError::error(
-						self.source.clone(),
-						"Expected type",
-						self.get_span(0),
-					))?;
+					let kind = self.pop().ok_or(
+						OlympusError::error(self.source.clone(), "Expected type", self.get_span(0))
+							.code(ErrorCode::UnexpectedEndOfInput),
+					)?;
 					let kind = self.parse_type(kind)?;
 
 					self.pop_must_match(
@@ -327,7 +326,8 @@ impl Parser {
 						self.source.clone(),
 						&format!("Expected '}}' or ident. Got: {token:?}"),
 						self.get_span(0),
-					))
+					)
+					.code(ErrorCode::UnexpectedToken))
 				}
 			}
 		}
@@ -362,11 +362,10 @@ impl Parser {
 				Token::Ident(ident) => {
 					self.pop_must_match(|t| matches!(t, Token::Arrow), "Expected '->' after ident")?;
 
-					let kind = self.pop().ok_or(OlympusError::error(
-						self.source.clone(),
-						"Expected type",
-						self.get_span(0),
-					))?;
+					let kind = self.pop().ok_or(
+						OlympusError::error(self.source.clone(), "Expected type", self.get_span(0))
+							.code(ErrorCode::UnexpectedEndOfInput),
+					)?;
 					let kind = self.parse_type(kind)?;
 
 					params.push(ParsedProcedureParam {
@@ -375,13 +374,14 @@ impl Parser {
 					});
 				}
 				Token::Ascii(AsciiToken::CloseParen) => break,
-				Token::Ascii(AsciiToken::Comma) => continue,
+				Token::Ascii(AsciiToken::Comma) => {}
 				token => {
 					return Err(OlympusError::error(
 						self.source.clone(),
 						&format!("Expected ident or ')'. Got: {token:?}"),
 						self.get_span(0),
-					))
+					)
+					.code(ErrorCode::UnexpectedToken))
 				}
 			}
 		}
@@ -396,11 +396,10 @@ impl Parser {
 		} else {
 			self.pop_must_match(|t| matches!(t, Token::Arrow), "Expected '->' after params")?;
 
-			let return_kind = self.pop().ok_or(OlympusError::error(
-				self.source.clone(),
-				"Expected type",
-				self.get_span(0),
-			))?;
+			let return_kind = self.pop().ok_or(
+				OlympusError::error(self.source.clone(), "Expected type", self.get_span(0))
+					.code(ErrorCode::UnexpectedEndOfInput),
+			)?;
 			let return_kind = self.parse_type(return_kind)?;
 
 			self.pop_must_match(
@@ -433,9 +432,15 @@ macro_rules! next_must_match {
 					let span = $self.get_span(0);
 					Spanned::new(v, span)
 				}
-				_ => return Err(OlympusError::error($self.source.clone(), $expected, $self.get_span(0))),
+				_ => {
+					return Err(OlympusError::error($self.source.clone(), $expected, $self.get_span(0))
+						.code(ErrorCode::UnexpectedToken))
+				}
 			},
-			_ => return Err(OlympusError::error($self.source.clone(), $expected, $self.get_span(0))),
+			_ => {
+				return Err(OlympusError::error($self.source.clone(), $expected, $self.get_span(0))
+					.code(ErrorCode::UnexpectedToken))
+			}
 		}
 	}};
 }
@@ -103,7 +103,8 @@ where
 			let procedure_name_hash = frame.get_u64();
 
 			match Self::run_procedure(context.clone(), procedures.clone(), procedure_name_hash, frame).await? {
-				Some((response, _)) if !response.is_empty() => {
+				// callers wait for a response from every procedure that returns something, even an empty one
+				Some((Some(response), _)) => {
 					let mut out = BytesMut::new();
 					out.reserve(size_of::<u64>() + response.len());
 					out.put_u64(procedure_name_hash);
@@ -111,7 +112,7 @@ where
 
 					framed_write.send(out).await?;
 				}
-				Some(_) => {}
+				Some((None, _)) => {}
 				None => {
 					eprintln!("Procedure with hash ({procedure_name_hash}) not found but client '{session_id}' tried to call it");
 				}
@@ -126,7 +127,7 @@ where
 		procedures: ArcMut<HandlersMap<Ctx>>,
 		name_hash: u64,
 		input: BytesMut,
-	) -> Result<Option<(BytesMut, &'static str)>> {
+	) -> Result<Option<(Option<BytesMut>, &'static str)>> {
 		let procedure = procedures.lock().await;
 		if let Some((procedure, name)) = procedure.get(&name_hash) {
 			Ok(Some((procedure.call(context, input).await?, name)))
@@ -5,7 +5,8 @@ pub use olympus_net_common::{bytes::BytesMut, ProcedureInput, ProcedureOutput, R
 
 #[async_trait]
 pub trait Procedure<Ctx>: Send {
-	async fn call(&self, context: Ctx, input: BytesMut) -> Result<BytesMut>;
+	/// The serialized response, `None` if the procedure doesn't answer.
+	async fn call(&self, context: Ctx, input: BytesMut) -> Result<Option<BytesMut>>;
 }
 
 #[derive(Clone)]
@@ -26,7 +27,8 @@ where
 	Res: ProcedureOutput,
 	I: ProcedureInput + Send + Sync,
 {
-	async fn call(&self, context: Ctx, mut input: BytesMut) -> Result<BytesMut> {
-		Ok(self.0(context, I::deserialize(&mut input)?).await?.serialize()?)
+	async fn call(&self, context: Ctx, mut input: BytesMut) -> Result<Option<BytesMut>> {
+		let response = self.0(context, I::deserialize(&mut input)?).await?.serialize()?;
+		Ok(Res::RESPONDS.then_some(response))
 	}
 }
@@ -0,0 +1,104 @@
+use std::fmt::{Display, Formatter};
+
+macro_rules! error_codes {
+	($($variant:ident = $code:literal: $title:literal,)*) => {
+		/// A stable identifier for every kind of diagnostic, e.g. `OL0012`.
+		///
+		/// Codes are never reused or renumbered, so docs and configs can refer to them.
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub enum ErrorCode {
+			$($variant,)*
+		}
+
+		impl ErrorCode {
+			pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)*];
+
+			#[must_use]
+			pub fn code(self) -> &'static str {
+				match self {
+					$(ErrorCode::$variant => $code,)*
+				}
+			}
+
+			/// A short summary of the diagnostic.
+			#[must_use]
+			pub fn title(self) -> &'static str {
+				match self {
+					$(ErrorCode::$variant => $title,)*
+				}
+			}
+
+			/// The long-form explanation, with examples of what triggers the diagnostic and how to fix it.
+			#[must_use]
+			pub fn explanation(self) -> &'static str {
+				match self {
+					$(ErrorCode::$variant => include_str!(concat!("../explanations/", $code, ".md")),)*
+				}
+			}
+		}
+	};
+}
+
+error_codes! {
+	Internal = "OL0000": "internal compiler error",
+	UnexpectedCharacter = "OL0001": "unexpected character",
+	UnknownBuiltin = "OL0002": "unknown builtin type",
+	EnumTagOutOfRange = "OL0003": "enum tag out of range",
+	UnexpectedEndOfInput = "OL0004": "unexpected end of input",
+	UnexpectedToken = "OL0005": "unexpected token",
+	ExpectedType = "OL0006": "expected a type",
+	DuplicateEnumVariant = "OL0010": "duplicate enum variant",
+	DuplicateEnumValue = "OL0011": "duplicate enum variant value",
+	DuplicateStructField = "OL0012": "duplicate struct field",
+	DuplicateProcParam = "OL0013": "duplicate proc parameter",
+	DuplicateType = "OL0014": "duplicate enum or struct",
+	DuplicateProc = "OL0015": "duplicate proc",
+	TypeNotFound = "OL0016": "type not found",
+	TypeNotImported = "OL0017": "type declared in a file that isn't imported",
+	RecursiveType = "OL0018": "recursive type has infinite size",
+	UnreadableFile = "OL0020": "couldn't read schema file",
+	ImportNotFound = "OL0021": "imported file not found",
+	NameCollision = "OL0030": "generated names collide",
+	InvalidDerive = "OL0031": "invalid derive",
+	EmptyEnum = "OL0100": "enum without variants",
+	UnusedType = "OL0101": "unused enum or struct",
+	UnusedImport = "OL0102": "unused import",
+	NonPascalCaseName = "OL0103": "name isn't PascalCase",
+}
+
+impl ErrorCode {
+	/// Looks a code up by its string form, ignoring case.
+	#[must_use]
+	pub fn from_code(code: &str) -> Option<Self> {
+		Self::ALL
+			.iter()
+			.copied()
+			.find(|it| it.code().eq_ignore_ascii_case(code))
+	}
+}
+
+impl Display for ErrorCode {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.code())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+
+	use super::ErrorCode;
+
+	#[test]
+	fn codes_are_unique_and_explained() {
+		let mut seen = HashSet::new();
+		for code in ErrorCode::ALL {
+			assert!(seen.insert(code.code()), "{code} is used twice");
+			assert!(
+				code.explanation().starts_with(&format!("# {code}")),
+				"explanation for {code} should start with its code"
+			);
+			assert_eq!(ErrorCode::from_code(&code.code().to_lowercase()), Some(*code));
+		}
+	}
+}
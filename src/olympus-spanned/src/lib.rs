@@ -1,5 +1,8 @@
+mod codes;
+
 pub use ariadne::Color as ErrorColor;
-use std::{ops::Range, rc::Rc};
+pub use codes::ErrorCode;
+use std::{ops::Range, sync::Arc};
 
 #[derive(Debug, Clone)]
 pub struct CodeSource {
@@ -7,20 +10,36 @@ pub struct CodeSource {
 	pub src: String,
 }
 
+#[derive(Debug)]
 pub struct OlympusErrorLabel {
-	pub source: Rc<CodeSource>,
+	pub source: Arc<CodeSource>,
 	pub message: String,
 	pub span: Range<usize>,
 	pub color: ErrorColor,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+	Error,
+	Warning,
+}
+
+#[derive(Debug)]
 pub struct OlympusError {
 	pub subject: String,
 	pub labels: Vec<OlympusErrorLabel>,
+	pub severity: Severity,
+	pub code: Option<ErrorCode>,
+	/// The name of the lint that produced this diagnostic, if any.
+	pub lint: Option<&'static str>,
 }
 
 impl OlympusError {
-	pub fn error<S: ToOwned<Owned = String> + ?Sized>(source: Rc<CodeSource>, subject: &S, span: Range<usize>) -> Self {
+	pub fn error<S: ToOwned<Owned = String> + ?Sized>(
+		source: Arc<CodeSource>,
+		subject: &S,
+		span: Range<usize>,
+	) -> Self {
 		Self {
 			subject: subject.to_owned(),
 			labels: vec![OlympusErrorLabel {
@@ -29,6 +48,9 @@ impl OlympusError {
 				span,
 				color: ErrorColor::Red,
 			}],
+			severity: Severity::Error,
+			code: None,
+			lint: None,
 		}
 	}
 
@@ -36,11 +58,37 @@ impl OlympusError {
 		Self {
 			subject: subject.to_owned(),
 			labels: vec![],
+			severity: Severity::Error,
+			code: None,
+			lint: None,
 		}
 	}
 
 	#[must_use]
-	pub fn span(mut self, source: Rc<CodeSource>, span: Range<usize>, color: ErrorColor) -> Self {
+	pub fn severity(mut self, severity: Severity) -> Self {
+		self.severity = severity;
+		self
+	}
+
+	#[must_use]
+	pub fn code(mut self, code: ErrorCode) -> Self {
+		self.code = Some(code);
+		self
+	}
+
+	#[must_use]
+	pub fn lint(mut self, lint: &'static str) -> Self {
+		self.lint = Some(lint);
+		self
+	}
+
+	#[must_use]
+	pub fn is_error(&self) -> bool {
+		self.severity == Severity::Error
+	}
+
+	#[must_use]
+	pub fn span(mut self, source: Arc<CodeSource>, span: Range<usize>, color: ErrorColor) -> Self {
 		self.labels.push(OlympusErrorLabel {
 			source,
 			message: self.subject.clone(),
@@ -53,7 +101,7 @@ impl OlympusError {
 	#[must_use]
 	pub fn label<S: ToOwned<Owned = String> + ?Sized>(
 		mut self,
-		source: Rc<CodeSource>,
+		source: Arc<CodeSource>,
 		message: &S,
 		span: Range<usize>,
 		color: ErrorColor,
@@ -0,0 +1,331 @@
+use std::collections::{HashMap, HashSet};
+
+use olympus_spanned::{ErrorCode, ErrorColor, OlympusError, Spanned};
+
+use crate::{ResolvedSchema, ResolvedStruct, ResolvedStructField, ResolvedType, StructId};
+
+/// How a struct field reaches the type it references.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Containment {
+	/// The type is stored inline, e.g. `next->Node;`
+	Direct,
+	/// The type is stored inline behind an `@option`, e.g. `next->@option[Node];`
+	Option,
+	/// The type is stored behind an `@array`, which is always heap allocated.
+	Array,
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeEdge {
+	pub from: StructId,
+	pub to: StructId,
+	/// Index of the field in `from` that references `to`.
+	pub field: usize,
+	pub containment: Containment,
+}
+
+/// A graph of which structs contain which other structs through their fields.
+pub struct TypeGraph {
+	edges: Vec<Vec<TypeEdge>>,
+}
+
+fn collect_references(kind: &ResolvedType, containment: Containment, out: &mut Vec<(StructId, Containment)>) {
+	match kind {
+		ResolvedType::Struct { id, .. } => out.push((*id, containment)),
+		ResolvedType::Option(inner) => collect_references(inner, containment.max(Containment::Option), out),
+		ResolvedType::Array(inner) => collect_references(inner, Containment::Array, out),
+		_ => {}
+	}
+}
+
+impl TypeGraph {
+	#[must_use]
+	pub fn new(structs: &[ResolvedStruct]) -> Self {
+		let edges = structs
+			.iter()
+			.enumerate()
+			.map(|(from, ResolvedStruct { fields, .. })| {
+				let mut struct_edges = Vec::new();
+				for (field, ResolvedStructField { kind, .. }) in fields.iter().enumerate() {
+					let mut references = Vec::new();
+					collect_references(&kind.value, Containment::Direct, &mut references);
+
+					struct_edges.extend(references.into_iter().map(|(to, containment)| TypeEdge {
+						from: StructId(from),
+						to,
+						field,
+						containment,
+					}));
+				}
+				struct_edges
+			})
+			.collect();
+
+		Self { edges }
+	}
+
+	fn edges_of(&self, id: StructId) -> &[TypeEdge] {
+		&self.edges[id.0]
+	}
+
+	fn ids(&self) -> impl Iterator<Item = StructId> {
+		(0..self.edges.len()).map(StructId)
+	}
+
+	/// Finds a cycle of structs that directly contain each other, which would have infinite size.
+	#[must_use]
+	pub fn find_unguarded_cycle(&self) -> Option<Vec<TypeEdge>> {
+		#[derive(Clone, Copy, PartialEq, Eq)]
+		enum Visit {
+			InProgress,
+			Done,
+		}
+
+		fn visit<'a>(
+			graph: &'a TypeGraph,
+			id: StructId,
+			state: &mut HashMap<StructId, Visit>,
+			path: &mut Vec<&'a TypeEdge>,
+		) -> Option<Vec<TypeEdge>> {
+			state.insert(id, Visit::InProgress);
+
+			for edge in graph.edges_of(id) {
+				if edge.containment != Containment::Direct {
+					continue;
+				}
+
+				path.push(edge);
+				match state.get(&edge.to) {
+					Some(Visit::InProgress) => {
+						let start = path.iter().position(|e| e.from == edge.to).unwrap_or(0);
+						return Some(path[start..].iter().map(|e| (*e).clone()).collect());
+					}
+					Some(Visit::Done) => {}
+					None => {
+						if let Some(cycle) = visit(graph, edge.to, state, path) {
+							return Some(cycle);
+						}
+					}
+				}
+				path.pop();
+			}
+
+			state.insert(id, Visit::Done);
+			None
+		}
+
+		let mut state = HashMap::new();
+		for id in self.ids() {
+			if state.contains_key(&id) {
+				continue;
+			}
+
+			if let Some(cycle) = visit(self, id, &mut state, &mut Vec::new()) {
+				return Some(cycle);
+			}
+		}
+
+		None
+	}
+
+	/// Returns `(struct, field index)` for every field that has to be stored behind a pointer for the containing
+	/// struct to have a known size.
+	///
+	/// A field needs indirection when it holds another struct inline through an `@option` and both structs are part
+	/// of the same cycle. Fields reached through an `@array` never need it.
+	#[must_use]
+	pub fn indirect_fields(&self) -> HashSet<(StructId, usize)> {
+		let components = self.inline_components();
+
+		self.ids()
+			.flat_map(|id| self.edges_of(id))
+			.filter(|edge| edge.containment == Containment::Option && components[&edge.from] == components[&edge.to])
+			.map(|edge| (edge.from, edge.field))
+			.collect()
+	}
+
+	/// Tarjan's strongly connected components over every edge that stores a struct inline.
+	fn inline_components(&self) -> HashMap<StructId, usize> {
+		struct State {
+			index: usize,
+			indices: HashMap<StructId, usize>,
+			low_links: HashMap<StructId, usize>,
+			stack: Vec<StructId>,
+			on_stack: HashSet<StructId>,
+			components: HashMap<StructId, usize>,
+			component_count: usize,
+		}
+
+		fn connect(graph: &TypeGraph, id: StructId, state: &mut State) {
+			state.indices.insert(id, state.index);
+			state.low_links.insert(id, state.index);
+			state.index += 1;
+			state.stack.push(id);
+			state.on_stack.insert(id);
+
+			for edge in graph.edges_of(id) {
+				if edge.containment == Containment::Array {
+					continue;
+				}
+
+				let to = edge.to;
+				if !state.indices.contains_key(&to) {
+					connect(graph, to, state);
+					let low = state.low_links[&id].min(state.low_links[&to]);
+					state.low_links.insert(id, low);
+				} else if state.on_stack.contains(&to) {
+					let low = state.low_links[&id].min(state.indices[&to]);
+					state.low_links.insert(id, low);
+				}
+			}
+
+			if state.low_links[&id] == state.indices[&id] {
+				while let Some(member) = state.stack.pop() {
+					state.on_stack.remove(&member);
+					state.components.insert(member, state.component_count);
+					if member == id {
+						break;
+					}
+				}
+				state.component_count += 1;
+			}
+		}
+
+		let mut state = State {
+			index: 0,
+			indices: HashMap::new(),
+			low_links: HashMap::new(),
+			stack: Vec::new(),
+			on_stack: HashSet::new(),
+			components: HashMap::new(),
+			component_count: 0,
+		};
+
+		for id in self.ids() {
+			if !state.indices.contains_key(&id) {
+				connect(self, id, &mut state);
+			}
+		}
+
+		state.components
+	}
+}
+
+pub(crate) fn cycle_error(schema: &ResolvedSchema, cycle: &[TypeEdge]) -> OlympusError {
+	let ident = |id: StructId| schema[id].ident.value.as_str();
+	let path = cycle
+		.iter()
+		.map(|edge| ident(edge.from))
+		.chain(cycle.first().map(|edge| ident(edge.from)))
+		.collect::<Vec<_>>()
+		.join(" -> ");
+
+	let mut error =
+		OlympusError::new(&format!("Recursive type has infinite size: {path}")).code(ErrorCode::RecursiveType);
+	for (idx, edge) in cycle.iter().enumerate() {
+		let from = &schema[edge.from];
+		let Spanned { span, .. } = &from.fields[edge.field].kind;
+		let (message, color) = if idx == 0 {
+			(
+				format!(
+					"'{}' contains '{}' here, wrap it in @option or @array to break the cycle",
+					ident(edge.from),
+					ident(edge.to)
+				),
+				ErrorColor::Red,
+			)
+		} else {
+			(
+				format!("'{}' contains '{}' here", ident(edge.from), ident(edge.to)),
+				ErrorColor::Yellow,
+			)
+		};
+		error = error.label(from.source.clone(), &message, span.clone(), color);
+	}
+
+	error
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use olympus_spanned::{CodeSource, Spanned};
+
+	use super::TypeGraph;
+	use crate::{ResolvedStruct, ResolvedStructField, ResolvedType, StructId};
+
+	fn structs(fields: &[&[ResolvedType]]) -> Vec<ResolvedStruct> {
+		let source = Arc::new(CodeSource {
+			file_name: "test.ol".to_string(),
+			src: String::new(),
+		});
+		fields
+			.iter()
+			.enumerate()
+			.map(|(i, fields)| ResolvedStruct {
+				ident: Spanned::new(format!("S{i}"), 0..0),
+				source: source.clone(),
+				fields: fields
+					.iter()
+					.enumerate()
+					.map(|(i, kind)| ResolvedStructField {
+						ident: Spanned::new(format!("f{i}"), 0..0),
+						kind: Spanned::new(kind.clone(), 0..0),
+					})
+					.collect(),
+			})
+			.collect()
+	}
+
+	fn direct(id: usize) -> ResolvedType {
+		ResolvedType::Struct {
+			id: StructId(id),
+			indirect: false,
+		}
+	}
+
+	fn option(kind: ResolvedType) -> ResolvedType {
+		ResolvedType::Option(Box::new(kind))
+	}
+
+	fn array(kind: ResolvedType) -> ResolvedType {
+		ResolvedType::Array(Box::new(kind))
+	}
+
+	#[test]
+	fn rejects_direct_cycles() {
+		let graph = TypeGraph::new(&structs(&[&[direct(1)], &[direct(2)], &[direct(0)]]));
+		let cycle = graph.find_unguarded_cycle().unwrap();
+		let path = cycle.iter().map(|edge| edge.from.0).collect::<Vec<_>>();
+		assert_eq!(path, [0, 1, 2]);
+
+		assert!(TypeGraph::new(&structs(&[&[direct(0)]]))
+			.find_unguarded_cycle()
+			.is_some());
+	}
+
+	#[test]
+	fn allows_guarded_cycles() {
+		let graph = TypeGraph::new(&structs(&[&[option(direct(0)), array(direct(0))]]));
+		assert!(graph.find_unguarded_cycle().is_none());
+
+		let indirect = graph.indirect_fields();
+		assert!(indirect.contains(&(StructId(0), 0)));
+		assert!(!indirect.contains(&(StructId(0), 1)));
+	}
+
+	#[test]
+	fn only_boxes_fields_inside_cycles() {
+		let graph = TypeGraph::new(&structs(&[
+			&[option(direct(1))],
+			&[option(direct(0)), option(direct(2))],
+			&[ResolvedType::String],
+		]));
+		assert!(graph.find_unguarded_cycle().is_none());
+
+		let indirect = graph.indirect_fields();
+		assert_eq!(indirect.len(), 2);
+		assert!(!indirect.contains(&(StructId(1), 1)));
+	}
+}
@@ -0,0 +1,351 @@
+use std::{
+	collections::{HashMap, HashSet},
+	ops::Index,
+	sync::Arc,
+};
+
+use olympus_lexer::IntToken;
+use olympus_spanned::{CodeSource, ErrorCode, OlympusError, Spanned};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntKind {
+	Int8,
+	Int16,
+	Int32,
+	Int64,
+	UInt8,
+	UInt16,
+	UInt32,
+	UInt64,
+}
+
+impl IntKind {
+	#[must_use]
+	pub fn bits(self) -> u32 {
+		match self {
+			IntKind::Int8 | IntKind::UInt8 => 8,
+			IntKind::Int16 | IntKind::UInt16 => 16,
+			IntKind::Int32 | IntKind::UInt32 => 32,
+			IntKind::Int64 | IntKind::UInt64 => 64,
+		}
+	}
+
+	#[must_use]
+	pub fn is_signed(self) -> bool {
+		matches!(self, IntKind::Int8 | IntKind::Int16 | IntKind::Int32 | IntKind::Int64)
+	}
+}
+
+impl From<&IntToken> for IntKind {
+	fn from(value: &IntToken) -> Self {
+		match value {
+			IntToken::Int8 => IntKind::Int8,
+			IntToken::Int16 => IntKind::Int16,
+			IntToken::Int32 => IntKind::Int32,
+			IntToken::Int64 => IntKind::Int64,
+			IntToken::UInt8 => IntKind::UInt8,
+			IntToken::UInt16 => IntKind::UInt16,
+			IntToken::UInt32 => IntKind::UInt32,
+			IntToken::UInt64 => IntKind::UInt64,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EnumId(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StructId(pub usize);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ResolvedType {
+	Int(IntKind),
+	VariableInt(IntKind),
+	String,
+	Array(Box<ResolvedType>),
+	Option(Box<ResolvedType>),
+	Enum(EnumId),
+	Struct {
+		id: StructId,
+		/// Whether this reference closes a recursive cycle and has to be stored behind a pointer.
+		indirect: bool,
+	},
+}
+
+impl ResolvedType {
+	/// Calls `f` for this type and every type nested inside of it.
+	pub fn walk(&self, f: &mut impl FnMut(&ResolvedType)) {
+		f(self);
+		match self {
+			ResolvedType::Array(inner) | ResolvedType::Option(inner) => inner.walk(f),
+			_ => {}
+		}
+	}
+
+	/// This type with every enum and struct reference replaced by the id `enums` or `structs` maps it to.
+	fn map_ids(&self, enums: &HashMap<EnumId, EnumId>, structs: &HashMap<StructId, StructId>) -> ResolvedType {
+		match self {
+			ResolvedType::Array(inner) => ResolvedType::Array(Box::new(inner.map_ids(enums, structs))),
+			ResolvedType::Option(inner) => ResolvedType::Option(Box::new(inner.map_ids(enums, structs))),
+			ResolvedType::Enum(id) => ResolvedType::Enum(enums[id]),
+			ResolvedType::Struct { id, indirect } => ResolvedType::Struct {
+				id: structs[id],
+				indirect: *indirect,
+			},
+			_ => self.clone(),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedEnumVariant {
+	pub ident: Spanned<String>,
+	pub value: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedEnum {
+	pub ident: Spanned<String>,
+	pub source: Arc<CodeSource>,
+	pub variants: Vec<ResolvedEnumVariant>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedStructField {
+	pub ident: Spanned<String>,
+	pub kind: Spanned<ResolvedType>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedStruct {
+	pub ident: Spanned<String>,
+	pub source: Arc<CodeSource>,
+	pub fields: Vec<ResolvedStructField>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedProcedureParam {
+	pub ident: Spanned<String>,
+	pub kind: Spanned<ResolvedType>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedProcedure {
+	pub ident: Spanned<String>,
+	pub source: Arc<CodeSource>,
+	pub params: Vec<ResolvedProcedureParam>,
+	/// `None` when the procedure doesn't return anything.
+	pub return_kind: Option<Spanned<ResolvedType>>,
+}
+
+/// A verified schema where every type reference is bound to its declaration.
+///
+/// Declarations from every imported file are merged into one schema, in the order the files were loaded.
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedSchema {
+	pub enums: Vec<ResolvedEnum>,
+	pub structs: Vec<ResolvedStruct>,
+	pub procedures: Vec<ResolvedProcedure>,
+}
+
+impl ResolvedSchema {
+	/// The declarations of the files named `file_names`, ordered like the files and then like they were declared.
+	///
+	/// Cuts the schema of a file and its imports out of a schema several files were verified into, which is the schema
+	/// verifying that file on its own results in. Every type they use has to be declared in one of them.
+	#[must_use]
+	pub fn subset(&self, file_names: &[String]) -> ResolvedSchema {
+		fn kept<T>(items: &[T], position: impl Fn(&T) -> Option<usize>) -> Vec<usize> {
+			let mut kept = items
+				.iter()
+				.enumerate()
+				.filter_map(|(idx, item)| Some((position(item)?, idx)))
+				.collect::<Vec<_>>();
+			kept.sort_unstable();
+			kept.into_iter().map(|(_, idx)| idx).collect()
+		}
+
+		let position = |source: &CodeSource| file_names.iter().position(|name| *name == source.file_name);
+		let enums = kept(&self.enums, |r#enum| position(&r#enum.source));
+		let structs = kept(&self.structs, |r#struct| position(&r#struct.source));
+		let procedures = kept(&self.procedures, |proc| position(&proc.source));
+
+		let enum_ids = enums
+			.iter()
+			.enumerate()
+			.map(|(new, old)| (EnumId(*old), EnumId(new)))
+			.collect::<HashMap<_, _>>();
+		let struct_ids = structs
+			.iter()
+			.enumerate()
+			.map(|(new, old)| (StructId(*old), StructId(new)))
+			.collect::<HashMap<_, _>>();
+
+		ResolvedSchema {
+			enums: enums.into_iter().map(|idx| self.enums[idx].clone()).collect(),
+			structs: structs
+				.into_iter()
+				.map(|idx| self.structs[idx].map_ids(&enum_ids, &struct_ids))
+				.collect(),
+			procedures: procedures
+				.into_iter()
+				.map(|idx| self.procedures[idx].map_ids(&enum_ids, &struct_ids))
+				.collect(),
+		}
+	}
+
+	/// Merges schemas that were verified on their own into one, like verifying their files together would.
+	///
+	/// A file several of them were loaded from, like a shared import, is only taken from the first one. Errors if two
+	/// files declare the same type or procedure.
+	pub fn merge(schemas: &[&ResolvedSchema]) -> Result<ResolvedSchema, OlympusError> {
+		// the files each schema contributes
+		let mut merged_files = HashSet::<&str>::new();
+		let mut contributed = Vec::with_capacity(schemas.len());
+		for schema in schemas {
+			let files = schema
+				.sources()
+				.map(|source| source.file_name.as_str())
+				.filter(|file_name| !merged_files.contains(file_name))
+				.collect::<HashSet<_>>();
+			merged_files.extend(&files);
+			contributed.push(files);
+		}
+
+		let mut merged = ResolvedSchema::default();
+		for (schema, files) in schemas.iter().zip(&contributed) {
+			let kept = |source: &CodeSource| files.contains(source.file_name.as_str());
+			let enums = schema.enums.iter().filter(|r#enum| kept(&r#enum.source));
+			merged.enums.extend(enums.cloned());
+			let structs = schema.structs.iter().filter(|r#struct| kept(&r#struct.source));
+			merged.structs.extend(structs.cloned());
+			let procedures = schema.procedures.iter().filter(|proc| kept(&proc.source));
+			merged.procedures.extend(procedures.cloned());
+		}
+
+		let type_idents = merged.enums.iter().map(|r#enum| (&r#enum.ident, &r#enum.source)).chain(
+			merged
+				.structs
+				.iter()
+				.map(|r#struct| (&r#struct.ident, &r#struct.source)),
+		);
+		crate::find_global_duplicates(
+			type_idents,
+			"Duplicate enum/struct ident found",
+			ErrorCode::DuplicateType,
+		)?;
+		let proc_idents = merged.procedures.iter().map(|proc| (&proc.ident, &proc.source));
+		crate::find_global_duplicates(proc_idents, "Duplicate proc ident found", ErrorCode::DuplicateProc)?;
+
+		// every type has its own name now, so the ids of each schema are mapped by name
+		let enum_names = merged
+			.enums
+			.iter()
+			.enumerate()
+			.map(|(idx, r#enum)| (r#enum.ident.value.as_str(), EnumId(idx)))
+			.collect::<HashMap<_, _>>();
+		let struct_names = merged
+			.structs
+			.iter()
+			.enumerate()
+			.map(|(idx, r#struct)| (r#struct.ident.value.as_str(), StructId(idx)))
+			.collect::<HashMap<_, _>>();
+
+		let (mut structs, mut procedures) = (Vec::new(), Vec::new());
+		for (schema, files) in schemas.iter().zip(&contributed) {
+			let enum_ids = schema
+				.enums
+				.iter()
+				.enumerate()
+				.map(|(idx, r#enum)| (EnumId(idx), enum_names[r#enum.ident.value.as_str()]))
+				.collect::<HashMap<_, _>>();
+			let struct_ids = schema
+				.structs
+				.iter()
+				.enumerate()
+				.map(|(idx, r#struct)| (StructId(idx), struct_names[r#struct.ident.value.as_str()]))
+				.collect::<HashMap<_, _>>();
+
+			let kept = |source: &CodeSource| files.contains(source.file_name.as_str());
+			let kept_structs = schema.structs.iter().filter(|r#struct| kept(&r#struct.source));
+			structs.extend(kept_structs.map(|r#struct| r#struct.map_ids(&enum_ids, &struct_ids)));
+			let kept_procedures = schema.procedures.iter().filter(|proc| kept(&proc.source));
+			procedures.extend(kept_procedures.map(|proc| proc.map_ids(&enum_ids, &struct_ids)));
+		}
+		merged.structs = structs;
+		merged.procedures = procedures;
+
+		Ok(merged)
+	}
+
+	/// The file of every declaration.
+	fn sources(&self) -> impl Iterator<Item = &Arc<CodeSource>> {
+		self.enums
+			.iter()
+			.map(|r#enum| &r#enum.source)
+			.chain(self.structs.iter().map(|r#struct| &r#struct.source))
+			.chain(self.procedures.iter().map(|proc| &proc.source))
+	}
+}
+
+impl ResolvedStruct {
+	fn map_ids(&self, enums: &HashMap<EnumId, EnumId>, structs: &HashMap<StructId, StructId>) -> ResolvedStruct {
+		ResolvedStruct {
+			ident: self.ident.clone(),
+			source: self.source.clone(),
+			fields: self
+				.fields
+				.iter()
+				.map(|field| ResolvedStructField {
+					ident: field.ident.clone(),
+					kind: map_spanned_ids(&field.kind, enums, structs),
+				})
+				.collect(),
+		}
+	}
+}
+
+impl ResolvedProcedure {
+	fn map_ids(&self, enums: &HashMap<EnumId, EnumId>, structs: &HashMap<StructId, StructId>) -> ResolvedProcedure {
+		ResolvedProcedure {
+			ident: self.ident.clone(),
+			source: self.source.clone(),
+			params: self
+				.params
+				.iter()
+				.map(|param| ResolvedProcedureParam {
+					ident: param.ident.clone(),
+					kind: map_spanned_ids(&param.kind, enums, structs),
+				})
+				.collect(),
+			return_kind: self
+				.return_kind
+				.as_ref()
+				.map(|kind| map_spanned_ids(kind, enums, structs)),
+		}
+	}
+}
+
+fn map_spanned_ids(
+	kind: &Spanned<ResolvedType>,
+	enums: &HashMap<EnumId, EnumId>,
+	structs: &HashMap<StructId, StructId>,
+) -> Spanned<ResolvedType> {
+	Spanned::new(kind.value.map_ids(enums, structs), kind.span.clone())
+}
+
+impl Index<EnumId> for ResolvedSchema {
+	type Output = ResolvedEnum;
+
+	fn index(&self, index: EnumId) -> &Self::Output {
+		&self.enums[index.0]
+	}
+}
+
+impl Index<StructId> for ResolvedSchema {
+	type Output = ResolvedStruct;
+
+	fn index(&self, index: StructId) -> &Self::Output {
+		&self.structs[index.0]
+	}
+}
@@ -1,10 +1,25 @@
-use std::{collections::HashMap, rc::Rc};
+mod graph;
+mod ir;
+mod lints;
+
+use std::{collections::HashMap, ops::Range, sync::Arc};
 
 use olympus_parser::{
-	ParsedBultin, ParsedEnum, ParsedEnumVariant, ParsedProcedure, ParsedProcedureParam, ParsedStruct,
-	ParsedStructField, ParsedTypeKind, Parser,
+	ParsedBultin, ParsedEnum, ParsedEnumVariant, ParsedProcedureParam, ParsedStruct, ParsedStructField, ParsedTypeKind,
+	Parser,
 };
-use olympus_spanned::{CodeSource, ErrorColor, OlympusError, Spanned};
+use olympus_spanned::{CodeSource, ErrorCode, ErrorColor, OlympusError, Spanned};
+
+pub use graph::*;
+pub use ir::*;
+pub use lints::*;
+
+/// A parsed file together with the files its imports point to.
+pub struct SchemaFile {
+	pub parser: Parser,
+	/// Index into the file list for every entry of `parser.imports`, in the same order.
+	pub imports: Vec<usize>,
+}
 
 fn find_duplicate_ident(idents: &[Spanned<String>]) -> Option<(Spanned<String>, Spanned<String>)> {
 	let mut idents_map = HashMap::<String, (Spanned<String>, Option<Spanned<String>>)>::new();
@@ -26,11 +41,12 @@ fn find_duplicate_ident(idents: &[Spanned<String>]) -> Option<(Spanned<String>,
 	None
 }
 
-fn find_enum_variant_duplicates(source: Rc<CodeSource>, variants: &[ParsedEnumVariant]) -> Result<(), OlympusError> {
+fn find_enum_variant_duplicates(source: Arc<CodeSource>, variants: &[ParsedEnumVariant]) -> Result<(), OlympusError> {
 	if let Some((original, dup)) = find_duplicate_ident(&variants.iter().map(|v| v.ident.clone()).collect::<Vec<_>>()) {
 		return Err(OlympusError::new("Duplicate variant ident found")
 			.label(source.clone(), "Original here", original.span, ErrorColor::Yellow)
-			.label(source, "Duplicate here", dup.span, ErrorColor::Red));
+			.label(source, "Duplicate here", dup.span, ErrorColor::Red)
+			.code(ErrorCode::DuplicateEnumVariant));
 	}
 
 	let mut values = HashMap::<i16, (Spanned<String>, Option<Spanned<String>>)>::new();
@@ -47,87 +63,152 @@ fn find_enum_variant_duplicates(source: Rc<CodeSource>, variants: &[ParsedEnumVa
 		if let Some(dup_ident) = dup_ident {
 			return Err(OlympusError::new("Duplicate variant value found")
 				.label(source.clone(), "Original here", original_ident.span, ErrorColor::Yellow)
-				.label(source, "Duplicate here", dup_ident.span, ErrorColor::Red));
+				.label(source, "Duplicate here", dup_ident.span, ErrorColor::Red)
+				.code(ErrorCode::DuplicateEnumValue));
 		}
 	}
 
 	Ok(())
 }
 
-fn find_struct_field_duplicates(source: Rc<CodeSource>, fields: &[ParsedStructField]) -> Result<(), OlympusError> {
+fn find_struct_field_duplicates(source: Arc<CodeSource>, fields: &[ParsedStructField]) -> Result<(), OlympusError> {
 	if let Some((original, dup)) = find_duplicate_ident(&fields.iter().map(|v| v.ident.clone()).collect::<Vec<_>>()) {
 		return Err(OlympusError::new("Duplicate field ident found")
 			.label(source.clone(), "Original here", original.span, ErrorColor::Yellow)
-			.label(source, "Duplicate here", dup.span, ErrorColor::Red));
-	}
-
-	Ok(())
-}
-
-fn find_rpc_procedure_duplicates(source: Rc<CodeSource>, procs: &[ParsedProcedure]) -> Result<(), OlympusError> {
-	let mut idents = HashMap::<String, (Spanned<String>, Option<Spanned<String>>)>::new();
-
-	for proc in procs {
-		if let Some((_, duplicated)) = idents.get_mut(&proc.ident.value) {
-			*duplicated = Some(proc.ident.clone());
-		} else {
-			idents.insert(proc.ident.value.clone(), (proc.ident.clone(), None));
-		}
-	}
-
-	for (_, (original_ident, dup_ident)) in idents {
-		if let Some(dup_ident) = dup_ident {
-			return Err(OlympusError::new("Duplicate proc ident found")
-				.label(source.clone(), "Original here", original_ident.span, ErrorColor::Yellow)
-				.label(source, "Duplicate here", dup_ident.span, ErrorColor::Red));
-		}
+			.label(source, "Duplicate here", dup.span, ErrorColor::Red)
+			.code(ErrorCode::DuplicateStructField));
 	}
 
 	Ok(())
 }
 
 fn find_rpc_procedure_param_duplicates(
-	source: Rc<CodeSource>,
+	source: Arc<CodeSource>,
 	params: &[ParsedProcedureParam],
 ) -> Result<(), OlympusError> {
 	if let Some((original, dup)) = find_duplicate_ident(&params.iter().map(|v| v.ident.clone()).collect::<Vec<_>>()) {
 		return Err(OlympusError::new("Duplicate proc param ident found")
 			.label(source.clone(), "Original here", original.span, ErrorColor::Yellow)
-			.label(source, "Duplicate here", dup.span, ErrorColor::Red));
+			.label(source, "Duplicate here", dup.span, ErrorColor::Red)
+			.code(ErrorCode::DuplicateProcParam));
 	}
 
 	Ok(())
 }
 
-fn check_accessible_type(
-	source: Rc<CodeSource>,
-	accessible_types: &[Spanned<String>],
-	asking_for: &Spanned<ParsedTypeKind>,
+#[derive(Clone, Copy)]
+enum Declaration {
+	Enum(EnumId),
+	Struct(StructId),
+}
+
+struct Scope<'a> {
+	files: &'a [SchemaFile],
+	declarations: HashMap<&'a str, (Declaration, usize)>,
+}
+
+impl Scope<'_> {
+	fn resolve(&self, file: usize, kind: &Spanned<ParsedTypeKind>) -> Result<ResolvedType, OlympusError> {
+		let source = &self.files[file].parser.source;
+		let resolved = match &kind.value {
+			ParsedTypeKind::Builtin(builtin) => match builtin {
+				ParsedBultin::Nothing => {
+					return Err(
+						OlympusError::error(source.clone(), "Expected a type", kind.span.clone())
+							.code(ErrorCode::ExpectedType),
+					)
+				}
+				ParsedBultin::Int(int) => ResolvedType::Int(int.into()),
+				ParsedBultin::VariableInt(int) => ResolvedType::VariableInt(int.into()),
+				ParsedBultin::String => ResolvedType::String,
+				ParsedBultin::Array(inner) => ResolvedType::Array(Box::new(self.resolve(file, inner)?)),
+				ParsedBultin::Option(inner) => ResolvedType::Option(Box::new(self.resolve(file, inner)?)),
+			},
+			ParsedTypeKind::External(external) => {
+				let Some((declaration, declared_in)) = self.declarations.get(external.as_str()) else {
+					return Err(OlympusError::error(
+						source.clone(),
+						&format!("Type '{external}' not found"),
+						kind.span.clone(),
+					)
+					.code(ErrorCode::TypeNotFound));
+				};
+
+				if *declared_in != file && !self.files[file].imports.contains(declared_in) {
+					let declared_source = &self.files[*declared_in].parser.source;
+					let import = declared_source
+						.file_name
+						.strip_suffix(".ol")
+						.unwrap_or(&declared_source.file_name);
+					return Err(OlympusError::new(&format!("Type '{external}' not found"))
+						.label(
+							source.clone(),
+							&format!(
+								"'{external}' is declared in '{}', add `import {import};` to use it",
+								declared_source.file_name
+							),
+							kind.span.clone(),
+							ErrorColor::Red,
+						)
+						.code(ErrorCode::TypeNotImported));
+				}
+
+				match declaration {
+					Declaration::Enum(id) => ResolvedType::Enum(*id),
+					Declaration::Struct(id) => ResolvedType::Struct {
+						id: *id,
+						indirect: false,
+					},
+				}
+			}
+		};
+
+		Ok(resolved)
+	}
+
+	fn resolve_spanned(
+		&self,
+		file: usize,
+		kind: &Spanned<ParsedTypeKind>,
+	) -> Result<Spanned<ResolvedType>, OlympusError> {
+		Ok(Spanned::new(self.resolve(file, kind)?, kind.span.clone()))
+	}
+}
+
+fn find_global_duplicates<'a>(
+	idents: impl Iterator<Item = (&'a Spanned<String>, &'a Arc<CodeSource>)>,
+	subject: &str,
+	code: ErrorCode,
 ) -> Result<(), OlympusError> {
-	if let Spanned {
-		value: ParsedTypeKind::External(external),
-		span,
-	} = asking_for
-	{
-		if !accessible_types.iter().any(|t| external == &t.value) {
-			return Err(OlympusError::error(
-				source,
-				&format!("Type '{external}' not found"),
-				span.clone(),
-			));
+	let mut seen = HashMap::<&str, (&Arc<CodeSource>, Range<usize>)>::new();
+	for (ident, source) in idents {
+		if let Some((original_source, original_span)) = seen.get(ident.value.as_str()) {
+			return Err(OlympusError::new(subject)
+				.label(
+					(*original_source).clone(),
+					"Original here",
+					original_span.clone(),
+					ErrorColor::Yellow,
+				)
+				.label(source.clone(), "Duplicate here", ident.span.clone(), ErrorColor::Red)
+				.code(code));
 		}
-	} else if let Spanned {
-		value: ParsedTypeKind::Builtin(ParsedBultin::Array(ty)),
-		..
-	} = asking_for
-	{
-		check_accessible_type(source, accessible_types, ty)?;
+		seen.insert(&ident.value, (source, ident.span.clone()));
 	}
 
 	Ok(())
 }
 
-pub fn verify_parser_outputs(
+fn mark_indirect(kind: &mut ResolvedType) {
+	match kind {
+		ResolvedType::Option(inner) => mark_indirect(inner),
+		ResolvedType::Struct { indirect, .. } => *indirect = true,
+		_ => {}
+	}
+}
+
+/// Checks a single file on its own, without looking at anything it imports.
+fn verify_parser_outputs(
 	Parser {
 		source,
 		enums: parsed_enums,
@@ -136,68 +217,249 @@ pub fn verify_parser_outputs(
 		..
 	}: &Parser,
 ) -> Result<(), OlympusError> {
-	let accessible_types = parsed_enums
-		.iter()
-		.map(|v| v.ident.clone())
-		.chain(parsed_structs.iter().map(|v| v.ident.clone()))
-		.collect::<Vec<_>>();
-
-	// checking for duplicate idents / values
-
-	if let Some((original_ident, dup_ident)) = find_duplicate_ident(&accessible_types) {
-		return Err(OlympusError::new("Duplicate enum/struct ident found")
-			.label(source.clone(), "Original here", original_ident.span, ErrorColor::Yellow)
-			.label(source.clone(), "Duplicate here", dup_ident.span, ErrorColor::Red));
-	}
-
 	for ParsedEnum { ident: _, variants } in parsed_enums {
 		find_enum_variant_duplicates(source.clone(), variants)?;
 	}
 
-	for ParsedStruct {
-		ident: struct_ident,
-		fields,
-	} in parsed_structs
-	{
+	for ParsedStruct { ident: _, fields } in parsed_structs {
 		find_struct_field_duplicates(source.clone(), fields)?;
-
-		for field in fields {
-			if let Spanned {
-				value: ParsedTypeKind::External(external),
-				span,
-			} = &field.kind
-			{
-				if &struct_ident.value == external {
-					return Err(OlympusError::error(
-						source.clone(),
-						"Self referencing field type",
-						span.clone(),
-					));
-				}
-			}
-		}
 	}
 
-	find_rpc_procedure_duplicates(source.clone(), procedures)?;
 	for proc in procedures {
 		find_rpc_procedure_param_duplicates(source.clone(), &proc.params)?;
 	}
 
-	// checking that types are actually there
+	Ok(())
+}
+
+/// Verifies every file and resolves their declarations into a single schema.
+///
+/// The first file is treated as the root, every other file should be reachable through its imports.
+pub fn verify(files: &[SchemaFile]) -> Result<ResolvedSchema, OlympusError> {
+	for file in files {
+		verify_parser_outputs(&file.parser)?;
+	}
 
-	for ParsedStruct { ident: _, fields } in parsed_structs {
-		for field in fields {
-			check_accessible_type(source.clone(), &accessible_types, &field.kind)?;
+	// checking for duplicates across every file
+
+	let type_idents = files.iter().flat_map(|file| {
+		let source = &file.parser.source;
+		file.parser
+			.enums
+			.iter()
+			.map(move |v| (&v.ident, source))
+			.chain(file.parser.structs.iter().map(move |v| (&v.ident, source)))
+	});
+	find_global_duplicates(
+		type_idents,
+		"Duplicate enum/struct ident found",
+		ErrorCode::DuplicateType,
+	)?;
+
+	let proc_idents = files
+		.iter()
+		.flat_map(|file| file.parser.procedures.iter().map(|v| (&v.ident, &file.parser.source)));
+	find_global_duplicates(proc_idents, "Duplicate proc ident found", ErrorCode::DuplicateProc)?;
+
+	// binding every type reference to its declaration
+
+	let mut declarations = HashMap::new();
+	let (mut enum_count, mut struct_count) = (0, 0);
+	for (file_idx, file) in files.iter().enumerate() {
+		for r#enum in &file.parser.enums {
+			declarations.insert(
+				r#enum.ident.value.as_str(),
+				(Declaration::Enum(EnumId(enum_count)), file_idx),
+			);
+			enum_count += 1;
+		}
+
+		for r#struct in &file.parser.structs {
+			declarations.insert(
+				r#struct.ident.value.as_str(),
+				(Declaration::Struct(StructId(struct_count)), file_idx),
+			);
+			struct_count += 1;
 		}
 	}
 
-	for proc in procedures {
-		for param in &proc.params {
-			check_accessible_type(source.clone(), &accessible_types, &param.kind)?;
+	let scope = Scope { files, declarations };
+	let mut schema = ResolvedSchema::default();
+	for (file_idx, SchemaFile { parser, .. }) in files.iter().enumerate() {
+		for r#enum in &parser.enums {
+			schema.enums.push(ResolvedEnum {
+				ident: r#enum.ident.clone(),
+				source: parser.source.clone(),
+				variants: r#enum
+					.variants
+					.iter()
+					.map(|variant| ResolvedEnumVariant {
+						ident: variant.ident.clone(),
+						value: variant.value.unsigned_abs(),
+					})
+					.collect(),
+			});
+		}
+
+		for r#struct in &parser.structs {
+			let fields = r#struct
+				.fields
+				.iter()
+				.map(|field| {
+					Ok(ResolvedStructField {
+						ident: field.ident.clone(),
+						kind: scope.resolve_spanned(file_idx, &field.kind)?,
+					})
+				})
+				.collect::<Result<_, OlympusError>>()?;
+
+			schema.structs.push(ResolvedStruct {
+				ident: r#struct.ident.clone(),
+				source: parser.source.clone(),
+				fields,
+			});
 		}
 
-		check_accessible_type(source.clone(), &accessible_types, &proc.return_kind)?;
+		for proc in &parser.procedures {
+			let params = proc
+				.params
+				.iter()
+				.map(|param| {
+					Ok(ResolvedProcedureParam {
+						ident: param.ident.clone(),
+						kind: scope.resolve_spanned(file_idx, &param.kind)?,
+					})
+				})
+				.collect::<Result<_, OlympusError>>()?;
+
+			let return_kind = match &proc.return_kind.value {
+				ParsedTypeKind::Builtin(ParsedBultin::Nothing) => None,
+				_ => Some(scope.resolve_spanned(file_idx, &proc.return_kind)?),
+			};
+
+			schema.procedures.push(ResolvedProcedure {
+				ident: proc.ident.clone(),
+				source: parser.source.clone(),
+				params,
+				return_kind,
+			});
+		}
 	}
 
-	Ok(())
+	// checking that structs don't contain themselves without indirection
+
+	let graph = TypeGraph::new(&schema.structs);
+	if let Some(cycle) = graph.find_unguarded_cycle() {
+		return Err(graph::cycle_error(&schema, &cycle));
+	}
+
+	for (id, field) in graph.indirect_fields() {
+		mark_indirect(&mut schema.structs[id.0].fields[field].kind.value);
+	}
+
+	Ok(schema)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use olympus_lexer::Lexer;
+	use olympus_parser::Parser;
+	use olympus_spanned::{CodeSource, OlympusError};
+
+	use crate::{lint, verify, Lint, LintConfig, LintLevel, ResolvedSchema, ResolvedType, SchemaFile};
+
+	fn parse(src: &str) -> Result<Vec<SchemaFile>, OlympusError> {
+		let mut lexer = Lexer::new(Arc::new(CodeSource {
+			file_name: "test.ol".to_string(),
+			src: src.to_string(),
+		}));
+		lexer.lex()?;
+		let mut parser = Parser::new(lexer);
+		parser.parse()?;
+		Ok(vec![SchemaFile {
+			parser,
+			imports: Vec::new(),
+		}])
+	}
+
+	fn verify_src(src: &str) -> Result<ResolvedSchema, OlympusError> {
+		verify(&parse(src)?)
+	}
+
+	fn lint_src(src: &str, config: &LintConfig) -> Vec<(Option<&'static str>, bool)> {
+		let files = parse(src).unwrap();
+		let schema = verify(&files).unwrap();
+		lint(&files, &schema, config)
+			.into_iter()
+			.map(|diagnostic| (diagnostic.lint, diagnostic.is_error()))
+			.collect()
+	}
+
+	fn is_indirect(schema: &ResolvedSchema, ident: &str, field: &str) -> bool {
+		let r#struct = schema.structs.iter().find(|s| s.ident.value == ident).unwrap();
+		let field = r#struct.fields.iter().find(|f| f.ident.value == field).unwrap();
+
+		let mut indirect = false;
+		field.kind.value.walk(&mut |ty| {
+			if let ResolvedType::Struct { indirect: true, .. } = ty {
+				indirect = true;
+			}
+		});
+		indirect
+	}
+
+	#[test]
+	fn reports_direct_cycles() {
+		let err = verify_src("struct A { b->B; } struct B { c->C; } struct C { a->A; }").unwrap_err();
+		assert_eq!(err.labels.len(), 3);
+		assert!(err.subject.ends_with("A -> B -> C -> A"));
+
+		assert!(verify_src("struct Node { next->Node; }").is_err());
+	}
+
+	#[test]
+	fn marks_guarded_cycles_indirect() {
+		let schema = verify_src("struct Node { next->@option[Node]; children->@array[Node]; }").unwrap();
+		assert!(is_indirect(&schema, "Node", "next"));
+		assert!(!is_indirect(&schema, "Node", "children"));
+	}
+
+	#[test]
+	fn only_marks_fields_inside_cycles_indirect() {
+		let schema =
+			verify_src("struct A { b->@option[B]; } struct B { a->@option[A]; c->@option[C]; } struct C { x->@int8; }")
+				.unwrap();
+		assert!(is_indirect(&schema, "A", "b"));
+		assert!(is_indirect(&schema, "B", "a"));
+		assert!(!is_indirect(&schema, "B", "c"));
+	}
+
+	#[test]
+	fn resolves_nested_generics() {
+		assert!(verify_src("struct A { b->@option[DoesNotExist]; }").is_err());
+		assert!(verify_src("struct A { b->@array[@option[DoesNotExist]]; }").is_err());
+		assert!(verify_src("proc Get() -> @option[DoesNotExist];").is_err());
+	}
+
+	#[test]
+	fn lints_respect_levels() {
+		let src = "enum Empty {} struct unused { x->@int8; } proc Get() -> Empty;";
+
+		let mut config = LintConfig::default();
+		let diagnostics = lint_src(src, &config);
+		assert!(diagnostics.contains(&(Some("empty_enum"), false)));
+		assert!(diagnostics.contains(&(Some("unused_type"), false)));
+		assert!(diagnostics.contains(&(Some("non_pascal_case_name"), false)));
+		assert!(!diagnostics.iter().any(|(_, is_error)| *is_error));
+
+		config.set(Lint::UnusedType, LintLevel::Deny);
+		config.set(Lint::NonPascalCaseName, LintLevel::Allow);
+		let diagnostics = lint_src(src, &config);
+		assert!(diagnostics.contains(&(Some("unused_type"), true)));
+		assert!(!diagnostics
+			.iter()
+			.any(|(lint, _)| *lint == Some("non_pascal_case_name")));
+	}
 }
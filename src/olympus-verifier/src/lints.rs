@@ -0,0 +1,309 @@
+use std::{collections::HashMap, collections::HashSet, sync::Arc};
+
+use heck::AsPascalCase;
+use olympus_spanned::{CodeSource, ErrorCode, ErrorColor, OlympusError, Severity, Spanned};
+
+use crate::{EnumId, ResolvedSchema, ResolvedType, SchemaFile, StructId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+	EmptyEnum,
+	UnusedType,
+	UnusedImport,
+	NonPascalCaseName,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintLevel {
+	Allow,
+	Warn,
+	Deny,
+}
+
+impl Lint {
+	pub const ALL: &'static [Lint] = &[
+		Lint::EmptyEnum,
+		Lint::UnusedType,
+		Lint::UnusedImport,
+		Lint::NonPascalCaseName,
+	];
+
+	#[must_use]
+	pub fn name(self) -> &'static str {
+		match self {
+			Lint::EmptyEnum => "empty_enum",
+			Lint::UnusedType => "unused_type",
+			Lint::UnusedImport => "unused_import",
+			Lint::NonPascalCaseName => "non_pascal_case_name",
+		}
+	}
+
+	#[must_use]
+	pub fn code(self) -> ErrorCode {
+		match self {
+			Lint::EmptyEnum => ErrorCode::EmptyEnum,
+			Lint::UnusedType => ErrorCode::UnusedType,
+			Lint::UnusedImport => ErrorCode::UnusedImport,
+			Lint::NonPascalCaseName => ErrorCode::NonPascalCaseName,
+		}
+	}
+
+	#[must_use]
+	pub fn description(self) -> &'static str {
+		match self {
+			Lint::EmptyEnum => "enums without any variants",
+			Lint::UnusedType => "enums and structs in the root file that nothing refers to",
+			Lint::UnusedImport => "imports that none of the file's declarations use",
+			Lint::NonPascalCaseName => "enum, struct, variant and proc names that aren't PascalCase",
+		}
+	}
+
+	#[must_use]
+	pub fn default_level(self) -> LintLevel {
+		LintLevel::Warn
+	}
+
+	#[must_use]
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.iter().copied().find(|lint| lint.name() == name)
+	}
+
+	#[must_use]
+	pub fn from_code(code: ErrorCode) -> Option<Self> {
+		Self::ALL.iter().copied().find(|lint| lint.code() == code)
+	}
+}
+
+/// Which level every lint runs at, lints that haven't been configured use their default level.
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+	levels: HashMap<Lint, LintLevel>,
+}
+
+impl LintConfig {
+	pub fn set(&mut self, lint: Lint, level: LintLevel) {
+		self.levels.insert(lint, level);
+	}
+
+	pub fn set_all(&mut self, level: LintLevel) {
+		for lint in Lint::ALL {
+			self.set(*lint, level);
+		}
+	}
+
+	#[must_use]
+	pub fn level(&self, lint: Lint) -> LintLevel {
+		self.levels.get(&lint).copied().unwrap_or(lint.default_level())
+	}
+}
+
+struct Linter<'a> {
+	config: &'a LintConfig,
+	diagnostics: Vec<OlympusError>,
+}
+
+impl Linter<'_> {
+	fn emit(&mut self, lint: Lint, source: &Arc<CodeSource>, subject: &str, message: &str, span: &Spanned<String>) {
+		let severity = match self.config.level(lint) {
+			LintLevel::Allow => return,
+			LintLevel::Warn => Severity::Warning,
+			LintLevel::Deny => Severity::Error,
+		};
+
+		let color = match severity {
+			Severity::Error => ErrorColor::Red,
+			Severity::Warning => ErrorColor::Yellow,
+		};
+
+		self.diagnostics.push(
+			OlympusError::new(subject)
+				.label(source.clone(), message, span.span.clone(), color)
+				.severity(severity)
+				.code(lint.code())
+				.lint(lint.name()),
+		);
+	}
+}
+
+#[must_use]
+fn is_pascal_case(ident: &str) -> bool {
+	ident.starts_with(|c: char| c.is_ascii_uppercase()) && !ident.contains('_')
+}
+
+fn file_of(files: &[SchemaFile], source: &Arc<CodeSource>) -> usize {
+	files
+		.iter()
+		.position(|file| Arc::ptr_eq(&file.parser.source, source))
+		.unwrap_or(0)
+}
+
+/// Runs every lint that isn't allowed over a verified schema and returns the diagnostics they produced.
+///
+/// `unused_type` only looks at declarations in the root file, so shared files don't warn about types that only some
+/// of their importers use.
+#[must_use]
+pub fn lint(files: &[SchemaFile], schema: &ResolvedSchema, config: &LintConfig) -> Vec<OlympusError> {
+	lint_roots(files, schema, config, &[0])
+}
+
+/// [`lint`] for a schema verified from several root files, `unused_type` looks at the declarations in each of them.
+#[must_use]
+pub fn lint_roots(
+	files: &[SchemaFile],
+	schema: &ResolvedSchema,
+	config: &LintConfig,
+	roots: &[usize],
+) -> Vec<OlympusError> {
+	let mut linter = Linter {
+		config,
+		diagnostics: Vec::new(),
+	};
+
+	for r#enum in &schema.enums {
+		if r#enum.variants.is_empty() {
+			linter.emit(
+				Lint::EmptyEnum,
+				&r#enum.source,
+				&format!("Enum '{}' has no variants", r#enum.ident.value),
+				"Declared here",
+				&r#enum.ident,
+			);
+		}
+	}
+
+	// naming
+
+	let names = schema
+		.enums
+		.iter()
+		.flat_map(|r#enum| {
+			std::iter::once((&r#enum.ident, &r#enum.source, "Enum")).chain(
+				r#enum
+					.variants
+					.iter()
+					.map(|v| (&v.ident, &r#enum.source, "Enum variant")),
+			)
+		})
+		.chain(
+			schema
+				.structs
+				.iter()
+				.map(|r#struct| (&r#struct.ident, &r#struct.source, "Struct")),
+		)
+		.chain(schema.procedures.iter().map(|proc| (&proc.ident, &proc.source, "Proc")));
+	for (ident, source, kind) in names {
+		if !is_pascal_case(&ident.value) {
+			linter.emit(
+				Lint::NonPascalCaseName,
+				source,
+				&format!("{kind} '{}' should be PascalCase", ident.value),
+				&format!("Consider renaming it to '{}'", AsPascalCase(&ident.value)),
+				ident,
+			);
+		}
+	}
+
+	// usage
+
+	let mut used_files = vec![HashSet::<usize>::new(); files.len()];
+	walk_references(schema, |from, _, ty| {
+		let to = match ty {
+			ResolvedType::Enum(id) => &schema[*id].source,
+			ResolvedType::Struct { id, .. } => &schema[*id].source,
+			_ => return,
+		};
+		used_files[file_of(files, from)].insert(file_of(files, to));
+	});
+
+	unused_types(&mut linter, schema, |source| roots.contains(&file_of(files, source)));
+
+	for (idx, file) in files.iter().enumerate() {
+		for (import, target) in file.parser.imports.iter().zip(&file.imports) {
+			if !used_files[idx].contains(target) {
+				linter.emit(
+					Lint::UnusedImport,
+					&file.parser.source,
+					&format!("Imported file '{}' is never used", import.value),
+					"Imported here",
+					import,
+				);
+			}
+		}
+	}
+
+	linter.diagnostics
+}
+
+/// Only runs `unused_type`, for a schema whose files aren't at hand anymore, like one put together with
+/// [`ResolvedSchema::merge`]. The declarations in the files named `roots` have to be used.
+#[must_use]
+pub fn lint_unused_types(schema: &ResolvedSchema, config: &LintConfig, roots: &[&str]) -> Vec<OlympusError> {
+	let mut linter = Linter {
+		config,
+		diagnostics: Vec::new(),
+	};
+	unused_types(&mut linter, schema, |source| roots.contains(&source.file_name.as_str()));
+	linter.diagnostics
+}
+
+/// Calls `f` with the file, the struct if any and every type referenced by a field, parameter or return type.
+fn walk_references(schema: &ResolvedSchema, mut f: impl FnMut(&Arc<CodeSource>, Option<StructId>, &ResolvedType)) {
+	for (idx, r#struct) in schema.structs.iter().enumerate() {
+		for field in &r#struct.fields {
+			field
+				.kind
+				.value
+				.walk(&mut |ty| f(&r#struct.source, Some(StructId(idx)), ty));
+		}
+	}
+
+	for proc in &schema.procedures {
+		let kinds = proc
+			.params
+			.iter()
+			.map(|param| &param.kind)
+			.chain(proc.return_kind.as_ref());
+		for kind in kinds {
+			kind.value.walk(&mut |ty| f(&proc.source, None, ty));
+		}
+	}
+}
+
+/// Emits `unused_type` for the declarations in files `in_root` accepts that nothing but themselves uses.
+fn unused_types(linter: &mut Linter, schema: &ResolvedSchema, in_root: impl Fn(&Arc<CodeSource>) -> bool) {
+	let mut used_enums = HashSet::<EnumId>::new();
+	let mut used_structs = HashSet::<StructId>::new();
+	walk_references(schema, |_, from_struct, ty| match ty {
+		ResolvedType::Enum(id) => {
+			used_enums.insert(*id);
+		}
+		ResolvedType::Struct { id, .. } if from_struct != Some(*id) => {
+			used_structs.insert(*id);
+		}
+		_ => {}
+	});
+
+	for (idx, r#enum) in schema.enums.iter().enumerate() {
+		if in_root(&r#enum.source) && !used_enums.contains(&EnumId(idx)) {
+			linter.emit(
+				Lint::UnusedType,
+				&r#enum.source,
+				&format!("Enum '{}' is never used", r#enum.ident.value),
+				"Declared here",
+				&r#enum.ident,
+			);
+		}
+	}
+
+	for (idx, r#struct) in schema.structs.iter().enumerate() {
+		if in_root(&r#struct.source) && !used_structs.contains(&StructId(idx)) {
+			linter.emit(
+				Lint::UnusedType,
+				&r#struct.source,
+				&format!("Struct '{}' is never used", r#struct.ident.value),
+				"Declared here",
+				&r#struct.ident,
+			);
+		}
+	}
+}